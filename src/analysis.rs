@@ -0,0 +1,310 @@
+// ============================================================================
+// ANALYSIS MODULE: Comparing Coin Systems
+// ============================================================================
+// How good is a set of denominations for making change? analyze() pays
+// every amount in a range and reports:
+//
+//   - the average and worst-case number of coins, paying optimally
+//   - whether the greedy rule (largest coin first) is always optimal, and
+//     the first amount where it is not
+//   - the amounts that can't be paid at all
+//
+//   analyze(&DenominationSet::us(), 1..=99).average_coins      // 4.75
+//   analyze(&DenominationSet::new([1, 3, 4])?, 1..=20)          // greedy fails at 6
+//
+// is_canonical() answers the greedy question for every amount, not just a
+// range: a system is canonical when largest-coin-first is always optimal.
+
+use std::ops::RangeInclusive;
+
+use serde::Serialize;
+
+use crate::DenominationSet;
+
+/// An amount where paying largest coin first uses more coins than needed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GreedyCounterexample {
+    pub amount: u32,
+    /// Coins the greedy rule uses, or None if it gets stuck with a
+    /// remainder no coin fits
+    pub greedy_coins: Option<u32>,
+    pub optimal_coins: u32,
+}
+
+/// How well a denomination set makes change over a range of amounts
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SystemReport {
+    pub denominations: Vec<u32>,
+    /// First amount analysed
+    pub from: u32,
+    /// Last amount analysed
+    pub to: u32,
+    /// Mean number of coins over the payable amounts, paying optimally
+    pub average_coins: f64,
+    /// Most coins any payable amount needs
+    pub worst_case_coins: u32,
+    /// The amounts that need worst_case_coins coins
+    pub worst_case_amounts: Vec<u32>,
+    /// Whether largest-coin-first is optimal for every amount in the range
+    pub greedy_optimal: bool,
+    /// The smallest amount where it is not, if any
+    pub greedy_counterexample: Option<GreedyCounterexample>,
+    /// Amounts in the range that can't be paid at all
+    pub gaps: Vec<u32>,
+}
+
+/// Fewest coins needed for every amount from 0 to `max`, None where the
+/// amount can't be paid
+pub(crate) fn optimal_coin_counts(denominations: &DenominationSet, max: u32) -> Vec<Option<u32>> {
+    let mut best: Vec<Option<u32>> = vec![None; max as usize + 1];
+    best[0] = Some(0);
+
+    for amount in 1..=max as usize {
+        best[amount] = denominations
+            .values()
+            .iter()
+            .map(|&d| d as usize)
+            .filter(|&d| d <= amount)
+            .filter_map(|d| best[amount - d])
+            .min()
+            .map(|coins| coins + 1);
+    }
+    best
+}
+
+/// Number of coins paying largest coin first, or None if that gets stuck
+pub(crate) fn greedy_coin_count(denominations: &DenominationSet, amount: u32) -> Option<u32> {
+    let mut remaining = amount;
+    let mut coins = 0;
+    for &d in denominations.values().iter().rev() {
+        coins += remaining / d;
+        remaining %= d;
+    }
+    (remaining == 0).then_some(coins)
+}
+
+/// The smallest amount where paying largest coin first is not optimal, or
+/// None if the system is canonical
+///
+/// Only amounts below the sum of the two largest denominations need
+/// checking. If x is the smallest counterexample, no optimal payment of x
+/// uses the largest coin c (otherwise x - c would be a smaller one), and if
+/// x were at least c plus the next largest, taking any coin d out of an
+/// optimal payment leaves x - d >= c, which greedy pays optimally starting
+/// with c, giving an optimal payment of x that does use c.
+pub fn greedy_counterexample(denominations: &DenominationSet) -> Option<GreedyCounterexample> {
+    let values = denominations.values();
+    if values.len() < 2 {
+        return None;
+    }
+    let limit = values[values.len() - 1].saturating_add(values[values.len() - 2]) - 1;
+    let best = optimal_coin_counts(denominations, limit);
+
+    (1..=limit).find_map(|amount| {
+        let optimal_coins = best[amount as usize]?;
+        let greedy_coins = greedy_coin_count(denominations, amount);
+        (greedy_coins != Some(optimal_coins)).then_some(GreedyCounterexample {
+            amount,
+            greedy_coins,
+            optimal_coins,
+        })
+    })
+}
+
+/// Whether paying largest coin first uses the fewest coins for every amount
+/// that can be paid at all
+pub fn is_canonical(denominations: &DenominationSet) -> bool {
+    greedy_counterexample(denominations).is_none()
+}
+
+/// Pays every amount in `amounts` and summarises the results
+pub fn analyze(denominations: &DenominationSet, amounts: RangeInclusive<u32>) -> SystemReport {
+    let (from, to) = (*amounts.start(), *amounts.end());
+    let best = optimal_coin_counts(denominations, to);
+
+    let mut total_coins = 0u64;
+    let mut payable = 0u64;
+    let mut worst_case_coins = 0;
+    let mut worst_case_amounts = Vec::new();
+    let mut greedy_counterexample = None;
+    let mut gaps = Vec::new();
+
+    for amount in amounts {
+        let Some(optimal_coins) = best[amount as usize] else {
+            gaps.push(amount);
+            continue;
+        };
+
+        total_coins += u64::from(optimal_coins);
+        payable += 1;
+
+        if optimal_coins > worst_case_coins {
+            worst_case_coins = optimal_coins;
+            worst_case_amounts.clear();
+        }
+        if optimal_coins == worst_case_coins {
+            worst_case_amounts.push(amount);
+        }
+
+        let greedy_coins = greedy_coin_count(denominations, amount);
+        if greedy_counterexample.is_none() && greedy_coins != Some(optimal_coins) {
+            greedy_counterexample = Some(GreedyCounterexample {
+                amount,
+                greedy_coins,
+                optimal_coins,
+            });
+        }
+    }
+
+    SystemReport {
+        denominations: denominations.values().to_vec(),
+        from,
+        to,
+        average_coins: if payable == 0 {
+            0.0
+        } else {
+            total_coins as f64 / payable as f64
+        },
+        worst_case_coins,
+        worst_case_amounts,
+        greedy_optimal: greedy_counterexample.is_none(),
+        greedy_counterexample,
+        gaps,
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(values: &[u32]) -> DenominationSet {
+        DenominationSet::new(values.iter().copied()).unwrap()
+    }
+
+    // ========================================================================
+    // Coin System Tests
+    // ========================================================================
+
+    #[test]
+    fn test_us_coins() {
+        let report = analyze(&DenominationSet::us(), 1..=99);
+        assert_eq!(report.average_coins, 470.0 / 99.0);
+        assert_eq!(report.worst_case_coins, 9);
+        assert_eq!(report.worst_case_amounts, vec![94, 99]);
+        assert!(report.greedy_optimal);
+        assert!(report.gaps.is_empty());
+    }
+
+    #[test]
+    fn test_euro_coins() {
+        let euro = set(&[1, 2, 5, 10, 20, 50, 100, 200]);
+        let report = analyze(&euro, 1..=499);
+        assert_eq!(report.average_coins, 2300.0 / 499.0);
+        assert_eq!(report.worst_case_coins, 8);
+        assert!(report.greedy_optimal);
+    }
+
+    #[test]
+    fn test_euro_beats_us_on_average() {
+        let us = analyze(&DenominationSet::us(), 1..=99);
+        let euro = analyze(&set(&[1, 2, 5, 10, 20, 50]), 1..=99);
+        assert!(euro.average_coins < us.average_coins);
+    }
+
+    #[test]
+    fn test_greedy_failure() {
+        let report = analyze(&set(&[1, 3, 4]), 1..=20);
+        assert!(!report.greedy_optimal);
+        assert_eq!(
+            report.greedy_counterexample,
+            Some(GreedyCounterexample {
+                amount: 6,
+                greedy_coins: Some(3),
+                optimal_coins: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_greedy_gets_stuck() {
+        // Greedy pays 6 as 4 and is left with 2, but 3 + 3 works
+        let report = analyze(&set(&[3, 4]), 1..=10);
+        let counterexample = report.greedy_counterexample.unwrap();
+        assert_eq!(counterexample.amount, 6);
+        assert_eq!(counterexample.greedy_coins, None);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let report = analyze(&set(&[5, 10, 25]), 1..=30);
+        assert_eq!(report.gaps.len(), 24);
+        assert_eq!(report.gaps[..5], [1, 2, 3, 4, 6]);
+        assert_eq!(report.worst_case_amounts, vec![15, 20, 30]);
+        assert_eq!(report.average_coins, 1.5);
+    }
+
+    #[test]
+    fn test_range_with_nothing_payable() {
+        let report = analyze(&set(&[50]), 1..=10);
+        assert_eq!(report.gaps.len(), 10);
+        assert_eq!(report.average_coins, 0.0);
+        assert!(report.worst_case_amounts.is_empty());
+    }
+
+    // ========================================================================
+    // Canonical System Tests
+    // ========================================================================
+
+    #[test]
+    fn test_canonical_systems() {
+        assert!(is_canonical(&DenominationSet::us()));
+        assert!(is_canonical(&set(&[1, 2, 5, 10, 20, 50, 100, 200])));
+        assert!(is_canonical(&set(&[1])));
+        assert!(is_canonical(&set(&[7])));
+        // Greedy never gets stuck on multiples of 5 here
+        assert!(is_canonical(&set(&[5, 10, 25])));
+    }
+
+    #[test]
+    fn test_non_canonical_systems() {
+        let counterexample = greedy_counterexample(&set(&[1, 3, 4])).unwrap();
+        assert_eq!(counterexample.amount, 6);
+        assert!(!is_canonical(&set(&[1, 3, 4])));
+
+        // Pre-decimal British coins: 48 pence is a florin pair, greedy
+        // takes a half crown first
+        let counterexample = greedy_counterexample(&set(&[1, 3, 6, 12, 24, 30])).unwrap();
+        assert_eq!(counterexample.amount, 48);
+        assert_eq!(counterexample.greedy_coins, Some(3));
+        assert_eq!(counterexample.optimal_coins, 2);
+    }
+
+    #[test]
+    fn test_stuck_greedy_is_not_canonical() {
+        let counterexample = greedy_counterexample(&set(&[3, 4])).unwrap();
+        assert_eq!(counterexample.amount, 6);
+        assert_eq!(counterexample.greedy_coins, None);
+    }
+
+    #[test]
+    fn test_matches_exhaustive_check() {
+        // The bound is exact: compare with analyze() over a much wider range
+        for values in [[1, 4, 5], [2, 5, 7], [1, 5, 12], [3, 7, 8], [1, 10, 25]] {
+            let system = set(&values);
+            let wide = analyze(&system, 1..=500).greedy_counterexample;
+            assert_eq!(greedy_counterexample(&system), wide, "{values:?}");
+        }
+    }
+
+    #[test]
+    fn test_report_serializes() {
+        let report = analyze(&DenominationSet::us(), 1..=4);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["denominations"], serde_json::json!([1, 5, 10, 25]));
+        assert_eq!(json["greedy_counterexample"], serde_json::Value::Null);
+    }
+}
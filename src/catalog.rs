@@ -0,0 +1,343 @@
+// ============================================================================
+// CATALOG MODULE: Numismatic Coin Catalog
+// ============================================================================
+// Models what collectors care about beyond face value:
+// - series: the design a coin was struck with (e.g. "Buffalo" nickels)
+// - years and mint marks: where and when a coin was struck
+// - condition grades on the 1-70 Sheldon scale
+//
+// The built-in catalog covers the circulating US series for each Coin.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt;
+use ts_rs::TS;
+
+use crate::Coin;
+
+/// A US Mint facility, identified on coins by its mint mark
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "MintMark.d.ts")]
+pub enum MintMark {
+    Philadelphia,
+    Denver,
+    SanFrancisco,
+    WestPoint,
+    NewOrleans,
+    CarsonCity,
+}
+
+impl MintMark {
+    /// The letter(s) stamped on the coin
+    ///
+    /// Philadelphia coins carried no mark for most of their history;
+    /// "P" is used for them regardless.
+    pub fn letter(&self) -> &'static str {
+        match self {
+            MintMark::Philadelphia => "P",
+            MintMark::Denver => "D",
+            MintMark::SanFrancisco => "S",
+            MintMark::WestPoint => "W",
+            MintMark::NewOrleans => "O",
+            MintMark::CarsonCity => "CC",
+        }
+    }
+}
+
+/// A coin design and the years and mints it was struck at
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "Series.d.ts")]
+pub struct Series {
+    pub coin: Coin,
+    pub name: &'static str,
+    pub first_year: u16,
+    /// None while the series is still being minted
+    pub last_year: Option<u16>,
+    pub mint_marks: &'static [MintMark],
+}
+
+impl Series {
+    /// Whether the series was struck in the given year
+    pub fn minted_in(&self, year: u16) -> bool {
+        year >= self.first_year && self.last_year.is_none_or(|last| year <= last)
+    }
+}
+
+/// A specific year and mint of a series, e.g. 1916-D Mercury dime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Variant {
+    pub coin: Coin,
+    pub series: &'static str,
+    pub year: u16,
+    pub mint_mark: MintMark,
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{} {} {:?}",
+            self.year,
+            self.mint_mark.letter(),
+            self.series,
+            self.coin
+        )
+    }
+}
+
+/// Condition grade on the 1-70 Sheldon scale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Grade(u8);
+
+impl Grade {
+    /// Creates a grade, returning None outside 1..=70
+    pub fn new(sheldon: u8) -> Option<Grade> {
+        (1..=70).contains(&sheldon).then_some(Grade(sheldon))
+    }
+
+    /// The numeric Sheldon grade
+    pub fn sheldon(&self) -> u8 {
+        self.0
+    }
+
+    /// Standard abbreviation for the grade band, e.g. "VF" for 20-35
+    pub fn abbreviation(&self) -> &'static str {
+        match self.0 {
+            1 => "PO",
+            2 => "FR",
+            3 => "AG",
+            4..=7 => "G",
+            8..=11 => "VG",
+            12..=19 => "F",
+            20..=39 => "VF",
+            40..=49 => "XF",
+            50..=59 => "AU",
+            _ => "MS",
+        }
+    }
+
+    /// Whether the coin shows no wear from circulation (MS-60 and up)
+    pub fn is_uncirculated(&self) -> bool {
+        self.0 >= 60
+    }
+}
+
+// Displays the conventional label, e.g. "VF-20" or "MS-65"
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.abbreviation(), self.0)
+    }
+}
+
+/// A collection of coin series that can be searched by coin and year
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    series: Vec<Series>,
+    /// Last year included when expanding still-minted series into variants
+    as_of_year: u16,
+}
+
+use MintMark::{CarsonCity, Denver, NewOrleans, Philadelphia, SanFrancisco, WestPoint};
+
+const PDS: &[MintMark] = &[Philadelphia, Denver, SanFrancisco];
+const PDSW: &[MintMark] = &[Philadelphia, Denver, SanFrancisco, WestPoint];
+const BARBER_MINTS: &[MintMark] = &[Philadelphia, NewOrleans, SanFrancisco, Denver];
+
+impl Catalog {
+    /// Year up to which the built-in catalog is complete
+    pub const BUILT_IN_YEAR: u16 = 2025;
+
+    /// Builds a catalog from a list of series
+    pub fn new(series: Vec<Series>, as_of_year: u16) -> Self {
+        Catalog { series, as_of_year }
+    }
+
+    /// The built-in catalog of circulating US series
+    pub fn us() -> Self {
+        let series = |coin, name, first_year, last_year, mint_marks| Series {
+            coin,
+            name,
+            first_year,
+            last_year,
+            mint_marks,
+        };
+
+        Catalog::new(
+            vec![
+                series(
+                    Coin::Penny,
+                    "Indian Head",
+                    1859,
+                    Some(1909),
+                    &[Philadelphia, SanFrancisco],
+                ),
+                series(Coin::Penny, "Lincoln Wheat", 1909, Some(1958), PDS),
+                series(Coin::Penny, "Lincoln Memorial", 1959, Some(2008), PDS),
+                series(Coin::Penny, "Lincoln Bicentennial", 2009, Some(2009), PDS),
+                series(Coin::Penny, "Lincoln Shield", 2010, None, PDS),
+                series(Coin::Nickel, "Liberty Head", 1883, Some(1912), PDS),
+                series(Coin::Nickel, "Buffalo", 1913, Some(1938), PDS),
+                series(Coin::Nickel, "Jefferson", 1938, None, PDS),
+                series(Coin::Dime, "Barber", 1892, Some(1916), BARBER_MINTS),
+                series(Coin::Dime, "Mercury", 1916, Some(1945), PDS),
+                series(Coin::Dime, "Roosevelt", 1946, None, PDSW),
+                series(
+                    Coin::Quarter,
+                    "Seated Liberty",
+                    1838,
+                    Some(1891),
+                    &[Philadelphia, NewOrleans, SanFrancisco, CarsonCity],
+                ),
+                series(Coin::Quarter, "Barber", 1892, Some(1916), BARBER_MINTS),
+                series(Coin::Quarter, "Standing Liberty", 1916, Some(1930), PDS),
+                series(Coin::Quarter, "Washington", 1932, Some(1998), PDS),
+                series(Coin::Quarter, "50 State", 1999, Some(2008), PDS),
+                series(
+                    Coin::Quarter,
+                    "DC and U.S. Territories",
+                    2009,
+                    Some(2009),
+                    PDS,
+                ),
+                series(
+                    Coin::Quarter,
+                    "America the Beautiful",
+                    2010,
+                    Some(2021),
+                    PDSW,
+                ),
+                series(
+                    Coin::Quarter,
+                    "Crossing the Delaware",
+                    2021,
+                    Some(2021),
+                    PDS,
+                ),
+                series(Coin::Quarter, "American Women", 2022, Some(2025), PDS),
+            ],
+            Catalog::BUILT_IN_YEAR,
+        )
+    }
+
+    /// Every series of the given coin, oldest first
+    pub fn series_of(&self, coin: Coin) -> Vec<&Series> {
+        let mut series: Vec<&Series> = self.series.iter().filter(|s| s.coin == coin).collect();
+        series.sort_by_key(|s| s.first_year);
+        series
+    }
+
+    /// Every year and mint mark combination of the given coin
+    ///
+    /// Variants are expanded from each series' years and mints, so a few
+    /// pairings that were never actually struck are included.
+    pub fn variants_of(&self, coin: Coin) -> Vec<Variant> {
+        self.series_of(coin)
+            .into_iter()
+            .flat_map(|series| {
+                let last = series.last_year.unwrap_or(self.as_of_year);
+                (series.first_year..=last).flat_map(move |year| {
+                    series.mint_marks.iter().map(move |&mint_mark| Variant {
+                        coin,
+                        series: series.name,
+                        year,
+                        mint_mark,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// The series (possibly several, in transition years) of a coin
+    /// struck in the given year
+    pub fn series_in_year(&self, coin: Coin, year: u16) -> Vec<&Series> {
+        self.series_of(coin)
+            .into_iter()
+            .filter(|series| series.minted_in(year))
+            .collect()
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_coin_has_series() {
+        let catalog = Catalog::us();
+        for coin in Coin::iter() {
+            assert!(!catalog.series_of(coin).is_empty(), "{:?}", coin);
+        }
+    }
+
+    #[test]
+    fn test_series_of_is_ordered_by_year() {
+        let catalog = Catalog::us();
+        let names: Vec<&str> = catalog
+            .series_of(Coin::Nickel)
+            .iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["Liberty Head", "Buffalo", "Jefferson"]);
+    }
+
+    #[test]
+    fn test_series_in_transition_year() {
+        let catalog = Catalog::us();
+        let names: Vec<&str> = catalog
+            .series_in_year(Coin::Dime, 1916)
+            .iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["Barber", "Mercury"]);
+        assert!(catalog.series_in_year(Coin::Quarter, 1931).is_empty());
+    }
+
+    #[test]
+    fn test_variants_of() {
+        let catalog = Catalog::us();
+        let variants = catalog.variants_of(Coin::Quarter);
+
+        let delaware: Vec<&Variant> = variants
+            .iter()
+            .filter(|v| v.series == "Crossing the Delaware")
+            .collect();
+        assert_eq!(delaware.len(), 3);
+
+        let mercury_1916_d = catalog
+            .variants_of(Coin::Dime)
+            .into_iter()
+            .find(|v| v.series == "Mercury" && v.year == 1916 && v.mint_mark == MintMark::Denver)
+            .unwrap();
+        assert_eq!(mercury_1916_d.to_string(), "1916-D Mercury Dime");
+    }
+
+    #[test]
+    fn test_current_series_expand_to_catalog_year() {
+        let catalog = Catalog::us();
+        let latest = catalog
+            .variants_of(Coin::Nickel)
+            .iter()
+            .map(|v| v.year)
+            .max();
+        assert_eq!(latest, Some(Catalog::BUILT_IN_YEAR));
+    }
+
+    #[test]
+    fn test_grades() {
+        assert_eq!(Grade::new(0), None);
+        assert_eq!(Grade::new(71), None);
+
+        let fine = Grade::new(12).unwrap();
+        assert_eq!(fine.to_string(), "F-12");
+        assert!(!fine.is_uncirculated());
+
+        let gem = Grade::new(65).unwrap();
+        assert_eq!(gem.to_string(), "MS-65");
+        assert!(gem.is_uncirculated());
+        assert!(gem > fine);
+    }
+}
@@ -0,0 +1,783 @@
+// ============================================================================
+// CHANGE MODULE: Paying an Amount in Coins
+// ============================================================================
+// make_change() finds the fewest coins worth exactly an amount. It uses
+// dynamic programming rather than "largest coin first", so it stays correct
+// for coin systems where the greedy rule is not optimal (see
+// DenominationSet::make_change()).
+//
+// The table only has to cover small amounts: an optimal payment never holds
+// `largest` or more of any smaller coin, since `largest` coins of value d
+// could be swapped for d coins of the largest value. So the smaller coins
+// are worth at most (largest - 1) * (sum of the smaller values), and
+// anything above that is paid with the largest coin up front.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::{greedy_counterexample, Coin, DenominationSet, Purse};
+
+/// Error returned when an amount can't be paid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeError {
+    /// No combination of the denominations adds up to the amount
+    Unpayable { amount: u64 },
+    /// The coins available are worth less than the amount in total
+    Insufficient { amount: u64, available: u64 },
+    /// There is enough money, but no selection of the available coins adds
+    /// up to exactly the amount; `closest_below` is the most that can be
+    /// paid without going over
+    NoExactChange { amount: u64, closest_below: u64 },
+    /// Largest-coin-first was asked for, but the system is not canonical;
+    /// `counterexample` is the smallest amount it pays badly
+    NotCanonical { counterexample: u32 },
+}
+
+impl ChangeError {
+    /// How far short of the amount the best possible payment falls
+    pub fn shortfall(&self) -> u64 {
+        match *self {
+            ChangeError::Unpayable { .. } | ChangeError::NotCanonical { .. } => 0,
+            ChangeError::Insufficient { amount, available } => amount - available,
+            ChangeError::NoExactChange {
+                amount,
+                closest_below,
+            } => amount - closest_below,
+        }
+    }
+}
+
+impl fmt::Display for ChangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeError::Unpayable { amount } => {
+                write!(f, "{} can't be paid with these denominations", amount)
+            }
+            ChangeError::Insufficient { amount, available } => write!(
+                f,
+                "need {} but only {} is available (short by {})",
+                amount,
+                available,
+                self.shortfall()
+            ),
+            ChangeError::NoExactChange {
+                amount,
+                closest_below,
+            } => write!(
+                f,
+                "can't make exactly {} from the available coins; the closest is {} (short by {})",
+                amount,
+                closest_below,
+                self.shortfall()
+            ),
+            ChangeError::NotCanonical { counterexample } => write!(
+                f,
+                "largest coin first is not optimal for these denominations (e.g. {})",
+                counterexample
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChangeError {}
+
+impl DenominationSet {
+    /// The fewest coins worth exactly `amount`, smallest first
+    pub fn make_change(&self, amount: u64) -> Result<Vec<u32>, ChangeError> {
+        let values = self.values();
+        let largest = *values.last().expect("a set is never empty");
+        let bound: u64 = u64::from(largest - 1)
+            * values[..values.len() - 1]
+                .iter()
+                .map(|&d| u64::from(d))
+                .sum::<u64>();
+
+        // Pay everything above the bound with the largest coin
+        let upfront = amount.saturating_sub(bound) / u64::from(largest);
+        let rest = amount - upfront * u64::from(largest);
+
+        // last[a]: the coin added last in a best payment of a
+        let size = rest as usize + 1;
+        let mut coins_needed: Vec<Option<u32>> = vec![None; size];
+        let mut last = vec![0u32; size];
+        coins_needed[0] = Some(0);
+        for a in 1..size {
+            for &d in values.iter().rev() {
+                let d_usize = d as usize;
+                if d_usize > a {
+                    continue;
+                }
+                if let Some(n) = coins_needed[a - d_usize] {
+                    if coins_needed[a].is_none_or(|best| n + 1 < best) {
+                        coins_needed[a] = Some(n + 1);
+                        last[a] = d;
+                    }
+                }
+            }
+        }
+
+        if coins_needed[rest as usize].is_none() {
+            return Err(ChangeError::Unpayable { amount });
+        }
+
+        let mut change = Vec::new();
+        let mut a = rest as usize;
+        while a > 0 {
+            change.push(last[a]);
+            a -= last[a] as usize;
+        }
+        change.extend(std::iter::repeat_n(largest, upfront as usize));
+        change.sort_unstable();
+        Ok(change)
+    }
+
+    /// Whether largest coin first is always optimal, see is_canonical()
+    pub fn is_canonical(&self) -> bool {
+        greedy_counterexample(self).is_none()
+    }
+
+    /// Pays `amount` largest coin first, smallest first in the result
+    ///
+    /// Faster than make_change() but only correct for canonical systems, so
+    /// refuses with ChangeError::NotCanonical on any other. Checking costs
+    /// a table as large as the two largest denominations together; callers
+    /// paying many amounts should check is_canonical() once and then choose.
+    pub fn make_change_greedy(&self, amount: u64) -> Result<Vec<u32>, ChangeError> {
+        if let Some(counterexample) = greedy_counterexample(self) {
+            return Err(ChangeError::NotCanonical {
+                counterexample: counterexample.amount,
+            });
+        }
+
+        let mut change = Vec::new();
+        let mut remaining = amount;
+        for &d in self.values().iter().rev() {
+            let count = remaining / u64::from(d);
+            change.extend(std::iter::repeat_n(d, count as usize));
+            remaining -= count * u64::from(d);
+        }
+        if remaining != 0 {
+            return Err(ChangeError::Unpayable { amount });
+        }
+        change.reverse();
+        Ok(change)
+    }
+}
+
+// ============================================================================
+// Every way to pay
+// ============================================================================
+
+/// Lazy iterator over every distinct way to pay an amount, see
+/// DenominationSet::change_ways()
+#[derive(Debug, Clone)]
+pub struct ChangeWays {
+    amount: u64,
+    // Denominations largest first, and how many of each the current
+    // candidate uses
+    values: Vec<u64>,
+    counts: Vec<u64>,
+    done: bool,
+}
+
+impl ChangeWays {
+    fn new(values: &[u32], amount: u64) -> Self {
+        let mut ways = ChangeWays {
+            amount,
+            values: values.iter().rev().map(|&d| u64::from(d)).collect(),
+            counts: vec![0; values.len()],
+            done: false,
+        };
+        ways.fill(0);
+        ways
+    }
+
+    // Takes as many coins as fit at every level from `level` down
+    fn fill(&mut self, level: usize) {
+        let mut remaining = self.remaining_before(level);
+        for i in level..self.values.len() {
+            self.counts[i] = remaining / self.values[i];
+            remaining -= self.counts[i] * self.values[i];
+        }
+    }
+
+    fn remaining_before(&self, level: usize) -> u64 {
+        self.amount
+            - self.values[..level]
+                .iter()
+                .zip(&self.counts)
+                .map(|(&v, &c)| v * c)
+                .sum::<u64>()
+    }
+
+    fn is_exact(&self) -> bool {
+        let last = self.values.len() - 1;
+        self.remaining_before(last) == self.counts[last] * self.values[last]
+    }
+
+    // Gives up one coin at the deepest level that has one to spare (never
+    // the smallest coin, which always takes whatever is left) and refills
+    // the levels below it
+    fn advance(&mut self) {
+        let last = self.values.len() - 1;
+        match (0..last).rev().find(|&i| self.counts[i] > 0) {
+            Some(level) => {
+                self.counts[level] -= 1;
+                self.fill(level + 1);
+            }
+            None => self.done = true,
+        }
+    }
+}
+
+impl Iterator for ChangeWays {
+    type Item = Vec<u32>;
+
+    fn next(&mut self) -> Option<Vec<u32>> {
+        while !self.done {
+            let exact = self.is_exact();
+            let way: Vec<u32> = self
+                .values
+                .iter()
+                .zip(&self.counts)
+                .rev()
+                // Values came from u32 denominations
+                .flat_map(|(&value, &count)| std::iter::repeat_n(value as u32, count as usize))
+                .collect();
+            self.advance();
+            if exact {
+                return Some(way);
+            }
+        }
+        None
+    }
+}
+
+impl std::iter::FusedIterator for ChangeWays {}
+
+impl DenominationSet {
+    /// Every distinct way to pay `amount` with unlimited coins, each way
+    /// listed smallest coin first
+    ///
+    /// Ways come out starting from the one with the most large coins (the
+    /// greedy payment), trading large coins for smaller ones as it goes.
+    /// Nothing is computed ahead, so this works for amounts with far too
+    /// many ways to collect; count_ways() says how many there are.
+    pub fn change_ways(&self, amount: u64) -> ChangeWays {
+        ChangeWays::new(self.values(), amount)
+    }
+}
+
+/// Every distinct way to pay `amount` cents in US coins, see
+/// make_change_iter() for the lazy version
+pub fn make_change_all(amount: u64) -> Vec<Vec<Coin>> {
+    make_change_iter(amount).collect()
+}
+
+/// Lazily yields every distinct way to pay `amount` cents in US coins,
+/// starting from the fewest coins, each lowest value first
+pub fn make_change_iter(amount: u64) -> impl Iterator<Item = Vec<Coin>> {
+    DenominationSet::us()
+        .change_ways(amount)
+        .map(|way| way.into_iter().map(coin_from_value).collect())
+}
+
+pub(crate) fn coin_from_value(cents: u32) -> Coin {
+    Coin::from_cents(u64::from(cents)).expect("a US coin value")
+}
+
+// ============================================================================
+// Paying from a purse
+// ============================================================================
+
+/// The fewest coins from `purse` worth exactly `amount` cents
+///
+/// Only coins the purse actually holds are used. The result is the coins
+/// to take out; the purse itself is left untouched. Fails with
+/// ChangeError::Insufficient if the purse is worth less than the amount, or
+/// ChangeError::NoExactChange if no selection adds up exactly (say, 10 cents
+/// from a purse of quarters).
+///
+/// Builds a table of (coin types x amount) entries, so memory grows with
+/// the amount; the purse's total caps it, since larger amounts fail first.
+pub fn make_change_from(purse: &Purse, amount: u64) -> Result<Purse, ChangeError> {
+    let available = purse.total_value().cents();
+    if available < amount {
+        return Err(ChangeError::Insufficient { amount, available });
+    }
+
+    let coins: Vec<(Coin, u32)> = Coin::iter()
+        .map(|coin| (coin, purse.count_of(coin)))
+        .filter(|&(_, count)| count > 0)
+        .collect();
+    let size = amount as usize + 1;
+
+    // best[i][a]: fewest coins worth a using only the first i coin types
+    let mut best: Vec<Vec<Option<u32>>> = vec![vec![None; size]; coins.len() + 1];
+    best[0][0] = Some(0);
+    for (i, &(coin, count)) in coins.iter().enumerate() {
+        let value = coin.value_in_cents() as usize;
+        best[i + 1] = bounded_step(&best[i], value, count);
+    }
+
+    let table = best.last().expect("at least the empty row");
+    if table[amount as usize].is_none() {
+        let closest_below = (0..=amount)
+            .rev()
+            .find(|&a| table[a as usize].is_some())
+            .expect("zero is always payable");
+        return Err(ChangeError::NoExactChange {
+            amount,
+            closest_below,
+        });
+    }
+
+    // Walk back through the table to find how many of each coin were used
+    let mut change = Purse::new();
+    let mut a = amount as usize;
+    for (i, &(coin, count)) in coins.iter().enumerate().rev() {
+        let value = coin.value_in_cents() as usize;
+        let target = best[i + 1][a].expect("reachable");
+        let used = (0..=count.min((a / value) as u32))
+            .find(|&k| best[i][a - k as usize * value].map(|n| n + k) == Some(target))
+            .expect("some count reproduces the table");
+        change.set_count(coin, used);
+        a -= used as usize * value;
+    }
+    Ok(change)
+}
+
+// Adds one coin type (`count` coins of `value`) to a row of fewest-coin
+// counts. For each amount a this is the minimum over k <= count of
+// previous[a - k * value] + k, computed per residue class with a sliding
+// window minimum so it stays linear in the number of amounts.
+fn bounded_step(previous: &[Option<u32>], value: usize, count: u32) -> Vec<Option<u32>> {
+    let mut next = vec![None; previous.len()];
+
+    for residue in 0..value.min(previous.len()) {
+        // Candidates (j, previous[residue + j * value] - j), increasing key
+        let mut window: VecDeque<(usize, i64)> = VecDeque::new();
+        for (j, a) in (residue..previous.len()).step_by(value).enumerate() {
+            if let Some(n) = previous[a] {
+                let key = i64::from(n) - j as i64;
+                while window.back().is_some_and(|&(_, k)| k >= key) {
+                    window.pop_back();
+                }
+                window.push_back((j, key));
+            }
+            while window
+                .front()
+                .is_some_and(|&(start, _)| j - start > count as usize)
+            {
+                window.pop_front();
+            }
+            next[a] = window.front().map(|&(_, key)| (key + j as i64) as u32);
+        }
+    }
+    next
+}
+
+/// The fewest US coins worth exactly `amount` cents, lowest value first
+///
+/// Every amount can be paid (there is a penny), but the Result leaves room
+/// for coin systems and inventories where that is not true.
+pub fn make_change(amount: u64) -> Result<Vec<Coin>, ChangeError> {
+    let values = DenominationSet::us().make_change(amount)?;
+    Ok(values.into_iter().map(coin_from_value).collect())
+}
+
+// ============================================================================
+// Splitting an amount
+// ============================================================================
+
+/// Divides `amount` cents between `people` as evenly as possible, returning
+/// the coins for each share
+///
+/// Shares differ by at most a cent. The `amount % people` cents left over
+/// go one each to the first shares, so $1.00 between three is 34¢, 33¢ and
+/// 33¢, in that order. Each share is paid with the fewest coins.
+///
+/// Panics if `people` is zero, or if a share needs more than u32::MAX of
+/// one coin (more than about $1 billion in quarters).
+pub fn split(amount: u64, people: usize) -> Vec<Purse> {
+    assert!(people > 0, "cannot split an amount between zero people");
+    let people = people as u64;
+    let (share, remainder) = (amount / people, amount % people);
+    (0..people)
+        .map(|i| fewest_coins(share + u64::from(i < remainder)))
+        .collect()
+}
+
+/// The fewest US coins worth `cents`, counted straight into a purse rather
+/// than listed one by one like make_change(); greedy is optimal for US coins
+fn fewest_coins(mut cents: u64) -> Purse {
+    let mut purse = Purse::new();
+    for coin in Coin::iter().rev() {
+        let value = coin.value_in_cents();
+        let count = u32::try_from(cents / value).expect("Purse coin count overflowed");
+        purse.add(coin, count);
+        cents %= value;
+    }
+    purse
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::optimal_coin_counts;
+    use crate::{count_ways_in_coins, total_value, BigUint};
+
+    fn set(values: &[u32]) -> DenominationSet {
+        DenominationSet::new(values.iter().copied()).unwrap()
+    }
+
+    // ========================================================================
+    // US Coin Tests
+    // ========================================================================
+
+    #[test]
+    fn test_make_change_examples() {
+        assert_eq!(make_change(0).unwrap(), vec![]);
+        assert_eq!(
+            make_change(41).unwrap(),
+            vec![Coin::Penny, Coin::Nickel, Coin::Dime, Coin::Quarter]
+        );
+        assert_eq!(make_change(30).unwrap(), vec![Coin::Nickel, Coin::Quarter]);
+        assert_eq!(make_change(99).unwrap().len(), 9);
+    }
+
+    #[test]
+    fn test_make_change_totals_match() {
+        for amount in 0..=500 {
+            let change = make_change(amount).unwrap();
+            assert_eq!(total_value(&change), amount);
+        }
+    }
+
+    #[test]
+    fn test_make_change_large_amount() {
+        let change = make_change(1_000_003).unwrap();
+        assert_eq!(change.len(), 40_000 + 3);
+        assert_eq!(total_value(&change), 1_000_003);
+    }
+
+    // ========================================================================
+    // Custom Denomination Tests
+    // ========================================================================
+
+    #[test]
+    fn test_beats_greedy_on_non_canonical_system() {
+        // Greedy pays 6 as 4 + 1 + 1
+        assert_eq!(set(&[1, 3, 4]).make_change(6).unwrap(), vec![3, 3]);
+    }
+
+    #[test]
+    fn test_finds_payment_greedy_misses() {
+        // Greedy takes 4 and is stuck with 2
+        assert_eq!(set(&[3, 4]).make_change(6).unwrap(), vec![3, 3]);
+    }
+
+    #[test]
+    fn test_greedy_matches_make_change_on_canonical_systems() {
+        for system in [DenominationSet::us(), set(&[1, 2, 5, 10, 20, 50])] {
+            assert!(system.is_canonical());
+            for amount in 0..=150 {
+                assert_eq!(
+                    system.make_change_greedy(amount),
+                    system.make_change(amount),
+                    "amount {amount}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_greedy_refuses_non_canonical_system() {
+        let system = set(&[1, 3, 4]);
+        assert!(!system.is_canonical());
+        let error = system.make_change_greedy(8).unwrap_err();
+        assert_eq!(error, ChangeError::NotCanonical { counterexample: 6 });
+        assert_eq!(
+            error.to_string(),
+            "largest coin first is not optimal for these denominations (e.g. 6)"
+        );
+    }
+
+    #[test]
+    fn test_greedy_unpayable_amount() {
+        assert_eq!(
+            set(&[5, 10]).make_change_greedy(12),
+            Err(ChangeError::Unpayable { amount: 12 })
+        );
+    }
+
+    #[test]
+    fn test_unpayable_amount() {
+        let error = set(&[5, 10]).make_change(12).unwrap_err();
+        assert_eq!(error, ChangeError::Unpayable { amount: 12 });
+        assert_eq!(
+            error.to_string(),
+            "12 can't be paid with these denominations"
+        );
+    }
+
+    #[test]
+    fn test_is_optimal() {
+        let systems = [
+            set(&[1, 3, 4]),
+            set(&[1, 7, 10]),
+            set(&[2, 5, 9]),
+            set(&[1, 2, 5, 10, 20, 50]),
+        ];
+        for system in &systems {
+            let best = optimal_coin_counts(system, 300);
+            for amount in 0..=300u32 {
+                let change = system.make_change(u64::from(amount));
+                match best[amount as usize] {
+                    Some(count) => {
+                        let change = change.unwrap();
+                        assert_eq!(change.len() as u32, count, "{system:?} {amount}");
+                        assert_eq!(change.iter().sum::<u32>(), amount);
+                    }
+                    None => assert!(change.is_err()),
+                }
+            }
+        }
+    }
+
+    // ========================================================================
+    // Every Way to Pay Tests
+    // ========================================================================
+
+    #[test]
+    fn test_make_change_all_for_a_dime() {
+        assert_eq!(
+            make_change_all(10),
+            vec![
+                vec![Coin::Dime],
+                vec![Coin::Nickel, Coin::Nickel],
+                vec![
+                    Coin::Penny,
+                    Coin::Penny,
+                    Coin::Penny,
+                    Coin::Penny,
+                    Coin::Penny,
+                    Coin::Nickel
+                ],
+                vec![Coin::Penny; 10],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_make_change_all_counts_match_count_ways() {
+        for amount in [0, 1, 25, 99, 100, 137] {
+            let ways = make_change_all(amount);
+            assert_eq!(BigUint::from(ways.len()), count_ways_in_coins(amount));
+            assert!(ways.iter().all(|way| total_value(way) == amount));
+        }
+        assert_eq!(make_change_all(100).len(), 242);
+    }
+
+    #[test]
+    fn test_make_change_all_is_distinct() {
+        let ways = make_change_all(60);
+        let unique: std::collections::HashSet<&Vec<Coin>> = ways.iter().collect();
+        assert_eq!(unique.len(), ways.len());
+    }
+
+    #[test]
+    fn test_first_way_is_fewest_coins() {
+        let first = make_change_iter(68).next().unwrap();
+        assert_eq!(first, make_change(68).unwrap());
+    }
+
+    #[test]
+    fn test_iterator_handles_huge_amounts_lazily() {
+        // Far too many ways to collect; only the first few are computed
+        let first: Vec<Vec<Coin>> = make_change_iter(10_000_000).take(3).collect();
+        assert_eq!(first.len(), 3);
+        assert_eq!(first[0].len(), 400_000);
+    }
+
+    #[test]
+    fn test_change_ways_without_a_unit_coin() {
+        let ways: Vec<Vec<u32>> = set(&[3, 5]).change_ways(15).collect();
+        assert_eq!(ways, vec![vec![5, 5, 5], vec![3, 3, 3, 3, 3]]);
+        assert_eq!(set(&[3, 5]).change_ways(7).count(), 0);
+    }
+
+    // ========================================================================
+    // Paying From a Purse Tests
+    // ========================================================================
+
+    fn purse(counts: &[(Coin, u32)]) -> Purse {
+        counts.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_make_change_from_uses_available_coins() {
+        // No quarters, so 30 cents takes three dimes
+        let drawer = purse(&[(Coin::Penny, 10), (Coin::Dime, 5)]);
+        let change = make_change_from(&drawer, 30).unwrap();
+        assert_eq!(change, purse(&[(Coin::Dime, 3)]));
+    }
+
+    #[test]
+    fn test_make_change_from_respects_counts() {
+        // Only one dime: 30 cents is a dime and 20 pennies, not three dimes
+        let drawer = purse(&[(Coin::Penny, 50), (Coin::Dime, 1)]);
+        let change = make_change_from(&drawer, 30).unwrap();
+        assert_eq!(change, purse(&[(Coin::Penny, 20), (Coin::Dime, 1)]));
+    }
+
+    #[test]
+    fn test_make_change_from_prefers_fewest_coins() {
+        let drawer = purse(&[
+            (Coin::Penny, 100),
+            (Coin::Nickel, 20),
+            (Coin::Dime, 20),
+            (Coin::Quarter, 4),
+        ]);
+        let change = make_change_from(&drawer, 68).unwrap();
+        assert_eq!(
+            change,
+            purse(&[
+                (Coin::Penny, 3),
+                (Coin::Nickel, 1),
+                (Coin::Dime, 1),
+                (Coin::Quarter, 2)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_make_change_from_matches_unlimited_when_stocked() {
+        let drawer: Purse = Coin::iter().map(|coin| (coin, 100)).collect();
+        for amount in 0..=200 {
+            let from_drawer = make_change_from(&drawer, amount).unwrap();
+            let unlimited: Purse = make_change(amount)
+                .unwrap()
+                .into_iter()
+                .map(|coin| (coin, 1))
+                .collect();
+            assert_eq!(from_drawer, unlimited, "amount {amount}");
+        }
+    }
+
+    #[test]
+    fn test_make_change_from_insufficient_funds() {
+        let drawer = purse(&[(Coin::Quarter, 1)]);
+        let error = make_change_from(&drawer, 40).unwrap_err();
+        assert_eq!(
+            error,
+            ChangeError::Insufficient {
+                amount: 40,
+                available: 25
+            }
+        );
+        assert_eq!(error.shortfall(), 15);
+        assert_eq!(
+            error.to_string(),
+            "need 40 but only 25 is available (short by 15)"
+        );
+    }
+
+    #[test]
+    fn test_make_change_from_no_exact_change() {
+        let drawer = purse(&[(Coin::Nickel, 1), (Coin::Quarter, 2)]);
+        let error = make_change_from(&drawer, 12).unwrap_err();
+        assert_eq!(
+            error,
+            ChangeError::NoExactChange {
+                amount: 12,
+                closest_below: 5
+            }
+        );
+        assert_eq!(error.shortfall(), 7);
+    }
+
+    #[test]
+    fn test_make_change_from_zero_and_empty() {
+        assert_eq!(make_change_from(&Purse::new(), 0).unwrap(), Purse::new());
+        assert!(make_change_from(&Purse::new(), 1).is_err());
+    }
+
+    #[test]
+    fn test_large_amount_on_non_canonical_system() {
+        // Above the bound the largest coin is paid up front, but the
+        // remainder is still solved exactly
+        let change = set(&[1, 3, 4]).make_change(1_000_002).unwrap();
+        assert_eq!(change.iter().sum::<u32>(), 1_000_002);
+        assert_eq!(change.len(), 250_000 + 1);
+    }
+
+    // ========================================================================
+    // Splitting Tests
+    // ========================================================================
+
+    #[test]
+    fn test_split_remainder_goes_to_first_shares() {
+        let shares = split(100, 3);
+        let values: Vec<u64> = shares.iter().map(|s| s.total_value().cents()).collect();
+        assert_eq!(values, vec![34, 33, 33]);
+        assert_eq!(
+            shares[0],
+            purse(&[(Coin::Penny, 4), (Coin::Nickel, 1), (Coin::Quarter, 1)])
+        );
+        assert_eq!(
+            shares[1],
+            purse(&[(Coin::Penny, 3), (Coin::Nickel, 1), (Coin::Quarter, 1)])
+        );
+    }
+
+    #[test]
+    fn test_split_is_fair_and_complete() {
+        for amount in [0, 1, 7, 99, 250, 1_001] {
+            for people in 1..=7 {
+                let shares = split(amount, people);
+                assert_eq!(shares.len(), people);
+                let values: Vec<u64> = shares.iter().map(|s| s.total_value().cents()).collect();
+                assert_eq!(values.iter().sum::<u64>(), amount);
+                let (max, min) = (values.iter().max().unwrap(), values.iter().min().unwrap());
+                assert!(max - min <= 1, "{amount} between {people}: {values:?}");
+                assert!(values.windows(2).all(|w| w[0] >= w[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_less_than_a_cent_each() {
+        let shares = split(2, 4);
+        assert_eq!(shares[0], purse(&[(Coin::Penny, 1)]));
+        assert_eq!(shares[1], purse(&[(Coin::Penny, 1)]));
+        assert!(shares[2].is_empty() && shares[3].is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "zero people")]
+    fn test_split_between_nobody_panics() {
+        split(100, 0);
+    }
+
+    #[test]
+    fn test_split_large_amount_without_listing_coins() {
+        let cents = u64::from(u32::MAX) * 25 + 24;
+        let shares = split(cents, 1);
+        assert_eq!(
+            shares[0],
+            purse(&[(Coin::Penny, 4), (Coin::Dime, 2), (Coin::Quarter, u32::MAX)])
+        );
+        assert_eq!(split(cents, 2)[0].count_of(Coin::Quarter), u32::MAX / 2 + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed")]
+    fn test_split_share_too_large_for_a_purse_panics() {
+        split(u64::MAX, 1);
+    }
+}
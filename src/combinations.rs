@@ -0,0 +1,1081 @@
+// ============================================================================
+// COMBINATIONS MODULE: Lazy Subset Enumeration
+// ============================================================================
+// generate_all_combinations() builds every subset up front. combinations()
+// yields the same subsets, in the same order, one at a time:
+//
+//   for combination in combinations() { ... }           // stream
+//   combinations().len()                                // 16, no allocation
+//   combinations().rev().next()                         // all four coins
+//   combinations().filter(|c| total_value(c) > 25)     // adapters
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::{BitAnd, BitOr, Deref, Sub};
+use std::sync::OnceLock;
+use ts_rs::TS;
+
+use crate::{combination_from_bits, Coin, Money};
+
+// ============================================================================
+// Combination: a list of coins stored inline
+// ============================================================================
+
+/// A list of coins, as produced by the enumeration and search functions
+///
+/// Up to 8 coins live inline, so building one does not touch the heap;
+/// longer handfuls (e.g. from generate_combinations_with_counts) spill over
+/// transparently. It derefs to `[Coin]`, so slice methods and functions
+/// taking `&[Coin]` work unchanged, and it serializes as a plain array.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    TS,
+)]
+#[serde(transparent)]
+#[ts(export, export_to = "Combination.d.ts")]
+pub struct Combination(
+    #[schemars(with = "Vec<Coin>")]
+    #[ts(as = "Vec<Coin>")]
+    SmallVec<[Coin; 8]>,
+);
+
+impl Combination {
+    /// An empty combination
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a coin at the end
+    pub fn push(&mut self, coin: Coin) {
+        self.0.push(coin);
+    }
+
+    /// The coins as a slice
+    pub fn as_slice(&self) -> &[Coin] {
+        &self.0
+    }
+
+    /// Converts into a Vec, allocating only if the coins are still inline
+    pub fn into_vec(self) -> Vec<Coin> {
+        self.0.into_vec()
+    }
+
+    /// The set of coins present, with duplicates collapsed
+    pub fn mask(&self) -> CombinationMask {
+        CombinationMask::from(self.as_slice())
+    }
+
+    /// The same coins in canonical order: lowest value first, duplicates
+    /// kept
+    ///
+    /// Two combinations hold the same coins exactly when their canonical
+    /// forms are equal.
+    pub fn canonical(&self) -> Combination {
+        let mut coins = self.clone();
+        coins.0.sort_unstable();
+        coins
+    }
+
+    /// A stable ID that depends only on which coins are held, not on their
+    /// order, suitable for storing in a database
+    ///
+    /// A combination without duplicates gets its mask, so the IDs 0..=15
+    /// match combination_index(). One with duplicates gets a 64-bit FNV-1a
+    /// hash of its per-coin counts with the top bit set, so it can never
+    /// clash with a mask. The hash is fixed by this definition and does not
+    /// change between platforms or releases.
+    pub fn id(&self) -> u64 {
+        let counts: Vec<u64> = Coin::iter()
+            .map(|coin| self.iter().filter(|&&c| c == coin).count() as u64)
+            .collect();
+
+        if counts.iter().all(|&count| count <= 1) {
+            return u64::from(self.mask().bits());
+        }
+
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+        let hash = counts
+            .iter()
+            .flat_map(|count| count.to_le_bytes())
+            .fold(FNV_OFFSET, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+            });
+        hash | 1 << 63
+    }
+
+    // The set operations below treat both sides as sets: duplicates collapse
+    // and the result lists each coin once, lowest value first
+
+    /// Coins in either combination
+    pub fn union(&self, other: &Combination) -> Combination {
+        self.mask().union(other.mask()).into()
+    }
+
+    /// Coins in both combinations
+    pub fn intersection(&self, other: &Combination) -> Combination {
+        self.mask().intersection(other.mask()).into()
+    }
+
+    /// Coins in this combination but not in `other`
+    pub fn difference(&self, other: &Combination) -> Combination {
+        self.mask().difference(other.mask()).into()
+    }
+
+    /// Whether every coin here also appears in `other`
+    pub fn is_subset_of(&self, other: &Combination) -> bool {
+        self.mask().is_subset_of(other.mask())
+    }
+}
+
+impl Deref for Combination {
+    type Target = [Coin];
+
+    fn deref(&self) -> &[Coin] {
+        &self.0
+    }
+}
+
+impl AsRef<[Coin]> for Combination {
+    fn as_ref(&self) -> &[Coin] {
+        &self.0
+    }
+}
+
+impl From<&[Coin]> for Combination {
+    fn from(coins: &[Coin]) -> Self {
+        Combination(SmallVec::from_slice(coins))
+    }
+}
+
+impl From<Vec<Coin>> for Combination {
+    fn from(coins: Vec<Coin>) -> Self {
+        Combination(SmallVec::from_vec(coins))
+    }
+}
+
+impl From<Combination> for Vec<Coin> {
+    fn from(combination: Combination) -> Self {
+        combination.into_vec()
+    }
+}
+
+impl FromIterator<Coin> for Combination {
+    fn from_iter<I: IntoIterator<Item = Coin>>(iter: I) -> Self {
+        Combination(iter.into_iter().collect())
+    }
+}
+
+impl Extend<Coin> for Combination {
+    fn extend<I: IntoIterator<Item = Coin>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl IntoIterator for Combination {
+    type Item = Coin;
+    type IntoIter = smallvec::IntoIter<[Coin; 8]>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Combination {
+    type Item = &'a Coin;
+    type IntoIter = std::slice::Iter<'a, Coin>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+// Lets combinations be compared against plain coin lists, e.g. in tests
+impl PartialEq<Vec<Coin>> for Combination {
+    fn eq(&self, other: &Vec<Coin>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl PartialEq<[Coin]> for Combination {
+    fn eq(&self, other: &[Coin]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<const N: usize> PartialEq<[Coin; N]> for Combination {
+    fn eq(&self, other: &[Coin; N]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl PartialEq<Combination> for Vec<Coin> {
+    fn eq(&self, other: &Combination) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+// ============================================================================
+// CombinationMask: a combination packed into bits
+// ============================================================================
+
+/// A combination stored as a bitmask: bit j set means the j-th coin of
+/// Coin::iter() is present
+///
+/// The mask is the same number as the combination's index in
+/// generate_all_combinations(), so it doubles as a stable compact ID.
+/// Being Copy and heap-free, it is cheap to pass around in hot loops.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    TS,
+)]
+// Deserialized through from_bits(), so bits beyond FULL are rejected
+#[serde(try_from = "u16", into = "u16")]
+#[ts(export, export_to = "CombinationMask.d.ts")]
+pub struct CombinationMask(u16);
+
+impl CombinationMask {
+    /// The empty combination
+    pub const EMPTY: CombinationMask = CombinationMask(0);
+
+    /// Every coin present
+    pub const FULL: CombinationMask = CombinationMask((1 << Coin::COUNT) - 1);
+
+    /// Wraps raw bits, returning None if a bit beyond the last coin is set
+    pub fn from_bits(bits: u16) -> Option<CombinationMask> {
+        (bits & !Self::FULL.0 == 0).then_some(CombinationMask(bits))
+    }
+
+    /// The raw bits
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Whether `coin` is part of the combination
+    pub fn contains(self, coin: Coin) -> bool {
+        self.0 & coin_bit(coin) != 0
+    }
+
+    /// Returns a copy with `coin` added
+    pub fn with(self, coin: Coin) -> CombinationMask {
+        CombinationMask(self.0 | coin_bit(coin))
+    }
+
+    /// Number of coins in the combination
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Whether the combination has no coins
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Iterates the coins in the combination in ascending value order,
+    /// without allocating
+    pub fn coins(self) -> impl Iterator<Item = Coin> {
+        Coin::iter().filter(move |&coin| self.contains(coin))
+    }
+
+    /// Total value, computed straight from the bits
+    pub fn value(self) -> Money {
+        self.coins().map(Money::from).sum()
+    }
+
+    /// Expands the mask into a list of coins
+    pub fn to_vec(self) -> Vec<Coin> {
+        self.coins().collect()
+    }
+
+    /// Coins in either combination
+    pub fn union(self, other: CombinationMask) -> CombinationMask {
+        CombinationMask(self.0 | other.0)
+    }
+
+    /// Coins in both combinations
+    pub fn intersection(self, other: CombinationMask) -> CombinationMask {
+        CombinationMask(self.0 & other.0)
+    }
+
+    /// Coins in this combination but not in `other`
+    pub fn difference(self, other: CombinationMask) -> CombinationMask {
+        CombinationMask(self.0 & !other.0)
+    }
+
+    /// Whether every coin here is also in `other`
+    pub fn is_subset_of(self, other: CombinationMask) -> bool {
+        self.0 & !other.0 == 0
+    }
+}
+
+impl BitOr for CombinationMask {
+    type Output = CombinationMask;
+
+    fn bitor(self, other: CombinationMask) -> CombinationMask {
+        self.union(other)
+    }
+}
+
+impl BitAnd for CombinationMask {
+    type Output = CombinationMask;
+
+    fn bitand(self, other: CombinationMask) -> CombinationMask {
+        self.intersection(other)
+    }
+}
+
+impl Sub for CombinationMask {
+    type Output = CombinationMask;
+
+    fn sub(self, other: CombinationMask) -> CombinationMask {
+        self.difference(other)
+    }
+}
+
+// Bit for a coin: 1 << (position of the coin in Coin::iter())
+fn coin_bit(coin: Coin) -> u16 {
+    let position = Coin::iter()
+        .position(|c| c == coin)
+        .expect("every coin is listed in Coin::iter()");
+    1 << position
+}
+
+// Duplicate coins collapse into one bit, since a combination is a set
+impl From<&[Coin]> for CombinationMask {
+    fn from(coins: &[Coin]) -> Self {
+        coins
+            .iter()
+            .fold(CombinationMask::EMPTY, |mask, &coin| mask.with(coin))
+    }
+}
+
+impl From<CombinationMask> for Vec<Coin> {
+    fn from(mask: CombinationMask) -> Self {
+        mask.to_vec()
+    }
+}
+
+impl From<CombinationMask> for Combination {
+    fn from(mask: CombinationMask) -> Self {
+        mask.coins().collect()
+    }
+}
+
+impl FromIterator<Coin> for CombinationMask {
+    fn from_iter<I: IntoIterator<Item = Coin>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(CombinationMask::EMPTY, |mask, coin| mask.with(coin))
+    }
+}
+
+/// Error returned for bits that don't belong to any coin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMaskError {
+    bits: u16,
+}
+
+impl InvalidMaskError {
+    /// The bits that were rejected
+    pub fn bits(&self) -> u16 {
+        self.bits
+    }
+}
+
+impl fmt::Display for InvalidMaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid combination mask: {} (expected 0 to {})",
+            self.bits,
+            CombinationMask::FULL.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidMaskError {}
+
+impl TryFrom<u16> for CombinationMask {
+    type Error = InvalidMaskError;
+
+    fn try_from(bits: u16) -> Result<Self, InvalidMaskError> {
+        CombinationMask::from_bits(bits).ok_or(InvalidMaskError { bits })
+    }
+}
+
+impl From<CombinationMask> for u16 {
+    fn from(mask: CombinationMask) -> Self {
+        mask.0
+    }
+}
+
+// ============================================================================
+// Ranking and unranking
+// ============================================================================
+// Map between a combination and its index in generate_all_combinations()
+// (the `index` field of /all) without generating the power set.
+
+/// Index of a combination in generate_all_combinations()
+///
+/// Order and duplicates are ignored, since a combination is a set:
+/// [Dime, Penny] and [Penny, Dime, Dime] both have index 5.
+pub fn combination_index(coins: &[Coin]) -> usize {
+    CombinationMask::from(coins).bits() as usize
+}
+
+/// The combination at `index` in generate_all_combinations(), or None if
+/// the index is out of range
+pub fn combination_at(index: usize) -> Option<Combination> {
+    u16::try_from(index)
+        .ok()
+        .and_then(CombinationMask::from_bits)
+        .map(Combination::from)
+}
+
+// ============================================================================
+// Cached table
+// ============================================================================
+
+static ALL_COMBINATIONS: OnceLock<Vec<Combination>> = OnceLock::new();
+
+/// Every combination in index order, built on first use and then shared
+///
+/// Same contents as generate_all_combinations(), without rebuilding the
+/// table on every call.
+pub fn all_combinations_cached() -> &'static [Combination] {
+    ALL_COMBINATIONS.get_or_init(crate::generate_all_combinations)
+}
+
+// ============================================================================
+// Sorted enumeration
+// ============================================================================
+
+/// Key used by generate_all_combinations_sorted()
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortBy {
+    /// Lowest total value first
+    Value,
+    /// Fewest coins first
+    CoinCount,
+}
+
+/// Every combination, ordered by total value or by number of coins
+///
+/// Ties keep their binary (index) order, e.g. with SortBy::CoinCount the
+/// single coins come out as Penny, Nickel, Dime, Quarter.
+pub fn generate_all_combinations_sorted(sort_by: SortBy) -> Vec<Combination> {
+    let mut all = all_combinations_cached().to_vec();
+    match sort_by {
+        SortBy::Value => crate::sort_by_value(&mut all),
+        SortBy::CoinCount => all.sort_by_key(|coins| coins.len()),
+    }
+    all
+}
+
+// ============================================================================
+// Streaming callback
+// ============================================================================
+
+/// Calls `f` with every combination, in index order, reusing one buffer
+///
+/// Unlike combinations(), nothing is allocated per combination, which makes
+/// this the cheapest way to sum, filter or write out every subset.
+pub fn for_each_combination(f: impl FnMut(&[Coin])) {
+    crate::powerset::for_each_subset(&Coin::all(), f).expect("four coins are within the limit");
+}
+
+// ============================================================================
+// Fixed-size combinations
+// ============================================================================
+
+/// Combinations with exactly k coins, in index order
+///
+/// There are C(4, k) of them; any k above Coin::COUNT yields nothing.
+pub fn combinations_of_size(k: usize) -> impl Iterator<Item = Combination> {
+    combinations().filter(move |coins| coins.len() == k)
+}
+
+// ============================================================================
+// Multiset combinations
+// ============================================================================
+
+/// Every handful with between 0 and max_per_coin(coin) of each coin
+///
+/// Coins come out lowest value first, like generate_all_combinations(), and
+/// the order is a counter where the penny count ticks fastest. With a limit
+/// of 1 for every coin (`|_| 1`) this is exactly the power set; in general
+/// there are as many handfuls as the product of (limit + 1) over the coins.
+///
+/// Panics if that count doesn't fit in a usize (a limit of 65535 or more
+/// for every coin on 64-bit targets).
+pub fn generate_combinations_with_counts(max_per_coin: impl Fn(Coin) -> usize) -> Vec<Combination> {
+    let radixes: Vec<usize> = Coin::iter()
+        .map(|coin| max_per_coin(coin).checked_add(1))
+        .collect::<Option<_>>()
+        .expect("too many handfuls: max_per_coin is too large");
+    let total = radixes
+        .iter()
+        .try_fold(1usize, |total, &radix| total.checked_mul(radix))
+        .expect("too many handfuls: max_per_coin is too large");
+
+    (0..total)
+        .map(|index| {
+            let mut rest = index;
+            let mut coins = Combination::new();
+            for (coin, &radix) in Coin::iter().zip(&radixes) {
+                coins.extend(std::iter::repeat_n(coin, rest % radix));
+                rest /= radix;
+            }
+            coins
+        })
+        .collect()
+}
+
+// ============================================================================
+// CombinationIter: lazy enumeration
+// ============================================================================
+
+/// Order in which combinations are enumerated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Order {
+    /// Counting order: combination i has the coins whose bits are set in i
+    /// (the order of generate_all_combinations() and /all)
+    #[default]
+    Binary,
+    /// Reflected Gray code: consecutive combinations differ by exactly one
+    /// coin, like a machine adding or removing one coin per step
+    GrayCode,
+}
+
+impl Order {
+    // Bits of the combination at position `i` in this order
+    fn bits_at(self, i: usize) -> usize {
+        match self {
+            Order::Binary => i,
+            Order::GrayCode => i ^ (i >> 1),
+        }
+    }
+}
+
+/// Iterator over every combination of coins
+///
+/// Created by combinations() or combinations_ordered(). In binary order
+/// item `i` contains the coins whose bits are set in `i`, exactly like
+/// generate_all_combinations()[i].
+#[derive(Debug, Clone)]
+pub struct CombinationIter {
+    order: Order,
+    // Next position to yield from the front
+    front: usize,
+    // One past the next position to yield from the back
+    back: usize,
+}
+
+/// Returns a lazy iterator over all 2^n combinations of coins
+pub fn combinations() -> CombinationIter {
+    combinations_ordered(Order::Binary)
+}
+
+/// Returns a lazy iterator over all 2^n combinations in the given order
+pub fn combinations_ordered(order: Order) -> CombinationIter {
+    CombinationIter {
+        order,
+        front: 0,
+        back: 1 << Coin::COUNT,
+    }
+}
+
+/// Every combination in the given order, collected into a Vec
+pub fn generate_all_combinations_ordered(order: Order) -> Vec<Combination> {
+    combinations_ordered(order).collect()
+}
+
+impl Iterator for CombinationIter {
+    type Item = Combination;
+
+    fn next(&mut self) -> Option<Combination> {
+        if self.front >= self.back {
+            return None;
+        }
+        let combination = combination_from_bits(self.order.bits_at(self.front));
+        self.front += 1;
+        Some(combination)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+
+    // Skipping only moves the cursor, so nth() doesn't decode skipped items
+    fn nth(&mut self, n: usize) -> Option<Combination> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+}
+
+impl DoubleEndedIterator for CombinationIter {
+    fn next_back(&mut self) -> Option<Combination> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(combination_from_bits(self.order.bits_at(self.back)))
+    }
+}
+
+impl ExactSizeIterator for CombinationIter {}
+
+impl FusedIterator for CombinationIter {}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_all_combinations;
+
+    #[test]
+    fn test_mask_round_trip() {
+        for (index, combination) in generate_all_combinations().into_iter().enumerate() {
+            let mask = CombinationMask::from(combination.as_slice());
+            assert_eq!(mask.bits() as usize, index);
+            assert_eq!(Vec::from(mask), combination);
+        }
+    }
+
+    #[test]
+    fn test_mask_collapses_duplicates() {
+        let mask: CombinationMask = [Coin::Dime, Coin::Dime, Coin::Penny].into_iter().collect();
+        assert_eq!(mask.to_vec(), vec![Coin::Penny, Coin::Dime]);
+        assert_eq!(mask.len(), 2);
+    }
+
+    #[test]
+    fn test_mask_value_and_contains() {
+        let mask = CombinationMask::from_bits(0b0101).unwrap();
+        assert_eq!(mask.value(), 11);
+        assert!(mask.contains(Coin::Penny));
+        assert!(!mask.contains(Coin::Nickel));
+
+        assert_eq!(CombinationMask::FULL.value(), 41);
+        assert!(CombinationMask::EMPTY.is_empty());
+        assert_eq!(CombinationMask::EMPTY.value(), 0);
+    }
+
+    #[test]
+    fn test_mask_from_bits_rejects_unknown_bits() {
+        assert_eq!(
+            CombinationMask::from_bits(0b1111),
+            Some(CombinationMask::FULL)
+        );
+        assert_eq!(CombinationMask::from_bits(0b1_0000), None);
+    }
+
+    #[test]
+    fn test_mask_serializes_as_number() {
+        let mask = CombinationMask::from_bits(9).unwrap();
+        assert_eq!(serde_json::to_string(&mask).unwrap(), "9");
+    }
+
+    #[test]
+    fn test_mask_deserialize_rejects_unknown_bits() {
+        let mask: CombinationMask = serde_json::from_str("9").unwrap();
+        assert_eq!(mask.bits(), 9);
+
+        let error = serde_json::from_str::<CombinationMask>("65535").unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("invalid combination mask: 65535 (expected 0 to 15)"));
+        assert_eq!(
+            CombinationMask::try_from(16),
+            Err(InvalidMaskError { bits: 16 })
+        );
+    }
+
+    // ========================================================================
+    // Set Algebra Tests
+    // ========================================================================
+
+    #[test]
+    fn test_mask_set_operations() {
+        let a = CombinationMask::from([Coin::Penny, Coin::Dime].as_slice());
+        let b = CombinationMask::from([Coin::Dime, Coin::Quarter].as_slice());
+
+        assert_eq!(
+            a.union(b).to_vec(),
+            vec![Coin::Penny, Coin::Dime, Coin::Quarter]
+        );
+        assert_eq!(a.intersection(b).to_vec(), vec![Coin::Dime]);
+        assert_eq!(a.difference(b).to_vec(), vec![Coin::Penny]);
+        assert_eq!(b.difference(a).to_vec(), vec![Coin::Quarter]);
+    }
+
+    #[test]
+    fn test_mask_operators_match_methods() {
+        for a in (0..16).filter_map(CombinationMask::from_bits) {
+            for b in (0..16).filter_map(CombinationMask::from_bits) {
+                assert_eq!(a | b, a.union(b));
+                assert_eq!(a & b, a.intersection(b));
+                assert_eq!(a - b, a.difference(b));
+                assert_eq!(a.is_subset_of(b), (a | b) == b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_subset_relations() {
+        let dime = CombinationMask::EMPTY.with(Coin::Dime);
+        assert!(CombinationMask::EMPTY.is_subset_of(dime));
+        assert!(dime.is_subset_of(dime));
+        assert!(dime.is_subset_of(CombinationMask::FULL));
+        assert!(!CombinationMask::FULL.is_subset_of(dime));
+    }
+
+    #[test]
+    fn test_combination_set_operations() {
+        let a = Combination::from(vec![Coin::Penny, Coin::Penny, Coin::Nickel]);
+        let b = Combination::from(vec![Coin::Nickel, Coin::Quarter]);
+
+        assert_eq!(a.union(&b), [Coin::Penny, Coin::Nickel, Coin::Quarter]);
+        assert_eq!(a.intersection(&b), [Coin::Nickel]);
+        assert_eq!(a.difference(&b), [Coin::Penny]);
+        assert!(!a.is_subset_of(&b));
+        assert!(a.intersection(&b).is_subset_of(&b));
+    }
+
+    // ========================================================================
+    // Canonical Form and ID Tests
+    // ========================================================================
+
+    #[test]
+    fn test_canonical_sorts_and_keeps_duplicates() {
+        let coins = Combination::from(vec![Coin::Quarter, Coin::Penny, Coin::Quarter]);
+        assert_eq!(
+            coins.canonical(),
+            [Coin::Penny, Coin::Quarter, Coin::Quarter]
+        );
+    }
+
+    #[test]
+    fn test_id_of_sets_is_the_mask() {
+        for (index, coins) in combinations().enumerate() {
+            assert_eq!(coins.id(), index as u64);
+        }
+        let reordered = Combination::from(vec![Coin::Dime, Coin::Penny]);
+        assert_eq!(reordered.id(), 5);
+    }
+
+    #[test]
+    fn test_id_of_multisets_ignores_order() {
+        let a = Combination::from(vec![Coin::Penny, Coin::Dime, Coin::Penny]);
+        let b = Combination::from(vec![Coin::Dime, Coin::Penny, Coin::Penny]);
+        assert_eq!(a.id(), b.id());
+        assert!(a.id() >= 1 << 63);
+    }
+
+    #[test]
+    fn test_id_distinguishes_multisets() {
+        let ids: std::collections::HashSet<u64> = crate::generate_combinations_with_counts(|_| 5)
+            .iter()
+            .map(Combination::id)
+            .collect();
+        assert_eq!(ids.len(), 6usize.pow(4));
+    }
+
+    #[test]
+    fn test_id_is_stable() {
+        // Pinned so an accidental change to the hash shows up here
+        let two_pennies = Combination::from(vec![Coin::Penny, Coin::Penny]);
+        assert_eq!(two_pennies.id(), 0x9733_7645_9377_76a7);
+    }
+
+    #[test]
+    fn test_rank_unrank_round_trip() {
+        for (index, combination) in generate_all_combinations().into_iter().enumerate() {
+            assert_eq!(combination_index(&combination), index);
+            assert_eq!(combination_at(index), Some(combination));
+        }
+    }
+
+    #[test]
+    fn test_combination_index_ignores_order_and_duplicates() {
+        assert_eq!(combination_index(&[Coin::Dime, Coin::Penny]), 5);
+        assert_eq!(combination_index(&[Coin::Penny, Coin::Dime, Coin::Dime]), 5);
+        assert_eq!(combination_index(&[]), 0);
+    }
+
+    #[test]
+    fn test_combination_at_out_of_range() {
+        assert_eq!(combination_at(16), None);
+        assert_eq!(combination_at(usize::MAX), None);
+    }
+
+    #[test]
+    fn test_matches_generate_all_combinations() {
+        let lazy: Vec<Combination> = combinations().collect();
+        assert_eq!(lazy, generate_all_combinations());
+    }
+
+    #[test]
+    fn test_exact_size() {
+        let mut iter = combinations();
+        assert_eq!(iter.len(), 16);
+        iter.next();
+        iter.next_back();
+        assert_eq!(iter.len(), 14);
+    }
+
+    #[test]
+    fn test_reversed() {
+        let mut reversed: Vec<Combination> = combinations().rev().collect();
+        reversed.reverse();
+        assert_eq!(reversed, generate_all_combinations());
+        assert_eq!(combinations().next_back().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_front_and_back_meet() {
+        let mut iter = combinations();
+        let mut seen = 0;
+        while iter.next().is_some() {
+            seen += 1;
+            if iter.next_back().is_some() {
+                seen += 1;
+            }
+        }
+        assert_eq!(seen, 16);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn test_gray_code_changes_one_coin_per_step() {
+        let gray = generate_all_combinations_ordered(Order::GrayCode);
+        assert!(gray[0].is_empty());
+
+        for pair in gray.windows(2) {
+            let before = CombinationMask::from(pair[0].as_slice()).bits();
+            let after = CombinationMask::from(pair[1].as_slice()).bits();
+            assert_eq!((before ^ after).count_ones(), 1, "{:?}", pair);
+        }
+    }
+
+    #[test]
+    fn test_gray_code_visits_every_combination_once() {
+        let mut gray = generate_all_combinations_ordered(Order::GrayCode);
+        gray.sort();
+        let mut binary = generate_all_combinations_ordered(Order::Binary);
+        binary.sort();
+        assert_eq!(gray, binary);
+    }
+
+    #[test]
+    fn test_gray_code_reversed() {
+        let forward = generate_all_combinations_ordered(Order::GrayCode);
+        let mut backward: Vec<Combination> = combinations_ordered(Order::GrayCode).rev().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+    }
+
+    #[test]
+    fn test_cached_table_matches_generator() {
+        assert_eq!(
+            all_combinations_cached(),
+            crate::generate_all_combinations()
+        );
+    }
+
+    #[test]
+    fn test_cached_table_is_built_once() {
+        let first = all_combinations_cached();
+        let second = all_combinations_cached();
+        assert!(std::ptr::eq(first, second));
+    }
+
+    #[test]
+    fn test_sorted_by_value() {
+        let sorted = generate_all_combinations_sorted(SortBy::Value);
+        let values: Vec<Money> = sorted.iter().map(|c| crate::total_value(c)).collect();
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(sorted.len(), 16);
+    }
+
+    #[test]
+    fn test_sorted_by_coin_count() {
+        let sorted = generate_all_combinations_sorted(SortBy::CoinCount);
+        assert!(sorted.windows(2).all(|pair| pair[0].len() <= pair[1].len()));
+        assert_eq!(
+            &sorted[1..5],
+            &[
+                vec![Coin::Penny],
+                vec![Coin::Nickel],
+                vec![Coin::Dime],
+                vec![Coin::Quarter]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_deserializes_from_snake_case() {
+        let sort_by: SortBy = serde_json::from_str("\"coin_count\"").unwrap();
+        assert_eq!(sort_by, SortBy::CoinCount);
+    }
+
+    #[test]
+    fn test_for_each_combination_matches_generator() {
+        let mut seen = Vec::new();
+        for_each_combination(|coins| seen.push(coins.to_vec()));
+        assert_eq!(seen, crate::generate_all_combinations());
+    }
+
+    #[test]
+    fn test_for_each_combination_sums_values() {
+        let mut total = Money::ZERO;
+        for_each_combination(|coins| total = total + crate::total_value(coins));
+        // Every coin is in 8 of the 16 combinations: 8 * 41 cents
+        assert_eq!(total, 328);
+    }
+
+    #[test]
+    fn test_combinations_of_size_counts() {
+        let counts: Vec<usize> = (0..=5).map(|k| combinations_of_size(k).count()).collect();
+        assert_eq!(counts, vec![1, 4, 6, 4, 1, 0]);
+    }
+
+    #[test]
+    fn test_combinations_of_size_two() {
+        let pairs: Vec<Combination> = combinations_of_size(2).collect();
+        assert!(pairs.iter().all(|coins| coins.len() == 2));
+        assert_eq!(pairs[0], vec![Coin::Penny, Coin::Nickel]);
+        assert_eq!(pairs[5], vec![Coin::Dime, Coin::Quarter]);
+    }
+
+    #[test]
+    fn test_counts_limit_one_is_power_set() {
+        assert_eq!(
+            generate_combinations_with_counts(|_| 1),
+            crate::generate_all_combinations()
+        );
+    }
+
+    #[test]
+    fn test_counts_limit_zero_is_empty_handful() {
+        assert_eq!(
+            generate_combinations_with_counts(|_| 0),
+            vec![Vec::<Coin>::new()]
+        );
+    }
+
+    #[test]
+    fn test_counts_allow_repeats() {
+        let all = generate_combinations_with_counts(|_| 2);
+        assert_eq!(all.len(), 81);
+        assert_eq!(all[2], vec![Coin::Penny, Coin::Penny]);
+        assert_eq!(
+            all[80],
+            vec![
+                Coin::Penny,
+                Coin::Penny,
+                Coin::Nickel,
+                Coin::Nickel,
+                Coin::Dime,
+                Coin::Dime,
+                Coin::Quarter,
+                Coin::Quarter
+            ]
+        );
+        assert!(all.iter().all(|coins| Coin::iter().all(|coin| coins
+            .iter()
+            .filter(|&&c| c == coin)
+            .count()
+            <= 2)));
+    }
+
+    #[test]
+    fn test_counts_are_unique() {
+        let all = generate_combinations_with_counts(|_| 3);
+        let unique: std::collections::HashSet<_> = all.iter().collect();
+        assert_eq!(unique.len(), all.len());
+    }
+
+    #[test]
+    fn test_counts_with_mixed_limits() {
+        let limit = |coin| match coin {
+            Coin::Penny => 2,
+            Coin::Nickel => 0,
+            Coin::Dime => 1,
+            Coin::Quarter => 3,
+        };
+        let all = generate_combinations_with_counts(limit);
+        assert_eq!(all.len(), 3 * 2 * 4);
+        assert_eq!(all[1], vec![Coin::Penny]);
+        assert_eq!(all[3], vec![Coin::Dime]);
+        assert_eq!(all[6], vec![Coin::Quarter]);
+        assert_eq!(
+            all[23],
+            vec![
+                Coin::Penny,
+                Coin::Penny,
+                Coin::Dime,
+                Coin::Quarter,
+                Coin::Quarter,
+                Coin::Quarter
+            ]
+        );
+        assert!(all.iter().all(|coins| Coin::iter().all(|coin| coins
+            .iter()
+            .filter(|&&c| c == coin)
+            .count()
+            <= limit(coin))));
+        let unique: std::collections::HashSet<_> = all.iter().collect();
+        assert_eq!(unique.len(), all.len());
+    }
+
+    #[test]
+    #[should_panic(expected = "max_per_coin is too large")]
+    fn test_counts_limit_overflow_panics() {
+        generate_combinations_with_counts(|coin| if coin == Coin::Dime { usize::MAX } else { 0 });
+    }
+
+    #[test]
+    #[should_panic(expected = "max_per_coin is too large")]
+    fn test_counts_overflow_panics() {
+        generate_combinations_with_counts(|_| usize::MAX / 2);
+    }
+
+    #[test]
+    fn test_nth_skips_without_decoding() {
+        let mut iter = combinations();
+        assert_eq!(iter.nth(5).unwrap(), [Coin::Penny, Coin::Dime]);
+        assert_eq!(iter.len(), 10);
+        assert_eq!(iter.nth(100), None);
+        assert_eq!(iter.len(), 0);
+    }
+}
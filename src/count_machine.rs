@@ -0,0 +1,299 @@
+// ============================================================================
+// COUNT MACHINE MODULE: Cashing In a Coin Jar
+// ============================================================================
+// A supermarket coin-counting machine takes a jar of loose coins, counts
+// them, keeps a fee and pays out the rest:
+//
+//   let report = count_machine::process(&jar, &FeeSchedule::Percentage { basis_points: 1190 })?;
+//   report.gross   // value of the coins
+//   report.fee     // 11.9% of that, rounded to the nearest cent
+//   report.net     // what you walk away with
+//
+// Fees are in basis points (hundredths of a percent) so they stay exact.
+// A rate above 10000 (100%) is an error rather than silently capped.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt;
+use ts_rs::TS;
+
+use crate::{Money, Purse};
+
+/// Basis points in 100%
+const FULL_RATE: u64 = 10_000;
+
+/// One band of a tiered fee
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeTier {
+    /// Upper end of the band in cents, or None for everything above the
+    /// previous band
+    pub up_to: Option<u64>,
+    /// Fee charged on the part of the total inside this band
+    pub basis_points: u32,
+}
+
+/// How a coin-counting machine charges
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum FeeSchedule {
+    /// No fee, e.g. when taking the payout as a gift card
+    #[default]
+    Free,
+    /// A share of the total: 1190 basis points is 11.9%
+    Percentage { basis_points: u32 },
+    /// Marginal bands, like tax brackets: each band's rate applies only to
+    /// the part of the total that falls inside it
+    ///
+    /// Bands may be listed in any order. Any part of the total above the
+    /// highest `up_to` is free unless a band has `up_to: None`.
+    Tiered(Vec<FeeTier>),
+}
+
+/// Error returned for a fee rate above 100% (10000 basis points)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeRateError {
+    basis_points: u32,
+}
+
+impl FeeRateError {
+    /// The rejected rate
+    pub fn basis_points(&self) -> u32 {
+        self.basis_points
+    }
+}
+
+impl fmt::Display for FeeRateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a fee of {} basis points is more than 100% ({FULL_RATE})",
+            self.basis_points
+        )
+    }
+}
+
+impl std::error::Error for FeeRateError {}
+
+fn check_rate(basis_points: u32) -> Result<(), FeeRateError> {
+    if u64::from(basis_points) > FULL_RATE {
+        return Err(FeeRateError { basis_points });
+    }
+    Ok(())
+}
+
+impl FeeSchedule {
+    /// Fee in cents for counting coins worth `gross` cents, rounded to the
+    /// nearest cent (half a cent rounds up), or an error if any rate in the
+    /// schedule is above 100%
+    pub fn fee_for(&self, gross: u64) -> Result<u64, FeeRateError> {
+        // Fee in cents times FULL_RATE, rounded once at the end
+        let scaled: u128 = match self {
+            FeeSchedule::Free => 0,
+            FeeSchedule::Percentage { basis_points } => {
+                check_rate(*basis_points)?;
+                u128::from(gross) * u128::from(*basis_points)
+            }
+            FeeSchedule::Tiered(tiers) => {
+                for tier in tiers {
+                    check_rate(tier.basis_points)?;
+                }
+                let mut tiers = tiers.clone();
+                tiers.sort_by_key(|tier| tier.up_to.unwrap_or(u64::MAX));
+                let mut floor = 0u64;
+                let mut scaled = 0u128;
+                for tier in tiers {
+                    let ceiling = tier.up_to.unwrap_or(u64::MAX).min(gross);
+                    if ceiling > floor {
+                        scaled += u128::from(ceiling - floor) * u128::from(tier.basis_points);
+                        floor = ceiling;
+                    }
+                }
+                scaled
+            }
+        };
+        let fee = (scaled + u128::from(FULL_RATE / 2)) / u128::from(FULL_RATE);
+        Ok(u64::try_from(fee).expect("a fee of at most 100% is at most gross"))
+    }
+}
+
+/// What a coin-counting machine pays out for a jar of coins
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "CountReport.d.ts")]
+pub struct CountReport {
+    /// The coins counted
+    pub coins: Purse,
+    /// Number of physical coins counted
+    #[ts(type = "number")]
+    pub coin_count: u64,
+    /// Value of the coins
+    pub gross: Money,
+    /// Amount kept by the machine
+    pub fee: Money,
+    /// Amount paid out: gross minus fee
+    pub net: Money,
+}
+
+/// Counts the coins in `purse` and applies `fees`, or fails if a rate in
+/// `fees` is above 100%
+pub fn process(purse: &Purse, fees: &FeeSchedule) -> Result<CountReport, FeeRateError> {
+    let gross = purse.total_value();
+    let fee = Money::from_cents(fees.fee_for(gross.cents())?);
+    Ok(CountReport {
+        coins: purse.clone(),
+        coin_count: purse.coin_count(),
+        gross,
+        fee,
+        net: gross - fee,
+    })
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Coin;
+
+    fn jar() -> Purse {
+        // $12.34
+        [
+            (Coin::Penny, 34),
+            (Coin::Nickel, 20),
+            (Coin::Dime, 30),
+            (Coin::Quarter, 32),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn tiered() -> FeeSchedule {
+        FeeSchedule::Tiered(vec![
+            FeeTier {
+                up_to: None,
+                basis_points: 500,
+            },
+            FeeTier {
+                up_to: Some(1_000),
+                basis_points: 1_000,
+            },
+        ])
+    }
+
+    // ========================================================================
+    // Fee schedules
+    // ========================================================================
+
+    #[test]
+    fn test_free() {
+        let report = process(&jar(), &FeeSchedule::Free).unwrap();
+        assert_eq!(report.gross, 1_234);
+        assert_eq!(report.fee, 0);
+        assert_eq!(report.net, report.gross);
+        assert_eq!(report.coin_count, 116);
+        assert_eq!(FeeSchedule::default(), FeeSchedule::Free);
+    }
+
+    #[test]
+    fn test_percentage_rounds_to_nearest_cent() {
+        let fees = FeeSchedule::Percentage {
+            basis_points: 1_190,
+        };
+        let report = process(&jar(), &fees).unwrap();
+        // 11.9% of 1234 is 146.846
+        assert_eq!(report.fee, 147);
+        assert_eq!(report.net, 1_234 - 147);
+        // 11.9% of 50 is 5.95, and half a cent rounds up
+        assert_eq!(fees.fee_for(50).unwrap(), 6);
+        assert_eq!(
+            FeeSchedule::Percentage {
+                basis_points: 5_000
+            }
+            .fee_for(1)
+            .unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_tiered_fee_is_marginal() {
+        // 10% of the first $10, then 5% of the other $2.34
+        assert_eq!(tiered().fee_for(1_234).unwrap(), 100 + 12);
+        assert_eq!(tiered().fee_for(1_000).unwrap(), 100);
+        assert_eq!(tiered().fee_for(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_tiered_without_open_band() {
+        let capped = FeeSchedule::Tiered(vec![FeeTier {
+            up_to: Some(500),
+            basis_points: 2_000,
+        }]);
+        // Only the first $5 is charged
+        assert_eq!(capped.fee_for(2_000).unwrap(), 100);
+        assert_eq!(FeeSchedule::Tiered(vec![]).fee_for(2_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_fee_never_exceeds_gross() {
+        let everything = FeeSchedule::Percentage {
+            basis_points: 10_000,
+        };
+        let report = process(&jar(), &everything).unwrap();
+        assert_eq!(report.fee, report.gross);
+        assert_eq!(report.net, 0);
+    }
+
+    #[test]
+    fn test_rate_above_full_is_rejected() {
+        let greedy = FeeSchedule::Percentage {
+            basis_points: 25_000,
+        };
+        let error = process(&jar(), &greedy).unwrap_err();
+        assert_eq!(error.basis_points(), 25_000);
+        assert_eq!(
+            error.to_string(),
+            "a fee of 25000 basis points is more than 100% (10000)"
+        );
+
+        // Even a band the total never reaches
+        let tiers = FeeSchedule::Tiered(vec![
+            FeeTier {
+                up_to: Some(100),
+                basis_points: 500,
+            },
+            FeeTier {
+                up_to: None,
+                basis_points: 10_001,
+            },
+        ]);
+        assert_eq!(tiers.fee_for(50).unwrap_err().basis_points(), 10_001);
+    }
+
+    // ========================================================================
+    // Reports
+    // ========================================================================
+
+    #[test]
+    fn test_empty_jar() {
+        let report = process(&Purse::new(), &tiered()).unwrap();
+        assert_eq!(report.coin_count, 0);
+        assert_eq!(report.gross, 0);
+        assert_eq!(report.net, 0);
+    }
+
+    #[test]
+    fn test_report_json() {
+        let report = process(
+            &jar(),
+            &FeeSchedule::Percentage {
+                basis_points: 1_190,
+            },
+        )
+        .unwrap();
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["coins"]["Quarter"], 32);
+        assert_eq!(json["gross"], 1_234);
+        assert_eq!(json["net"], 1_087);
+    }
+}
@@ -0,0 +1,118 @@
+// ============================================================================
+// COUNTING MODULE: Ways to Make an Amount
+// ============================================================================
+// How many different handfuls of coins add up to an amount, with unlimited
+// coins of each denomination? This is the coefficient of x^amount in
+//
+//   1 / ((1 - x^d1)(1 - x^d2)...(1 - x^dk))
+//
+// which the classic dynamic programme computes one denomination at a time,
+// in O(amount * k) additions and without enumerating a single handful.
+// Counts grow quickly (there are 15,658,181,104,580,771,094,597,751,280,645
+// ways to make 1000 from the denominations 1 to 100), so they are BigUint.
+
+// Re-exported so callers can name the result type without their own
+// dependency on num-bigint
+pub use num_bigint::BigUint;
+
+use crate::Coin;
+
+/// Number of ways to make `amount` from unlimited coins of the given
+/// denominations, ignoring order
+///
+/// Zero and repeated denominations are ignored. There is exactly one way to
+/// make 0 (no coins at all). Builds a table of a count per amount up to
+/// `amount`, so memory grows with the amount: a million cents already needs
+/// tens of megabytes, and amounts near u64::MAX can't be allocated at all.
+pub fn count_ways(amount: u64, denominations: &[u32]) -> BigUint {
+    let mut denominations: Vec<u32> = denominations.iter().copied().filter(|&d| d > 0).collect();
+    denominations.sort_unstable();
+    denominations.dedup();
+
+    let amount = amount as usize;
+    let mut ways = vec![BigUint::ZERO; amount + 1];
+    ways[0] = BigUint::from(1u32);
+
+    // After processing denomination d, ways[a] counts the handfuls of
+    // denominations up to d worth a: either no d at all (the old ways[a]),
+    // or at least one d on top of a handful worth a - d
+    for d in denominations {
+        let d = d as usize;
+        for a in d..=amount {
+            let (lower, upper) = ways.split_at_mut(a);
+            upper[0] += &lower[a - d];
+        }
+    }
+
+    ways.swap_remove(amount)
+}
+
+/// Number of ways to make `amount` cents from unlimited US coins
+///
+/// Memory grows with the amount, as for count_ways().
+pub fn count_ways_in_coins(amount: u64) -> BigUint {
+    let denominations: Vec<u32> = Coin::iter()
+        .map(|coin| coin.value_in_cents() as u32)
+        .collect();
+    count_ways(amount, &denominations)
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{combinations_with_value_counts, Money};
+
+    // ========================================================================
+    // Counting Tests
+    // ========================================================================
+
+    #[test]
+    fn test_ways_to_make_a_dollar() {
+        assert_eq!(count_ways_in_coins(100), BigUint::from(242u32));
+    }
+
+    #[test]
+    fn test_ways_to_make_five_dollars() {
+        let cents = Money::from_dollars_str("5.00").unwrap().cents();
+        assert_eq!(count_ways_in_coins(cents), BigUint::from(19_006u32));
+        assert_eq!(
+            count_ways(cents, &[1, 5, 10, 25, 50, 100]),
+            BigUint::from(98_411u32)
+        );
+    }
+
+    #[test]
+    fn test_matches_enumeration() {
+        // With enough of each coin, the pruned search finds the same handfuls
+        for amount in [0, 7, 10, 26, 55] {
+            let found = combinations_with_value_counts(amount, amount as usize);
+            assert_eq!(count_ways_in_coins(amount), BigUint::from(found.len()));
+        }
+    }
+
+    #[test]
+    fn test_uk_two_pounds() {
+        let pence = [1, 2, 5, 10, 20, 50, 100, 200];
+        assert_eq!(count_ways(200, &pence), BigUint::from(73_682u32));
+    }
+
+    #[test]
+    fn test_counts_beyond_u64() {
+        let denominations: Vec<u32> = (1..=100).collect();
+        let expected: BigUint = "15658181104580771094597751280645".parse().unwrap();
+        assert_eq!(count_ways(1000, &denominations), expected);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(count_ways(0, &[]), BigUint::from(1u32));
+        assert_eq!(count_ways(5, &[]), BigUint::ZERO);
+        assert_eq!(count_ways(3, &[2]), BigUint::ZERO);
+        // Zeros and repeats do not change the count
+        assert_eq!(count_ways(10, &[0, 5, 5, 1]), count_ways(10, &[1, 5]));
+    }
+}
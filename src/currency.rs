@@ -0,0 +1,683 @@
+// ============================================================================
+// CURRENCY MODULE: Coins of Several Currencies
+// ============================================================================
+// Coin and Purse model US coins. A CurrencySystem describes the circulating
+// coins of another currency, with the same metadata:
+//
+//   CurrencySystem::Eur.denominations()[6]     // €1: 100 cents, 7.5 g
+//   CurrencySystem::Gbp.format_amount(250)     // "£2.50"
+//
+// A MultiCurrencyPurse holds coins of several currencies at once, like a
+// traveller's pocket. Amounts are kept per currency and never added across
+// currencies; operations that need a single currency (total_value(),
+// to_purse()) fail when the purse is mixed.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use ts_rs::TS;
+
+use crate::{Coin, DenominationSet, Purse};
+
+/// One coin of a currency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "Denomination.d.ts")]
+pub struct Denomination {
+    /// English name, e.g. "Dime" or "2 euro"
+    pub name: &'static str,
+    /// Short label as printed on price tags, e.g. "10¢" or "€2"
+    pub symbol: &'static str,
+    /// Face value in the currency's minor unit (cents, pence, yen)
+    pub value: u32,
+    /// Mass in milligrams, as in CoinSpecs
+    pub mass_mg: u32,
+}
+
+const fn denomination(
+    name: &'static str,
+    symbol: &'static str,
+    value: u32,
+    mass_mg: u32,
+) -> Denomination {
+    Denomination {
+        name,
+        symbol,
+        value,
+        mass_mg,
+    }
+}
+
+const USD: [Denomination; 4] = [
+    denomination("Penny", "1¢", 1, 2_500),
+    denomination("Nickel", "5¢", 5, 5_000),
+    denomination("Dime", "10¢", 10, 2_268),
+    denomination("Quarter", "25¢", 25, 5_670),
+];
+
+const EUR: [Denomination; 8] = [
+    denomination("1 cent", "1c", 1, 2_300),
+    denomination("2 cent", "2c", 2, 3_060),
+    denomination("5 cent", "5c", 5, 3_920),
+    denomination("10 cent", "10c", 10, 4_100),
+    denomination("20 cent", "20c", 20, 5_740),
+    denomination("50 cent", "50c", 50, 7_800),
+    denomination("1 euro", "€1", 100, 7_500),
+    denomination("2 euro", "€2", 200, 8_500),
+];
+
+const GBP: [Denomination; 8] = [
+    denomination("1 penny", "1p", 1, 3_560),
+    denomination("2 pence", "2p", 2, 7_120),
+    denomination("5 pence", "5p", 5, 3_250),
+    denomination("10 pence", "10p", 10, 6_500),
+    denomination("20 pence", "20p", 20, 5_000),
+    denomination("50 pence", "50p", 50, 8_000),
+    denomination("1 pound", "£1", 100, 8_750),
+    denomination("2 pounds", "£2", 200, 12_000),
+];
+
+const JPY: [Denomination; 6] = [
+    denomination("1 yen", "¥1", 1, 1_000),
+    denomination("5 yen", "¥5", 5, 3_750),
+    denomination("10 yen", "¥10", 10, 4_500),
+    denomination("50 yen", "¥50", 50, 4_000),
+    denomination("100 yen", "¥100", 100, 4_800),
+    denomination("500 yen", "¥500", 500, 7_100),
+];
+
+/// A currency and its circulating coins
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    TS,
+)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "CurrencySystem.d.ts")]
+pub enum CurrencySystem {
+    /// US dollar: 1, 5, 10 and 25 cents
+    #[default]
+    Usd,
+    /// Euro: 1 cent to 2 euro
+    Eur,
+    /// Pound sterling: 1 penny to 2 pounds
+    Gbp,
+    /// Japanese yen: 1 to 500 yen
+    Jpy,
+}
+
+impl CurrencySystem {
+    /// Every supported currency
+    pub fn all() -> [CurrencySystem; 4] {
+        [
+            CurrencySystem::Usd,
+            CurrencySystem::Eur,
+            CurrencySystem::Gbp,
+            CurrencySystem::Jpy,
+        ]
+    }
+
+    /// Lowercase ISO 4217 code, e.g. "usd"
+    pub fn code(&self) -> &'static str {
+        match self {
+            CurrencySystem::Usd => "usd",
+            CurrencySystem::Eur => "eur",
+            CurrencySystem::Gbp => "gbp",
+            CurrencySystem::Jpy => "jpy",
+        }
+    }
+
+    /// English name, e.g. "Pound sterling"
+    pub fn name(&self) -> &'static str {
+        match self {
+            CurrencySystem::Usd => "US dollar",
+            CurrencySystem::Eur => "Euro",
+            CurrencySystem::Gbp => "Pound sterling",
+            CurrencySystem::Jpy => "Japanese yen",
+        }
+    }
+
+    /// Currency symbol, e.g. "€"
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            CurrencySystem::Usd => "$",
+            CurrencySystem::Eur => "€",
+            CurrencySystem::Gbp => "£",
+            CurrencySystem::Jpy => "¥",
+        }
+    }
+
+    /// Minor units per major unit: 100 cents to the dollar, but the yen has
+    /// no minor unit
+    pub fn minor_units(&self) -> u32 {
+        match self {
+            CurrencySystem::Jpy => 1,
+            _ => 100,
+        }
+    }
+
+    /// The circulating coins, smallest first
+    pub fn denominations(&self) -> &'static [Denomination] {
+        match self {
+            CurrencySystem::Usd => &USD,
+            CurrencySystem::Eur => &EUR,
+            CurrencySystem::Gbp => &GBP,
+            CurrencySystem::Jpy => &JPY,
+        }
+    }
+
+    /// The coin worth `value` minor units, if there is one
+    pub fn denomination(&self, value: u32) -> Option<&'static Denomination> {
+        self.denominations().iter().find(|d| d.value == value)
+    }
+
+    /// The face values as a DenominationSet, for change-making and counting
+    pub fn denomination_set(&self) -> DenominationSet {
+        DenominationSet::new(self.denominations().iter().map(|d| d.value))
+            .expect("every currency has positive denominations")
+    }
+
+    /// Formats an amount in minor units, e.g. "£2.50" or "¥250"
+    pub fn format_amount(&self, amount: u64) -> String {
+        let per_major = u64::from(self.minor_units());
+        if per_major == 1 {
+            format!("{}{}", self.symbol(), amount)
+        } else {
+            format!(
+                "{}{}.{:02}",
+                self.symbol(),
+                amount / per_major,
+                amount % per_major
+            )
+        }
+    }
+}
+
+impl fmt::Display for CurrencySystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Error returned for unrecognised currency codes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrencyParseError {
+    input: String,
+}
+
+impl fmt::Display for CurrencyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let codes: Vec<&str> = CurrencySystem::all().iter().map(|c| c.code()).collect();
+        write!(
+            f,
+            "unknown currency '{}': expected one of {}",
+            self.input,
+            codes.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for CurrencyParseError {}
+
+// Accepts "usd", "USD", " Eur ", ... (case-insensitive)
+impl FromStr for CurrencySystem {
+    type Err = CurrencyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CurrencySystem::all()
+            .into_iter()
+            .find(|currency| currency.code().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| CurrencyParseError {
+                input: s.to_string(),
+            })
+    }
+}
+
+// ============================================================================
+// Multi-currency purses
+// ============================================================================
+
+/// Error returned by MultiCurrencyPurse operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CurrencyError {
+    /// The currency has no coin of this value
+    UnknownDenomination {
+        currency: CurrencySystem,
+        value: u32,
+    },
+    /// More coins were asked for than the purse holds
+    NotEnoughCoins {
+        currency: CurrencySystem,
+        value: u32,
+        requested: u32,
+        available: u32,
+    },
+    /// The operation needs a single currency but the purse holds several
+    MixedCurrencies { currencies: Vec<CurrencySystem> },
+    /// The operation needs `expected` but the purse holds `found`
+    WrongCurrency {
+        expected: CurrencySystem,
+        found: CurrencySystem,
+    },
+}
+
+impl fmt::Display for CurrencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CurrencyError::UnknownDenomination { currency, value } => {
+                write!(f, "{} has no coin worth {}", currency.name(), value)
+            }
+            CurrencyError::NotEnoughCoins {
+                currency,
+                value,
+                requested,
+                available,
+            } => write!(
+                f,
+                "cannot remove {} {} coins worth {} from a purse holding {}",
+                requested, currency, value, available
+            ),
+            CurrencyError::MixedCurrencies { currencies } => {
+                let codes: Vec<&str> = currencies.iter().map(|c| c.code()).collect();
+                write!(
+                    f,
+                    "the purse mixes currencies ({}); this needs a single currency",
+                    codes.join(", ")
+                )
+            }
+            CurrencyError::WrongCurrency { expected, found } => {
+                write!(
+                    f,
+                    "expected {} coins but the purse holds {}",
+                    expected, found
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CurrencyError {}
+
+/// Coins of any supported currencies, counted per currency and face value
+///
+/// Serialized as a map from currency code to a map of face value to count,
+/// e.g. {"usd": {"25": 2}, "eur": {"100": 1}}. Deserializing rejects face
+/// values the currency has no coin for, like add().
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MultiCurrencyPurse(BTreeMap<CurrencySystem, BTreeMap<u32, u32>>);
+
+impl MultiCurrencyPurse {
+    /// Creates an empty purse
+    pub fn new() -> Self {
+        MultiCurrencyPurse::default()
+    }
+
+    /// Puts `count` coins worth `value` minor units of `currency` in the
+    /// purse, or fails if the currency has no such coin
+    ///
+    /// Panics if the purse would hold more than u32::MAX of one coin.
+    pub fn add(
+        &mut self,
+        currency: CurrencySystem,
+        value: u32,
+        count: u32,
+    ) -> Result<(), CurrencyError> {
+        if currency.denomination(value).is_none() {
+            return Err(CurrencyError::UnknownDenomination { currency, value });
+        }
+        if count == 0 {
+            return Ok(());
+        }
+        let held = self
+            .0
+            .entry(currency)
+            .or_default()
+            .entry(value)
+            .or_default();
+        *held = held
+            .checked_add(count)
+            .expect("Purse coin count overflowed");
+        Ok(())
+    }
+
+    /// Takes `count` coins worth `value` of `currency` out of the purse, or
+    /// fails without changing anything if it holds fewer than that
+    pub fn remove(
+        &mut self,
+        currency: CurrencySystem,
+        value: u32,
+        count: u32,
+    ) -> Result<(), CurrencyError> {
+        let available = self.count_of(currency, value);
+        if available < count {
+            return Err(CurrencyError::NotEnoughCoins {
+                currency,
+                value,
+                requested: count,
+                available,
+            });
+        }
+        if count == 0 {
+            return Ok(());
+        }
+        let coins = self.0.get_mut(&currency).expect("coins are held");
+        if available == count {
+            coins.remove(&value);
+            if coins.is_empty() {
+                self.0.remove(&currency);
+            }
+        } else {
+            coins.insert(value, available - count);
+        }
+        Ok(())
+    }
+
+    /// How many coins worth `value` of `currency` the purse holds
+    pub fn count_of(&self, currency: CurrencySystem, value: u32) -> u32 {
+        self.0
+            .get(&currency)
+            .and_then(|coins| coins.get(&value))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Whether the purse holds no coins
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The currencies the purse holds coins of
+    pub fn currencies(&self) -> impl Iterator<Item = CurrencySystem> + '_ {
+        self.0.keys().copied()
+    }
+
+    /// Total value of the coins of `currency`, in its minor unit
+    pub fn total_in(&self, currency: CurrencySystem) -> u64 {
+        self.0.get(&currency).map_or(0, |coins| {
+            coins
+                .iter()
+                .map(|(&value, &count)| u64::from(value) * u64::from(count))
+                .sum()
+        })
+    }
+
+    /// Total value held in each currency, in its minor unit
+    pub fn totals(&self) -> BTreeMap<CurrencySystem, u64> {
+        self.currencies()
+            .map(|currency| (currency, self.total_in(currency)))
+            .collect()
+    }
+
+    /// The only currency in the purse, None if it is empty, or an error if
+    /// it holds more than one
+    pub fn currency(&self) -> Result<Option<CurrencySystem>, CurrencyError> {
+        let currencies: Vec<CurrencySystem> = self.currencies().collect();
+        match currencies.as_slice() {
+            [] => Ok(None),
+            [currency] => Ok(Some(*currency)),
+            _ => Err(CurrencyError::MixedCurrencies { currencies }),
+        }
+    }
+
+    /// Total value of a single-currency purse in its minor unit (0 when
+    /// empty), or an error if the currencies are mixed
+    pub fn total_value(&self) -> Result<u64, CurrencyError> {
+        Ok(self
+            .currency()?
+            .map_or(0, |currency| self.total_in(currency)))
+    }
+
+    /// The US coins as a Purse, or an error if the purse holds anything
+    /// else
+    pub fn to_purse(&self) -> Result<Purse, CurrencyError> {
+        match self.currency()? {
+            None | Some(CurrencySystem::Usd) => Ok(Coin::iter()
+                .map(|coin| {
+                    let value = coin.value_in_cents() as u32;
+                    (coin, self.count_of(CurrencySystem::Usd, value))
+                })
+                .collect()),
+            Some(found) => Err(CurrencyError::WrongCurrency {
+                expected: CurrencySystem::Usd,
+                found,
+            }),
+        }
+    }
+}
+
+impl From<&Purse> for MultiCurrencyPurse {
+    fn from(purse: &Purse) -> Self {
+        let mut multi = MultiCurrencyPurse::new();
+        for (coin, count) in purse.iter() {
+            multi
+                .add(CurrencySystem::Usd, coin.value_in_cents() as u32, count)
+                .expect("every Coin is a US denomination");
+        }
+        multi
+    }
+}
+
+impl<'de> Deserialize<'de> for MultiCurrencyPurse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let coins = BTreeMap::<CurrencySystem, BTreeMap<u32, u32>>::deserialize(deserializer)?;
+        let mut purse = MultiCurrencyPurse::new();
+        for (currency, counts) in coins {
+            for (value, count) in counts {
+                purse
+                    .add(currency, value, count)
+                    .map_err(serde::de::Error::custom)?;
+            }
+        }
+        Ok(purse)
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========================================================================
+    // Currency systems
+    // ========================================================================
+
+    #[test]
+    fn test_usd_matches_coin() {
+        let usd = CurrencySystem::Usd.denominations();
+        assert_eq!(usd.len(), Coin::COUNT);
+        for (denomination, coin) in usd.iter().zip(Coin::iter()) {
+            assert_eq!(u64::from(denomination.value), coin.value_in_cents());
+            assert_eq!(denomination.mass_mg, coin.specs().mass_mg);
+            assert_eq!(denomination.name, format!("{coin:?}"));
+        }
+        assert_eq!(
+            CurrencySystem::Usd.denomination_set(),
+            DenominationSet::us()
+        );
+    }
+
+    #[test]
+    fn test_denominations_are_sorted_and_distinct() {
+        for currency in CurrencySystem::all() {
+            let values: Vec<u32> = currency.denominations().iter().map(|d| d.value).collect();
+            assert!(values.windows(2).all(|w| w[0] < w[1]), "{currency}");
+            assert_eq!(currency.denomination_set().values(), values.as_slice());
+            assert_eq!(values[0], 1);
+        }
+    }
+
+    #[test]
+    fn test_denomination_lookup() {
+        let two_euro = CurrencySystem::Eur.denomination(200).unwrap();
+        assert_eq!(two_euro.symbol, "€2");
+        assert_eq!(two_euro.mass_mg, 8_500);
+        assert_eq!(CurrencySystem::Jpy.denomination(25), None);
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(CurrencySystem::Usd.format_amount(41), "$0.41");
+        assert_eq!(CurrencySystem::Gbp.format_amount(250), "£2.50");
+        assert_eq!(CurrencySystem::Eur.format_amount(123_456), "€1234.56");
+        assert_eq!(CurrencySystem::Jpy.format_amount(250), "¥250");
+    }
+
+    #[test]
+    fn test_parse_currency() {
+        assert_eq!("usd".parse(), Ok(CurrencySystem::Usd));
+        assert_eq!(" JPY ".parse(), Ok(CurrencySystem::Jpy));
+        assert_eq!("Eur".parse(), Ok(CurrencySystem::Eur));
+        let error = "chf".parse::<CurrencySystem>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown currency 'chf': expected one of usd, eur, gbp, jpy"
+        );
+        for currency in CurrencySystem::all() {
+            assert_eq!(currency.to_string().parse(), Ok(currency));
+        }
+    }
+
+    #[test]
+    fn test_currency_serializes_as_code() {
+        assert_eq!(serde_json::to_value(CurrencySystem::Gbp).unwrap(), "gbp");
+        let back: CurrencySystem = serde_json::from_str(r#""eur""#).unwrap();
+        assert_eq!(back, CurrencySystem::Eur);
+        assert_eq!(CurrencySystem::default(), CurrencySystem::Usd);
+    }
+
+    // ========================================================================
+    // Multi-currency purses
+    // ========================================================================
+
+    fn pocket() -> MultiCurrencyPurse {
+        let mut purse = MultiCurrencyPurse::new();
+        purse.add(CurrencySystem::Usd, 25, 3).unwrap();
+        purse.add(CurrencySystem::Eur, 200, 1).unwrap();
+        purse.add(CurrencySystem::Eur, 50, 2).unwrap();
+        purse
+    }
+
+    #[test]
+    fn test_per_currency_totals() {
+        let purse = pocket();
+        assert_eq!(purse.total_in(CurrencySystem::Usd), 75);
+        assert_eq!(purse.total_in(CurrencySystem::Eur), 300);
+        assert_eq!(purse.total_in(CurrencySystem::Jpy), 0);
+        assert_eq!(
+            purse.totals(),
+            BTreeMap::from([(CurrencySystem::Usd, 75), (CurrencySystem::Eur, 300)])
+        );
+        assert_eq!(
+            purse.currencies().collect::<Vec<_>>(),
+            vec![CurrencySystem::Usd, CurrencySystem::Eur]
+        );
+    }
+
+    #[test]
+    fn test_single_currency_operations_reject_mixed_purse() {
+        let purse = pocket();
+        let mixed = CurrencyError::MixedCurrencies {
+            currencies: vec![CurrencySystem::Usd, CurrencySystem::Eur],
+        };
+        assert_eq!(purse.total_value(), Err(mixed.clone()));
+        assert_eq!(purse.to_purse(), Err(mixed.clone()));
+        assert_eq!(
+            mixed.to_string(),
+            "the purse mixes currencies (usd, eur); this needs a single currency"
+        );
+
+        let mut euros = purse.clone();
+        euros.remove(CurrencySystem::Usd, 25, 3).unwrap();
+        assert_eq!(euros.currency(), Ok(Some(CurrencySystem::Eur)));
+        assert_eq!(euros.total_value(), Ok(300));
+        assert_eq!(
+            euros.to_purse(),
+            Err(CurrencyError::WrongCurrency {
+                expected: CurrencySystem::Usd,
+                found: CurrencySystem::Eur
+            })
+        );
+    }
+
+    #[test]
+    fn test_unknown_denomination_is_rejected() {
+        let mut purse = MultiCurrencyPurse::new();
+        let error = purse.add(CurrencySystem::Usd, 50, 1).unwrap_err();
+        assert_eq!(error.to_string(), "US dollar has no coin worth 50");
+        assert!(purse.is_empty());
+        assert!(purse.add(CurrencySystem::Jpy, 500, 1).is_ok());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut purse = pocket();
+        assert_eq!(
+            purse.remove(CurrencySystem::Eur, 50, 5),
+            Err(CurrencyError::NotEnoughCoins {
+                currency: CurrencySystem::Eur,
+                value: 50,
+                requested: 5,
+                available: 2
+            })
+        );
+        assert_eq!(purse, pocket());
+
+        purse.remove(CurrencySystem::Eur, 50, 2).unwrap();
+        purse.remove(CurrencySystem::Eur, 200, 1).unwrap();
+        assert_eq!(purse.currency(), Ok(Some(CurrencySystem::Usd)));
+        purse.remove(CurrencySystem::Usd, 25, 3).unwrap();
+        assert!(purse.is_empty());
+        assert_eq!(purse.total_value(), Ok(0));
+    }
+
+    #[test]
+    fn test_us_purse_round_trip() {
+        let purse: Purse = [(Coin::Penny, 4), (Coin::Quarter, 2)].into_iter().collect();
+        let multi = MultiCurrencyPurse::from(&purse);
+        assert_eq!(multi.count_of(CurrencySystem::Usd, 1), 4);
+        assert_eq!(multi.total_value(), Ok(54));
+        assert_eq!(multi.to_purse(), Ok(purse));
+        assert_eq!(MultiCurrencyPurse::new().to_purse(), Ok(Purse::new()));
+    }
+
+    #[test]
+    fn test_multi_currency_json() {
+        let json = serde_json::to_value(pocket()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"usd": {"25": 3}, "eur": {"50": 2, "200": 1}})
+        );
+        let back: MultiCurrencyPurse = serde_json::from_value(json).unwrap();
+        assert_eq!(back, pocket());
+    }
+
+    #[test]
+    fn test_multi_currency_deserialize_validates_coins() {
+        let empty: MultiCurrencyPurse =
+            serde_json::from_str(r#"{"usd": {"25": 0}, "eur": {}}"#).unwrap();
+        assert!(empty.is_empty());
+
+        let error = serde_json::from_str::<MultiCurrencyPurse>(r#"{"usd": {"50": 1}}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with("US dollar has no coin worth 50"),
+            "{error}"
+        );
+        assert!(serde_json::from_str::<MultiCurrencyPurse>(r#"{"cad": {"25": 1}}"#).is_err());
+    }
+}
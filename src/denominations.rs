@@ -0,0 +1,273 @@
+// ============================================================================
+// DENOMINATIONS MODULE: Custom Coin Systems
+// ============================================================================
+// The Coin enum fixes the four US coins. A DenominationSet describes any
+// coin system by its face values, so the same questions can be asked about
+// euro cents, pre-decimal pounds or a made-up currency:
+//
+//   let euro = DenominationSet::new([1, 2, 5, 10, 20, 50, 100, 200])?;
+//   euro.count_ways(100);              // ways to make 1 euro
+//   euro.frobenius_number();           // Some(0): every amount is payable
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::fmt;
+
+use crate::{count_ways, BigUint, Coin};
+
+/// Error returned when a list of values can't form a denomination set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DenominationError {
+    reason: &'static str,
+}
+
+impl fmt::Display for DenominationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid denomination set: {}", self.reason)
+    }
+}
+
+impl std::error::Error for DenominationError {}
+
+/// A coin system: distinct positive face values, smallest first
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DenominationSet(Vec<u32>);
+
+impl DenominationSet {
+    /// Builds a set from face values in any order
+    ///
+    /// Fails if there are no values or one of them is zero; repeated values
+    /// are merged.
+    pub fn new(values: impl IntoIterator<Item = u32>) -> Result<Self, DenominationError> {
+        let mut values: Vec<u32> = values.into_iter().collect();
+        if values.is_empty() {
+            return Err(DenominationError {
+                reason: "no denominations",
+            });
+        }
+        if values.contains(&0) {
+            return Err(DenominationError {
+                reason: "denominations must be positive",
+            });
+        }
+        values.sort_unstable();
+        values.dedup();
+        Ok(DenominationSet(values))
+    }
+
+    /// The US coins: 1, 5, 10 and 25 cents
+    pub fn us() -> Self {
+        DenominationSet(
+            Coin::iter()
+                .map(|coin| coin.value_in_cents() as u32)
+                .collect(),
+        )
+    }
+
+    /// The face values, smallest first
+    pub fn values(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// Number of denominations (never zero)
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Always false; a set has at least one denomination
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Greatest common divisor of all the denominations
+    ///
+    /// Only multiples of it can be paid, so anything above 1 leaves
+    /// infinitely many amounts unpayable.
+    pub fn gcd(&self) -> u32 {
+        // Never larger than the smallest denomination, so it fits in a u32
+        self.0.iter().fold(0, |g, &d| gcd(g, u64::from(d))) as u32
+    }
+
+    /// Largest amount that can't be paid, see frobenius_number()
+    pub fn frobenius_number(&self) -> Option<u64> {
+        frobenius_number(&self.0)
+    }
+
+    /// Number of ways to pay `amount` with unlimited coins, see count_ways()
+    ///
+    /// Memory grows with the amount, as for count_ways().
+    pub fn count_ways(&self, amount: u64) -> BigUint {
+        count_ways(amount, &self.0)
+    }
+}
+
+pub(crate) fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// ============================================================================
+// Frobenius number
+// ============================================================================
+
+/// The largest amount that can't be paid with unlimited coins of the given
+/// denominations
+///
+/// Returns None when there is no largest one: the list is empty (after
+/// dropping zeros) or the denominations share a factor above 1, like
+/// [4, 6], which can never pay an odd amount. Returns Some(0) when every
+/// amount can be paid, e.g. whenever 1 is a denomination.
+///
+/// Works by finding, for each remainder r modulo the smallest denomination
+/// m, the smallest payable amount congruent to r (a shortest-path search
+/// over m nodes). Every larger amount with that remainder is payable by
+/// adding coins of m, so the answer is the largest of those minimums,
+/// minus m. Time and memory grow with m.
+pub fn frobenius_number(denominations: &[u32]) -> Option<u64> {
+    let values: Vec<u64> = denominations
+        .iter()
+        .filter(|&&d| d > 0)
+        .map(|&d| u64::from(d))
+        .collect::<HashSet<u64>>()
+        .into_iter()
+        .collect();
+
+    let smallest = *values.iter().min()?;
+    if values.iter().fold(0, |g, &d| gcd(g, d)) != 1 {
+        return None;
+    }
+    if smallest == 1 {
+        return Some(0);
+    }
+
+    // Dijkstra over remainders: lowest[r] is the smallest payable amount
+    // with remainder r
+    let m = smallest as usize;
+    let mut lowest = vec![u64::MAX; m];
+    let mut queue = BinaryHeap::new();
+    lowest[0] = 0;
+    queue.push(Reverse((0u64, 0usize)));
+
+    while let Some(Reverse((amount, remainder))) = queue.pop() {
+        if amount > lowest[remainder] {
+            continue;
+        }
+        for &d in &values {
+            let next = amount + d;
+            let next_remainder = (next % smallest) as usize;
+            if next < lowest[next_remainder] {
+                lowest[next_remainder] = next;
+                queue.push(Reverse((next, next_remainder)));
+            }
+        }
+    }
+
+    let largest = lowest.into_iter().max().expect("at least one remainder");
+    Some(largest - smallest)
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========================================================================
+    // DenominationSet Tests
+    // ========================================================================
+
+    #[test]
+    fn test_new_sorts_and_dedupes() {
+        let set = DenominationSet::new([25, 1, 10, 5, 10]).unwrap();
+        assert_eq!(set.values(), &[1, 5, 10, 25]);
+        assert_eq!(set, DenominationSet::us());
+        assert_eq!(set.len(), 4);
+    }
+
+    #[test]
+    fn test_new_rejects_empty_and_zero() {
+        let error = DenominationSet::new([]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid denomination set: no denominations"
+        );
+        assert!(DenominationSet::new([0, 5]).is_err());
+    }
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(DenominationSet::us().gcd(), 1);
+        assert_eq!(DenominationSet::new([4, 6, 10]).unwrap().gcd(), 2);
+        assert_eq!(DenominationSet::new([7]).unwrap().gcd(), 7);
+    }
+
+    #[test]
+    fn test_count_ways_delegates() {
+        assert_eq!(DenominationSet::us().count_ways(100), BigUint::from(242u32));
+    }
+
+    // ========================================================================
+    // Frobenius Number Tests
+    // ========================================================================
+
+    #[test]
+    fn test_two_coprime_denominations() {
+        // For two coprime values a and b it is a * b - a - b
+        assert_eq!(frobenius_number(&[3, 5]), Some(7));
+        assert_eq!(frobenius_number(&[7, 11]), Some(59));
+    }
+
+    #[test]
+    fn test_chicken_nuggets() {
+        assert_eq!(frobenius_number(&[6, 9, 20]), Some(43));
+    }
+
+    #[test]
+    fn test_sets_containing_one() {
+        assert_eq!(frobenius_number(&[1, 5, 10, 25]), Some(0));
+        assert_eq!(DenominationSet::us().frobenius_number(), Some(0));
+    }
+
+    #[test]
+    fn test_shared_factor_has_no_answer() {
+        assert_eq!(frobenius_number(&[4, 6]), None);
+        assert_eq!(frobenius_number(&[5, 10, 25]), None);
+    }
+
+    #[test]
+    fn test_degenerate_inputs() {
+        assert_eq!(frobenius_number(&[]), None);
+        assert_eq!(frobenius_number(&[0]), None);
+        assert_eq!(frobenius_number(&[0, 3, 5, 5]), Some(7));
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let denominations = [5, 8, 13];
+        let answer = frobenius_number(&denominations).unwrap();
+
+        let limit = answer as usize + 20;
+        let mut payable = vec![false; limit];
+        payable[0] = true;
+        for amount in 1..limit {
+            payable[amount] = denominations
+                .iter()
+                .any(|&d| amount >= d as usize && payable[amount - d as usize]);
+        }
+        assert!(!payable[answer as usize]);
+        assert!(payable[answer as usize + 1..].iter().all(|&p| p));
+    }
+
+    #[test]
+    fn test_large_values_do_not_overflow() {
+        assert_eq!(
+            frobenius_number(&[65_536, 65_537]),
+            Some(65_536 * 65_537 - 65_536 - 65_537)
+        );
+    }
+}
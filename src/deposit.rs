@@ -0,0 +1,247 @@
+// ============================================================================
+// DEPOSIT MODULE: Preparing Coins for the Bank
+// ============================================================================
+// Banks take coins in standard paper rolls (see the rolls module). Whether
+// they also take the loose coins left over depends on the bank, so
+// prepare_with() applies DepositRules to the rolls pack() fills and returns
+// a deposit slip:
+//
+//   let slip = deposit::prepare(&jar);
+//   slip.deposit_total     // value of the rolls going to the bank
+//   slip.returned          // loose coins to take back home
+//   slip.to_csv()          // one row per coin, then a total
+//
+// The slip serializes to JSON with serde, and to_csv() writes it as CSV.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::csv_out::write_csv;
+use crate::rolls::pack;
+use crate::{Coin, Money, Purse};
+
+/// What the bank does with coins that don't fill a roll
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoosePolicy {
+    /// Loose coins are handed back; only full rolls are deposited
+    #[default]
+    Return,
+    /// Loose coins are counted and deposited with the rolls
+    Accept,
+}
+
+/// A bank's rules for coin deposits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DepositRules {
+    pub loose: LoosePolicy,
+}
+
+/// One coin's line on a deposit slip
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "DepositLine.d.ts")]
+pub struct DepositLine {
+    pub coin: Coin,
+    /// Full rolls deposited
+    pub rolls: u32,
+    pub rolled_value: Money,
+    /// Loose coins deposited (0 when the bank returns them)
+    pub loose: u32,
+    pub loose_value: Money,
+    /// Everything deposited for this coin
+    pub total: Money,
+}
+
+/// What goes to the bank and what comes back
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "DepositSlip.d.ts")]
+pub struct DepositSlip {
+    /// One line per coin type, in ascending value order
+    pub lines: Vec<DepositLine>,
+    pub total_rolls: u32,
+    pub rolled_total: Money,
+    pub loose_total: Money,
+    /// Value of everything deposited
+    pub deposit_total: Money,
+    /// Loose coins the bank hands back
+    pub returned: Purse,
+}
+
+/// A deposit slip for `purse` under the usual rule that only full rolls
+/// are accepted
+pub fn prepare(purse: &Purse) -> DepositSlip {
+    prepare_with(purse, &DepositRules::default())
+}
+
+/// A deposit slip for `purse` under `rules`
+pub fn prepare_with(purse: &Purse, rules: &DepositRules) -> DepositSlip {
+    let accept_loose = rules.loose == LoosePolicy::Accept;
+    let mut returned = Purse::new();
+    let lines: Vec<DepositLine> = pack(purse)
+        .lines
+        .into_iter()
+        .map(|line| {
+            let (loose, loose_value) = if accept_loose {
+                (line.loose, line.loose_value)
+            } else {
+                returned.add(line.coin, line.loose);
+                (0, Money::from_cents(0))
+            };
+            DepositLine {
+                coin: line.coin,
+                rolls: line.rolls,
+                rolled_value: line.rolled_value,
+                loose,
+                loose_value,
+                total: line.rolled_value + loose_value,
+            }
+        })
+        .collect();
+
+    let rolled_total = lines.iter().map(|line| line.rolled_value).sum();
+    let loose_total = lines.iter().map(|line| line.loose_value).sum();
+    DepositSlip {
+        total_rolls: lines.iter().map(|line| line.rolls).sum(),
+        rolled_total,
+        loose_total,
+        deposit_total: rolled_total + loose_total,
+        returned,
+        lines,
+    }
+}
+
+impl DepositSlip {
+    /// The slip as CSV: a header, one row per coin and a final "Total" row
+    ///
+    /// Values are in cents. Returned coins are not part of the deposit and
+    /// are left out.
+    pub fn to_csv(&self) -> String {
+        let total = SlipCsvRow::Total(
+            "Total",
+            self.total_rolls,
+            self.rolled_total,
+            self.lines.iter().map(|line| line.loose).sum(),
+            self.loose_total,
+            self.deposit_total,
+        );
+        write_csv(
+            &[
+                "coin",
+                "rolls",
+                "rolled_value",
+                "loose",
+                "loose_value",
+                "total",
+            ],
+            self.lines.iter().map(SlipCsvRow::Line).chain([total]),
+        )
+    }
+}
+
+// A row of the slip's CSV: one coin's line, or the totals at the bottom
+#[derive(Serialize)]
+#[serde(untagged)]
+enum SlipCsvRow<'a> {
+    Line(&'a DepositLine),
+    Total(&'static str, u32, Money, u32, Money, Money),
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jar() -> Purse {
+        [(Coin::Penny, 123), (Coin::Dime, 50), (Coin::Quarter, 85)]
+            .into_iter()
+            .collect()
+    }
+
+    // ========================================================================
+    // Rules
+    // ========================================================================
+
+    #[test]
+    fn test_only_full_rolls_by_default() {
+        let slip = prepare(&jar());
+        assert_eq!(slip.total_rolls, 2 + 1 + 2);
+        assert_eq!(slip.rolled_total, 100 + 500 + 2_000);
+        assert_eq!(slip.loose_total, 0);
+        assert_eq!(slip.deposit_total, slip.rolled_total);
+        assert_eq!(
+            slip.returned,
+            [(Coin::Penny, 23), (Coin::Quarter, 5)]
+                .into_iter()
+                .collect()
+        );
+        assert!(slip.lines.iter().all(|line| line.loose == 0));
+    }
+
+    #[test]
+    fn test_accepting_loose_coins() {
+        let rules = DepositRules {
+            loose: LoosePolicy::Accept,
+        };
+        let slip = prepare_with(&jar(), &rules);
+        assert!(slip.returned.is_empty());
+        assert_eq!(slip.loose_total, 23 + 125);
+        assert_eq!(slip.deposit_total, jar().total_value());
+
+        let quarters = &slip.lines[3];
+        assert_eq!((quarters.rolls, quarters.loose), (2, 5));
+        assert_eq!(quarters.total, 2_125);
+    }
+
+    #[test]
+    fn test_nothing_is_lost() {
+        for rules in [
+            DepositRules::default(),
+            DepositRules {
+                loose: LoosePolicy::Accept,
+            },
+        ] {
+            let slip = prepare_with(&jar(), &rules);
+            assert_eq!(
+                slip.deposit_total + slip.returned.total_value(),
+                jar().total_value()
+            );
+        }
+    }
+
+    #[test]
+    fn test_empty_purse() {
+        let slip = prepare(&Purse::new());
+        assert_eq!(slip.lines.len(), Coin::COUNT);
+        assert_eq!(slip.deposit_total, 0);
+        assert!(slip.returned.is_empty());
+    }
+
+    // ========================================================================
+    // Output
+    // ========================================================================
+
+    #[test]
+    fn test_csv() {
+        let csv = prepare(&jar()).to_csv();
+        assert_eq!(
+            csv,
+            "coin,rolls,rolled_value,loose,loose_value,total\n\
+             Penny,2,100,0,0,100\n\
+             Nickel,0,0,0,0,0\n\
+             Dime,1,500,0,0,500\n\
+             Quarter,2,2000,0,0,2000\n\
+             Total,5,2600,0,0,2600\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let json = serde_json::to_value(prepare(&jar())).unwrap();
+        assert_eq!(json["deposit_total"], 2_600);
+        assert_eq!(json["returned"]["Penny"], 23);
+        assert_eq!(json["lines"][3]["coin"], "Quarter");
+    }
+}
@@ -0,0 +1,276 @@
+// ============================================================================
+// FLIP MODULE: Coin Flips
+// ============================================================================
+// Fair and biased coin flips, with streak statistics:
+//
+//   let side: Side = rng.gen();                       // fair
+//   let flips = flips_with(&mut rng, 100, 0.7)?;      // 70% heads
+//   flip_stats(&flips).longest_streak                 // e.g. 9
+//
+// Served at GET /flip and by the `flip` command of the binary.
+
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt;
+use ts_rs::TS;
+
+use crate::random::check_probability;
+use crate::ProbabilityError;
+
+/// Which way up a flipped coin lands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "Side.d.ts")]
+pub enum Side {
+    Heads,
+    Tails,
+}
+
+impl Side {
+    /// "H" or "T"
+    pub fn letter(&self) -> char {
+        match self {
+            Side::Heads => 'H',
+            Side::Tails => 'T',
+        }
+    }
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Side::Heads => "heads",
+            Side::Tails => "tails",
+        })
+    }
+}
+
+// A fair flip: `let side: Side = rng.gen();`
+impl Distribution<Side> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Side {
+        rng.sample(BiasedFlip::FAIR)
+    }
+}
+
+/// Flips that land heads with a fixed probability
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BiasedFlip {
+    heads: f64,
+}
+
+impl BiasedFlip {
+    /// Heads and tails equally likely
+    pub const FAIR: BiasedFlip = BiasedFlip { heads: 0.5 };
+
+    /// Flips landing heads with probability `heads`
+    pub fn new(heads: f64) -> Result<Self, ProbabilityError> {
+        Ok(BiasedFlip {
+            heads: check_probability(heads)?,
+        })
+    }
+
+    /// Probability of heads
+    pub fn heads_probability(&self) -> f64 {
+        self.heads
+    }
+}
+
+impl Default for BiasedFlip {
+    fn default() -> Self {
+        BiasedFlip::FAIR
+    }
+}
+
+impl Distribution<Side> for BiasedFlip {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Side {
+        if rng.gen_bool(self.heads) {
+            Side::Heads
+        } else {
+            Side::Tails
+        }
+    }
+}
+
+/// A single fair flip
+pub fn flip() -> Side {
+    rand::thread_rng().gen()
+}
+
+/// A single flip drawn from `rng` that lands heads with probability `heads`
+pub fn flip_biased_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    heads: f64,
+) -> Result<Side, ProbabilityError> {
+    Ok(rng.sample(BiasedFlip::new(heads)?))
+}
+
+/// `n` flips landing heads with probability `heads` (0.5 for a fair coin)
+pub fn flips(n: usize, heads: f64) -> Result<Vec<Side>, ProbabilityError> {
+    flips_with(&mut rand::thread_rng(), n, heads)
+}
+
+/// Like flips(), drawing from `rng`
+pub fn flips_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    n: usize,
+    heads: f64,
+) -> Result<Vec<Side>, ProbabilityError> {
+    let flip = BiasedFlip::new(heads)?;
+    Ok(rng.sample_iter(flip).take(n).collect())
+}
+
+// ============================================================================
+// Statistics
+// ============================================================================
+
+/// Summary of a sequence of flips
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "FlipStats.d.ts")]
+pub struct FlipStats {
+    pub count: usize,
+    pub heads: usize,
+    pub tails: usize,
+    /// Fraction of flips that were heads; 0 for no flips
+    pub heads_rate: f64,
+    /// Length of the longest run of the same side
+    pub longest_streak: usize,
+    /// Side of the longest run (the first one on ties), None for no flips
+    pub longest_streak_side: Option<Side>,
+}
+
+/// Counts and the longest streak of `flips`
+pub fn flip_stats(flips: &[Side]) -> FlipStats {
+    let heads = flips.iter().filter(|&&side| side == Side::Heads).count();
+
+    let mut longest: Option<(Side, usize)> = None;
+    for run in flips.chunk_by(|a, b| a == b) {
+        if longest.is_none_or(|(_, len)| run.len() > len) {
+            longest = Some((run[0], run.len()));
+        }
+    }
+
+    FlipStats {
+        count: flips.len(),
+        heads,
+        tails: flips.len() - heads,
+        heads_rate: if flips.is_empty() {
+            0.0
+        } else {
+            heads as f64 / flips.len() as f64
+        },
+        longest_streak: longest.map_or(0, |(_, len)| len),
+        longest_streak_side: longest.map(|(side, _)| side),
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use Side::{Heads, Tails};
+
+    // ========================================================================
+    // Flipping
+    // ========================================================================
+
+    #[test]
+    fn test_fair_flips_are_balanced() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let flips = flips_with(&mut rng, 10_000, 0.5).unwrap();
+        let rate = flip_stats(&flips).heads_rate;
+        assert!((rate - 0.5).abs() < 0.02, "heads rate {rate}");
+    }
+
+    #[test]
+    fn test_biased_flips_follow_bias() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let flips = flips_with(&mut rng, 10_000, 0.8).unwrap();
+        let rate = flip_stats(&flips).heads_rate;
+        assert!((rate - 0.8).abs() < 0.02, "heads rate {rate}");
+    }
+
+    #[test]
+    fn test_certain_bias() {
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!(flips_with(&mut rng, 50, 1.0)
+            .unwrap()
+            .iter()
+            .all(|&side| side == Heads));
+        assert_eq!(flip_biased_with(&mut rng, 0.0), Ok(Tails));
+    }
+
+    #[test]
+    fn test_invalid_bias_is_rejected() {
+        for bias in [-0.5, 1.01, f64::NAN] {
+            assert!(flips(3, bias).is_err(), "{bias} was accepted");
+        }
+        assert_eq!(
+            BiasedFlip::new(2.0).unwrap_err().to_string(),
+            "invalid probability 2: must be between 0 and 1"
+        );
+    }
+
+    #[test]
+    fn test_standard_flip_is_fair_flip() {
+        // The same RNG state gives the same side either way
+        let standard: Side = StepRng::new(0, 0).gen();
+        assert_eq!(standard, StepRng::new(0, 0).sample(BiasedFlip::FAIR));
+        assert_eq!(BiasedFlip::default(), BiasedFlip::FAIR);
+        assert_eq!(BiasedFlip::FAIR.heads_probability(), 0.5);
+    }
+
+    #[test]
+    fn test_same_seed_same_flips() {
+        let first = flips_with(&mut StdRng::seed_from_u64(9), 20, 0.5);
+        let second = flips_with(&mut StdRng::seed_from_u64(9), 20, 0.5);
+        assert_eq!(first, second);
+        assert_eq!(flips(0, 0.5), Ok(vec![]));
+        let _ = flip();
+    }
+
+    // ========================================================================
+    // Statistics
+    // ========================================================================
+
+    #[test]
+    fn test_stats_count_sides_and_streaks() {
+        let stats = flip_stats(&[Heads, Tails, Tails, Tails, Heads, Heads]);
+        assert_eq!(stats.count, 6);
+        assert_eq!(stats.heads, 3);
+        assert_eq!(stats.tails, 3);
+        assert_eq!(stats.heads_rate, 0.5);
+        assert_eq!(stats.longest_streak, 3);
+        assert_eq!(stats.longest_streak_side, Some(Tails));
+    }
+
+    #[test]
+    fn test_stats_ties_keep_first_streak() {
+        let stats = flip_stats(&[Heads, Heads, Tails, Tails]);
+        assert_eq!(stats.longest_streak, 2);
+        assert_eq!(stats.longest_streak_side, Some(Heads));
+    }
+
+    #[test]
+    fn test_stats_of_no_flips() {
+        let stats = flip_stats(&[]);
+        assert_eq!(stats.count, 0);
+        assert_eq!(stats.heads_rate, 0.0);
+        assert_eq!(stats.longest_streak, 0);
+        assert_eq!(stats.longest_streak_side, None);
+    }
+
+    #[test]
+    fn test_side_display() {
+        assert_eq!(Heads.to_string(), "heads");
+        assert_eq!(Tails.letter(), 'T');
+        assert_eq!(serde_json::to_value(Heads).unwrap(), "Heads");
+    }
+}
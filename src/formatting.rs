@@ -0,0 +1,91 @@
+// ============================================================================
+// FORMATTING MODULE: Human-Readable Combinations
+// ============================================================================
+// Renders combinations as English text, e.g. "2 quarters, 1 dime and 3 pennies"
+
+use crate::Coin;
+
+/// Lower-case name of a coin, singular or plural depending on `count`
+pub fn coin_name(coin: Coin, count: usize) -> &'static str {
+    match (coin, count == 1) {
+        (Coin::Penny, true) => "penny",
+        (Coin::Penny, false) => "pennies",
+        (Coin::Nickel, true) => "nickel",
+        (Coin::Nickel, false) => "nickels",
+        (Coin::Dime, true) => "dime",
+        (Coin::Dime, false) => "dimes",
+        (Coin::Quarter, true) => "quarter",
+        (Coin::Quarter, false) => "quarters",
+    }
+}
+
+/// Formats a combination as a count-aware English phrase
+///
+/// Coins are grouped by type and listed from highest to lowest value,
+/// regardless of their order in the input:
+/// - `[]` becomes `"no coins"`
+/// - `[Dime]` becomes `"1 dime"`
+/// - `[Penny, Quarter, Penny]` becomes `"1 quarter and 2 pennies"`
+pub fn format_combination(coins: &[Coin]) -> String {
+    let parts: Vec<String> = Coin::iter()
+        .rev()
+        .map(|coin| (coin, coins.iter().filter(|&&c| c == coin).count()))
+        .filter(|&(_, count)| count > 0)
+        .map(|(coin, count)| format!("{} {}", count, coin_name(coin, count)))
+        .collect();
+
+    match parts.as_slice() {
+        [] => "no coins".to_string(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coin_name_pluralization() {
+        assert_eq!(coin_name(Coin::Penny, 1), "penny");
+        assert_eq!(coin_name(Coin::Penny, 3), "pennies");
+        assert_eq!(coin_name(Coin::Quarter, 0), "quarters");
+        assert_eq!(coin_name(Coin::Dime, 2), "dimes");
+    }
+
+    #[test]
+    fn test_format_empty_combination() {
+        assert_eq!(format_combination(&[]), "no coins");
+    }
+
+    #[test]
+    fn test_format_single_coin() {
+        assert_eq!(format_combination(&[Coin::Dime]), "1 dime");
+    }
+
+    #[test]
+    fn test_format_two_groups_uses_and() {
+        let coins = [Coin::Penny, Coin::Quarter, Coin::Penny];
+        assert_eq!(format_combination(&coins), "1 quarter and 2 pennies");
+    }
+
+    #[test]
+    fn test_format_many_groups() {
+        let coins = [
+            Coin::Penny,
+            Coin::Quarter,
+            Coin::Dime,
+            Coin::Penny,
+            Coin::Quarter,
+            Coin::Penny,
+        ];
+        assert_eq!(
+            format_combination(&coins),
+            "2 quarters, 1 dime and 3 pennies"
+        );
+    }
+}
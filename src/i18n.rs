@@ -0,0 +1,158 @@
+// ============================================================================
+// I18N MODULE: Localized Coin Names
+// ============================================================================
+// Maps coin names into other languages. The web API exposes this through
+// the `?lang=` query parameter.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Coin;
+
+/// Supported languages for coin names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    Es,
+    Fr,
+    De,
+}
+
+impl Lang {
+    /// Every supported language
+    pub fn all() -> [Lang; 4] {
+        [Lang::En, Lang::Es, Lang::Fr, Lang::De]
+    }
+
+    /// ISO 639-1 code, e.g. "fr"
+    pub fn code(&self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Es => "es",
+            Lang::Fr => "fr",
+            Lang::De => "de",
+        }
+    }
+}
+
+/// Error returned for unsupported language codes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LangParseError {
+    input: String,
+}
+
+impl fmt::Display for LangParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let supported: Vec<&str> = Lang::all().iter().map(|l| l.code()).collect();
+        write!(
+            f,
+            "unsupported language '{}': expected one of {}",
+            self.input,
+            supported.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for LangParseError {}
+
+// Accepts a bare code ("fr") or a full tag whose language part is
+// supported ("fr-CA", "es_MX"); matching is case-insensitive
+impl FromStr for Lang {
+    type Err = LangParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let code = trimmed.split(['-', '_']).next().unwrap_or(trimmed);
+        Lang::all()
+            .into_iter()
+            .find(|lang| lang.code().eq_ignore_ascii_case(code))
+            .ok_or_else(|| LangParseError {
+                input: s.to_string(),
+            })
+    }
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl Coin {
+    /// Name of this coin in the given language
+    pub fn localized_name(&self, lang: Lang) -> &'static str {
+        match (lang, self) {
+            (Lang::En, Coin::Penny) => "Penny",
+            (Lang::En, Coin::Nickel) => "Nickel",
+            (Lang::En, Coin::Dime) => "Dime",
+            (Lang::En, Coin::Quarter) => "Quarter",
+
+            (Lang::Es, Coin::Penny) => "Centavo",
+            (Lang::Es, Coin::Nickel) => "Moneda de cinco centavos",
+            (Lang::Es, Coin::Dime) => "Moneda de diez centavos",
+            (Lang::Es, Coin::Quarter) => "Cuarto de dólar",
+
+            (Lang::Fr, Coin::Penny) => "Cent",
+            (Lang::Fr, Coin::Nickel) => "Pièce de cinq cents",
+            (Lang::Fr, Coin::Dime) => "Pièce de dix cents",
+            (Lang::Fr, Coin::Quarter) => "Quart de dollar",
+
+            (Lang::De, Coin::Penny) => "Cent",
+            (Lang::De, Coin::Nickel) => "Fünf-Cent-Münze",
+            (Lang::De, Coin::Dime) => "Zehn-Cent-Münze",
+            (Lang::De, Coin::Quarter) => "Vierteldollar",
+        }
+    }
+}
+
+/// Localized names for each coin in a combination, in the same order
+pub fn localized_names(coins: &[Coin], lang: Lang) -> Vec<String> {
+    coins
+        .iter()
+        .map(|coin| coin.localized_name(lang).to_string())
+        .collect()
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_names_match_variants() {
+        for coin in Coin::iter() {
+            assert_eq!(coin.localized_name(Lang::En), format!("{:?}", coin));
+        }
+    }
+
+    #[test]
+    fn test_localized_names() {
+        assert_eq!(Coin::Quarter.localized_name(Lang::Es), "Cuarto de dólar");
+        assert_eq!(Coin::Quarter.localized_name(Lang::Fr), "Quart de dollar");
+        assert_eq!(Coin::Quarter.localized_name(Lang::De), "Vierteldollar");
+    }
+
+    #[test]
+    fn test_localized_names_for_combination() {
+        let names = localized_names(&[Coin::Penny, Coin::Dime], Lang::De);
+        assert_eq!(names, vec!["Cent", "Zehn-Cent-Münze"]);
+    }
+
+    #[test]
+    fn test_parse_lang() {
+        assert_eq!("es".parse::<Lang>(), Ok(Lang::Es));
+        assert_eq!("FR".parse::<Lang>(), Ok(Lang::Fr));
+        assert_eq!("de-AT".parse::<Lang>(), Ok(Lang::De));
+        assert_eq!("en_US".parse::<Lang>(), Ok(Lang::En));
+
+        let err = "pt".parse::<Lang>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unsupported language 'pt': expected one of en, es, fr, de"
+        );
+    }
+}
@@ -4,22 +4,132 @@
 // This file contains all the core logic for coin combinations.
 // The `pub` keyword makes items publicly accessible from main.rs
 
-use rand::Rng;
-use serde::Serialize;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use schemars::JsonSchema;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use ts_rs::TS;
 
 // ============================================================================
 // Modules
 // ============================================================================
 
+pub mod analysis;
+pub mod catalog;
+pub mod change;
+pub mod combinations;
+pub mod count_machine;
+pub mod counting;
+mod csv_out;
+pub mod currency;
+pub mod denominations;
+pub mod deposit;
+pub mod flip;
+pub mod formatting;
+pub mod i18n;
+pub mod locale;
+pub mod money;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod powerset;
+pub mod probability;
+pub mod purse;
+pub mod query;
+pub mod random;
+pub mod register;
+pub mod rolls;
+pub mod rounding;
+pub mod savings;
+pub mod schema;
+pub mod search;
+pub mod simulation;
+pub mod specs;
+pub mod stats;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod transactions;
+pub mod vending;
+pub mod wallet;
 pub mod web;
 
+pub use analysis::{
+    analyze, greedy_counterexample, is_canonical, GreedyCounterexample, SystemReport,
+};
+pub use change::{
+    make_change, make_change_all, make_change_from, make_change_iter, split, ChangeError,
+    ChangeWays,
+};
+pub use combinations::{
+    all_combinations_cached, combination_at, combination_index, combinations, combinations_of_size,
+    combinations_ordered, for_each_combination, generate_all_combinations_ordered,
+    generate_all_combinations_sorted, generate_combinations_with_counts, Combination,
+    CombinationIter, CombinationMask, InvalidMaskError, Order, SortBy,
+};
+pub use counting::{count_ways, count_ways_in_coins, BigUint};
+pub use currency::{
+    CurrencyError, CurrencyParseError, CurrencySystem, Denomination, MultiCurrencyPurse,
+};
+pub use denominations::{frobenius_number, DenominationError, DenominationSet};
+pub use flip::{
+    flip, flip_biased_with, flip_stats, flips, flips_with, BiasedFlip, FlipStats, Side,
+};
+pub use formatting::format_combination;
+pub use i18n::{Lang, LangParseError};
+pub use locale::{Locale, LocaleParseError};
+pub use money::{Dollars, Money, MoneyOverflowError, MoneyParseError};
+pub use powerset::{for_each_subset, power_set, power_set_with_limit, PowerSet, PowerSetError};
+pub use probability::{
+    expected_value, probability_contains, probability_of, probability_value_at_least,
+    probability_value_at_most, probability_value_exactly, variance,
+};
+pub use purse::{
+    Capacity, NotEnoughCoinsError, OverflowError, Purse, PurseCsvError, PurseFileError,
+    PurseFileFormat, ReconciliationLine, ReconciliationReport, SubPurses, PURSE_FILE_VERSION,
+};
+pub use query::CombinationQuery;
+pub use random::{
+    daily_combination, generate_all_combinations_shuffled, generate_random_change_for,
+    generate_random_change_for_with, generate_random_combination_of_size,
+    generate_random_combination_of_size_with, generate_random_combination_weighted,
+    generate_random_combination_weighted_with, generate_random_combination_with,
+    generate_random_combinations, generate_random_combinations_with, generate_random_multiset,
+    generate_random_multiset_with, generate_random_purse, generate_random_purse_with,
+    generate_weighted_random_combination, generate_weighted_random_combination_with, random_stream,
+    random_stream_with, sample_stratified, sample_stratified_with, CirculationWeighted,
+    CoinDistribution, CoinWeights, EmptyBucketError, NaiveDate, ProbabilityError, RandomOptions,
+    RandomPurse, RngAlgorithm, RngAlgorithmParseError, ShuffledCombinations, UniformCombination,
+};
+pub use register::{CashDrawer, DrawerLine, DrawerReport, SaleError};
+pub use rounding::{round_for_cash, RoundingStrategy};
+pub use savings::{Cadence, PiggyBank, SavingsDeposit, SavingsProgress};
+pub use schema::schema;
+pub use search::{closest_combination, combinations_with_value, combinations_with_value_counts};
+pub use simulation::{simulate, simulate_with, SimulationReport};
+pub use specs::{total_weight, CoinSpecs};
+pub use stats::{group_by_value, group_by_value_of, stats, CombinationStats};
+pub use transactions::{Operation, Transaction, TransactionLog};
+pub use vending::{VendingError, VendingMachine, VendingState};
+pub use wallet::{
+    ReplayFailure, SnapshotId, UnknownSnapshotError, Wallet, WalletEvent, WalletManager,
+    WalletManagerError, WalletReplayError, WalletSnapshot,
+};
+
 // Derive traits automatically:
 // - Debug: allows printing with {:?}
 // - Clone: allows creating copies of the enum
 // - Copy: allows copying the value instead of moving it
-// - PartialEq: allows comparing coins with == and !=
+// - PartialEq/Eq: allows comparing coins with == and !=
+// - Hash: allows using coins as HashMap/HashSet keys
 // - Serialize: allows converting to JSON for web API responses
-#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+// - JsonSchema: describes the serialized form (variant names) for /schema
+// - TS: generates a TypeScript definition (bindings/Coin.d.ts) during cargo test
+// (Deserialize is implemented by hand further down to accept flexible input,
+// and PartialOrd/Ord are implemented by hand to order coins by cent value)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "Coin.d.ts")]
 pub enum Coin {
     Penny,
     Nickel,
@@ -28,14 +138,29 @@ pub enum Coin {
 }
 
 impl Coin {
+    // Number of coin variants
+    // Use this instead of hardcoding 4 so code keeps working if coins are added
+    pub const COUNT: usize = Coin::ALL.len();
+
+    // Every variant in ascending value order
+    // The exhaustive match in the tests fails to compile if a variant is missing
+    const ALL: [Coin; 4] = [Coin::Penny, Coin::Nickel, Coin::Dime, Coin::Quarter];
+
     // Associated function (like a static method) that returns all coin types
-    // Returns an array of all 4 coins
-    pub fn all() -> [Coin; 4] {
-        [Coin::Penny, Coin::Nickel, Coin::Dime, Coin::Quarter]
+    // Returns an array of all Coin::COUNT coins
+    pub fn all() -> [Coin; Coin::COUNT] {
+        Coin::ALL
+    }
+
+    // Returns an iterator over every coin type, in ascending value order
+    // The iterator knows its length and can be reversed:
+    //   Coin::iter().rev() yields Quarter, Dime, Nickel, Penny
+    pub fn iter() -> impl ExactSizeIterator<Item = Coin> + DoubleEndedIterator + Clone {
+        Coin::ALL.into_iter()
     }
 
     // Method that returns the value of a coin in cents
-    pub fn value_in_cents(&self) -> u8 {
+    pub fn value_in_cents(&self) -> u64 {
         match self {
             Coin::Penny => 1,
             Coin::Nickel => 5,
@@ -43,77 +168,276 @@ impl Coin {
             Coin::Quarter => 25,
         }
     }
+
+    // Inverse of value_in_cents(): maps 1/5/10/25 back to a coin
+    // Returns None for any value that isn't a coin denomination
+    pub fn from_cents(cents: u64) -> Option<Coin> {
+        Coin::iter().find(|coin| coin.value_in_cents() == cents)
+    }
 }
 
-// Function that generates all possible subsets (power set) of coins
-// Returns a Vec (dynamic array) of Vecs, where each inner Vec is one combination
-pub fn generate_all_combinations() -> Vec<Vec<Coin>> {
-    let coins = Coin::all();
-    let total_coins = coins.len(); // 4 coins
-    let total_combinations = 1 << total_coins; // 2^4 = 16 combinations (bit shift left)
-
-    // Create a mutable vector to store all combinations
-    let mut combinations = Vec::new();
-
-    // Iterate through all numbers from 0 to 15
-    for i in 0..total_combinations {
-        // Create a new vector for this combination
-        let mut combination = Vec::new();
-
-        // Check each bit position (0 to 3)
-        // Note: We use the index j for bit manipulation (i >> j), not just array indexing
-        #[allow(clippy::needless_range_loop)]
-        for j in 0..total_coins {
-            // If the j-th bit is set in i, include the j-th coin
-            // Example: i=5 (binary: 0101)
-            //   j=0: (5 >> 0) & 1 = 1 & 1 = 1 ✓ include Penny
-            //   j=1: (5 >> 1) & 1 = 2 & 1 = 0 ✗ skip Nickel
-            //   j=2: (5 >> 2) & 1 = 1 & 1 = 1 ✓ include Dime
-            //   j=3: (5 >> 3) & 1 = 0 & 1 = 0 ✗ skip Quarter
-            if (i >> j) & 1 == 1 {
-                combination.push(coins[j]);
-            }
+// ============================================================================
+// Conversions between Coins, Cent Values and Strings
+// ============================================================================
+// - Coin -> u8:    From (always succeeds), u8::from(Coin::Dime) == 10
+// - u8 -> Coin:    TryFrom, fails with CoinParseError for non-coin values
+// - &str -> Coin:  TryFrom, same rules as FromStr ("dime", "10c", "10")
+
+impl From<Coin> for u8 {
+    fn from(coin: Coin) -> u8 {
+        // The largest coin is 25 cents, well within a u8
+        coin.value_in_cents() as u8
+    }
+}
+
+impl TryFrom<&str> for Coin {
+    type Error = CoinParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+// TryFrom<u8> is the trait-based version of Coin::from_cents()
+// It lets us write Coin::try_from(25) or let coin: Coin = 25u8.try_into()?
+impl TryFrom<u8> for Coin {
+    type Error = CoinParseError;
+
+    fn try_from(cents: u8) -> Result<Self, Self::Error> {
+        Coin::from_cents(u64::from(cents)).ok_or_else(|| CoinParseError {
+            input: cents.to_string(),
+        })
+    }
+}
+
+// Coins are ordered by their value in cents, so Penny < Nickel < Dime < Quarter.
+// We compare values explicitly rather than deriving Ord, which would silently
+// follow declaration order instead.
+impl PartialOrd for Coin {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Coin {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.value_in_cents().cmp(&other.value_in_cents())
+    }
+}
+
+// ============================================================================
+// Parsing Coins from Strings
+// ============================================================================
+
+// Error returned when a string cannot be parsed into a Coin
+// Keeps the original input so callers can show it back to the user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoinParseError {
+    input: String,
+}
+
+impl CoinParseError {
+    // The string that failed to parse
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// The coin the input was probably meant to be, for "did you mean"
+    /// hints: the one whose name (singular or plural) is fewest edits away,
+    /// if that is at most two
+    pub fn suggestion(&self) -> Option<Coin> {
+        let input = self.input.trim().to_lowercase();
+        COIN_SPELLINGS
+            .iter()
+            .map(|&(spelling, coin)| (edit_distance(&input, spelling), coin))
+            .filter(|&(distance, _)| distance <= 2)
+            .min_by_key(|&(distance, _)| distance)
+            .map(|(_, coin)| coin)
+    }
+}
+
+// Names accepted by FromStr, lowercase
+const COIN_SPELLINGS: [(&str, Coin); 8] = [
+    ("penny", Coin::Penny),
+    ("pennies", Coin::Penny),
+    ("nickel", Coin::Nickel),
+    ("nickels", Coin::Nickel),
+    ("dime", Coin::Dime),
+    ("dimes", Coin::Dime),
+    ("quarter", Coin::Quarter),
+    ("quarters", Coin::Quarter),
+];
+
+// Levenshtein distance: the fewest single-character insertions, deletions
+// and substitutions that turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = previous[j] + usize::from(ca != cb);
+            current.push(substitute.min(previous[j + 1] + 1).min(current[j] + 1));
         }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+impl fmt::Display for CoinParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown coin '{}': expected penny, nickel, dime, quarter or a cent value (1, 5, 10, 25)",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for CoinParseError {}
+
+// FromStr lets us write "quarter".parse::<Coin>()
+// Accepted spellings (case-insensitive, surrounding whitespace ignored):
+// - the coin name, singular or plural: "penny", "Pennies", "QUARTER"
+// - the cent value, optionally followed by "c" or "¢": "1", "5c", "25¢"
+impl FromStr for Coin {
+    type Err = CoinParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_lowercase();
+
+        // Strip an optional cent suffix so "25c" and "25¢" become "25"
+        let without_suffix = normalized
+            .strip_suffix('c')
+            .or_else(|| normalized.strip_suffix('¢'))
+            .unwrap_or(&normalized);
+
+        let coin = match COIN_SPELLINGS
+            .iter()
+            .find(|&&(spelling, _)| spelling == normalized)
+        {
+            Some(&(_, coin)) => Some(coin),
+            None => without_suffix
+                .parse::<u64>()
+                .ok()
+                .and_then(Coin::from_cents),
+        };
+
+        coin.ok_or_else(|| CoinParseError {
+            input: s.to_string(),
+        })
+    }
+}
+
+// ============================================================================
+// Deserializing Coins
+// ============================================================================
+// Serialize (derived above) always writes the variant name, e.g. "Quarter".
+// Deserialize is implemented by hand so clients can send either:
+// - a name, using the same rules as FromStr: "Quarter", "quarter", "25c"
+// - a numeric cent value: 25
+// Both forms round-trip back to the same Coin.
+
+struct CoinVisitor;
+
+impl Visitor<'_> for CoinVisitor {
+    type Value = Coin;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a coin name or a cent value (1, 5, 10, 25)")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Coin, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Coin, E> {
+        Coin::from_cents(value)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Coin, E> {
+        // JSON parsers may hand us signed integers; negatives are never coins
+        u64::try_from(value)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+            .and_then(|cents| self.visit_u64(cents))
+    }
+}
 
-        combinations.push(combination);
+impl<'de> Deserialize<'de> for Coin {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(CoinVisitor)
     }
+}
+
+// Builds the combination encoded by the bits of `i`
+// Bit j set means "include the j-th coin from Coin::iter()"
+// Example: i=5 (binary: 0101)
+//   j=0: (5 >> 0) & 1 = 1 & 1 = 1 ✓ include Penny
+//   j=1: (5 >> 1) & 1 = 2 & 1 = 0 ✗ skip Nickel
+//   j=2: (5 >> 2) & 1 = 1 & 1 = 1 ✓ include Dime
+//   j=3: (5 >> 3) & 1 = 0 & 1 = 0 ✗ skip Quarter
+// The decoding itself lives in the powerset module, which works for any
+// number of denominations
+pub(crate) fn combination_from_bits(i: usize) -> Combination {
+    powerset::subset_items(&Coin::ALL, i as u64)
+        .copied()
+        .collect()
+}
 
-    combinations
+// Function that generates all possible subsets (power set) of coins
+// Returns a Vec (dynamic array) of Combinations, one per subset
+// To stream combinations without building them all first, use combinations()
+pub fn generate_all_combinations() -> Vec<Combination> {
+    // combinations() walks the numbers from 0 to 15 (2^4) and decodes each
+    // one into the coins whose bits are set
+    combinations().collect()
 }
 
 // Helper function to calculate total value of a combination
-pub fn total_value(coins: &[Coin]) -> u32 {
+pub fn total_value(coins: &[Coin]) -> Money {
     // Use iterator methods:
     // - iter(): creates an iterator over the slice
-    // - map(): transforms each coin to its value
+    // - map(): converts each coin to its Money value
     // - sum(): adds up all values
-    coins.iter().map(|coin| coin.value_in_cents() as u32).sum()
+    coins.iter().map(|&coin| Money::from(coin)).sum()
+}
+
+// Like total_value(), but returns an error instead of panicking if the
+// total doesn't fit in Money
+pub fn checked_total_value(coins: &[Coin]) -> Result<Money, MoneyOverflowError> {
+    Money::checked_sum(coins.iter().map(|&coin| Money::from(coin)))
+}
+
+// Old signature of total_value(), kept so existing callers keep compiling
+#[deprecated(note = "use total_value(), which returns Money")]
+pub fn total_value_cents(coins: &[Coin]) -> u64 {
+    total_value(coins).cents()
+}
+
+// Sorts a list of combinations in place by total value (lowest first)
+// Works on Vec<Combination> as well as plain Vec<Vec<Coin>>
+// The sort is stable, so combinations with equal value keep their order
+pub fn sort_by_value<C: AsRef<[Coin]>>(combinations: &mut [C]) {
+    combinations.sort_by_key(|combination| total_value(combination.as_ref()));
 }
 
 // Function that generates a single random combination of coins
 // Returns a Vec containing 0-4 coins, randomly selected
+// Uses thread_rng(); to supply your own RNG, use
+// generate_random_combination_with(&mut rng)
 pub fn generate_random_combination() -> Vec<Coin> {
-    let coins = Coin::all();
-    let total_coins = coins.len(); // 4 coins
-    let total_combinations = 1 << total_coins; // 2^4 = 16 combinations
-
-    // Generate a random number from 0 to 15
-    let mut rng = rand::thread_rng();
-    let i = rng.gen_range(0..total_combinations);
-
-    // Create a vector for this random combination
-    let mut combination = Vec::new();
-
-    // Check each bit position using the same algorithm as generate_all_combinations()
-    #[allow(clippy::needless_range_loop)]
-    for j in 0..total_coins {
-        // If the j-th bit is set in i, include the j-th coin
-        if (i >> j) & 1 == 1 {
-            combination.push(coins[j]);
-        }
-    }
+    // Picks a random number from 0 to 15 and decodes it using the same
+    // algorithm as generate_all_combinations()
+    generate_random_combination_with(&mut rand::thread_rng())
+}
 
-    combination
+// Same as generate_random_combination(), but the same seed always gives the
+// same combination, so demos, tests and bug reports can be replayed
+// Reproducible within one build; StdRng's algorithm may change when the
+// rand crate is upgraded
+pub fn generate_random_combination_seeded(seed: u64) -> Vec<Coin> {
+    generate_random_combination_with(&mut StdRng::seed_from_u64(seed))
 }
 
 // ============================================================================
@@ -148,6 +472,43 @@ mod tests {
         assert_eq!(all_coins[3], Coin::Quarter);
     }
 
+    // ========================================================================
+    // Tests for Coin::iter() and Coin::COUNT
+    // ========================================================================
+
+    #[test]
+    fn test_coin_iter_matches_all() {
+        assert_eq!(Coin::iter().len(), Coin::COUNT);
+        assert_eq!(Coin::iter().collect::<Vec<_>>(), Coin::all().to_vec());
+    }
+
+    #[test]
+    fn test_coin_iter_reversed() {
+        let reversed: Vec<Coin> = Coin::iter().rev().collect();
+        assert_eq!(
+            reversed,
+            vec![Coin::Quarter, Coin::Dime, Coin::Nickel, Coin::Penny]
+        );
+    }
+
+    #[test]
+    fn test_coin_iter_is_exhaustive() {
+        // No wildcard arm: adding a variant makes this match fail to compile,
+        // which is the reminder to add it to Coin::ALL as well
+        fn position(coin: Coin) -> usize {
+            match coin {
+                Coin::Penny => 0,
+                Coin::Nickel => 1,
+                Coin::Dime => 2,
+                Coin::Quarter => 3,
+            }
+        }
+
+        for (index, coin) in Coin::iter().enumerate() {
+            assert_eq!(position(coin), index);
+        }
+    }
+
     // ========================================================================
     // Tests for Coin::value_in_cents()
     // ========================================================================
@@ -172,6 +533,170 @@ mod tests {
         assert_eq!(Coin::Quarter.value_in_cents(), 25);
     }
 
+    // ========================================================================
+    // Tests for Coin::from_cents() and TryFrom<u8>
+    // ========================================================================
+
+    #[test]
+    fn test_from_cents_valid_values() {
+        assert_eq!(Coin::from_cents(1), Some(Coin::Penny));
+        assert_eq!(Coin::from_cents(5), Some(Coin::Nickel));
+        assert_eq!(Coin::from_cents(10), Some(Coin::Dime));
+        assert_eq!(Coin::from_cents(25), Some(Coin::Quarter));
+    }
+
+    #[test]
+    fn test_from_cents_invalid_values() {
+        assert_eq!(Coin::from_cents(0), None);
+        assert_eq!(Coin::from_cents(2), None);
+        assert_eq!(Coin::from_cents(50), None);
+    }
+
+    #[test]
+    fn test_from_cents_inverts_value_in_cents() {
+        for coin in Coin::all() {
+            assert_eq!(Coin::from_cents(coin.value_in_cents()), Some(coin));
+        }
+    }
+
+    #[test]
+    fn test_try_from_u8() {
+        assert_eq!(Coin::try_from(10u8), Ok(Coin::Dime));
+
+        let err = Coin::try_from(3u8).unwrap_err();
+        assert_eq!(err.input(), "3");
+    }
+
+    #[test]
+    fn test_from_coin_for_u8() {
+        assert_eq!(u8::from(Coin::Penny), 1);
+        let cents: u8 = Coin::Quarter.into();
+        assert_eq!(cents, 25);
+    }
+
+    #[test]
+    fn test_try_from_str() {
+        assert_eq!(Coin::try_from("Nickel"), Ok(Coin::Nickel));
+        assert_eq!(Coin::try_from("25c"), Ok(Coin::Quarter));
+
+        let err = Coin::try_from("loonie").unwrap_err();
+        assert_eq!(err, "loonie".parse::<Coin>().unwrap_err());
+        assert_eq!(err.input(), "loonie");
+    }
+
+    #[test]
+    fn test_conversions_round_trip() {
+        for coin in Coin::iter() {
+            assert_eq!(Coin::try_from(u8::from(coin)), Ok(coin));
+            let name = format!("{:?}", coin);
+            assert_eq!(Coin::try_from(name.as_str()), Ok(coin));
+        }
+    }
+
+    // ========================================================================
+    // Tests for Coin::from_str()
+    // ========================================================================
+
+    #[test]
+    fn test_parse_coin_names_case_insensitive() {
+        assert_eq!("penny".parse::<Coin>(), Ok(Coin::Penny));
+        assert_eq!("Nickel".parse::<Coin>(), Ok(Coin::Nickel));
+        assert_eq!("DIME".parse::<Coin>(), Ok(Coin::Dime));
+        assert_eq!("  quarter ".parse::<Coin>(), Ok(Coin::Quarter));
+    }
+
+    #[test]
+    fn test_parse_coin_plural_names() {
+        assert_eq!("pennies".parse::<Coin>(), Ok(Coin::Penny));
+        assert_eq!("Quarters".parse::<Coin>(), Ok(Coin::Quarter));
+    }
+
+    #[test]
+    fn test_parse_coin_cent_values() {
+        assert_eq!("1".parse::<Coin>(), Ok(Coin::Penny));
+        assert_eq!("1c".parse::<Coin>(), Ok(Coin::Penny));
+        assert_eq!("5C".parse::<Coin>(), Ok(Coin::Nickel));
+        assert_eq!("10¢".parse::<Coin>(), Ok(Coin::Dime));
+        assert_eq!("25".parse::<Coin>(), Ok(Coin::Quarter));
+    }
+
+    #[test]
+    fn test_parse_coin_invalid_input() {
+        let err = "half-dollar".parse::<Coin>().unwrap_err();
+        assert_eq!(err.input(), "half-dollar");
+        assert!(err.to_string().contains("unknown coin 'half-dollar'"));
+
+        assert!("".parse::<Coin>().is_err());
+        assert!("2".parse::<Coin>().is_err());
+        assert!("c".parse::<Coin>().is_err());
+    }
+
+    #[test]
+    fn test_parse_coin_suggestion() {
+        let suggest = |input: &str| input.parse::<Coin>().unwrap_err().suggestion();
+        assert_eq!(suggest("qaurter"), Some(Coin::Quarter));
+        assert_eq!(suggest("Peny"), Some(Coin::Penny));
+        assert_eq!(suggest(" NICKLES "), Some(Coin::Nickel));
+        assert_eq!(suggest("dim"), Some(Coin::Dime));
+        assert_eq!(suggest("pennys"), Some(Coin::Penny));
+        assert_eq!(suggest("half-dollar"), None);
+        assert_eq!(suggest("doubloon"), None);
+        assert_eq!(suggest(""), None);
+        assert_eq!(suggest("2"), None);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("dime", "dime"), 0);
+        assert_eq!(edit_distance("", "dime"), 4);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("qaurter", "quarter"), 2);
+        assert_eq!(edit_distance("¢ent", "cent"), 1);
+    }
+
+    // ========================================================================
+    // Tests for Coin deserialization
+    // ========================================================================
+
+    #[test]
+    fn test_deserialize_coin_from_name() {
+        let coin: Coin = serde_json::from_str("\"Quarter\"").unwrap();
+        assert_eq!(coin, Coin::Quarter);
+
+        let coin: Coin = serde_json::from_str("\"dime\"").unwrap();
+        assert_eq!(coin, Coin::Dime);
+    }
+
+    #[test]
+    fn test_deserialize_coin_from_cents() {
+        let coins: Vec<Coin> = serde_json::from_str("[1, 5, 10, 25]").unwrap();
+        assert_eq!(coins, Coin::all().to_vec());
+    }
+
+    #[test]
+    fn test_deserialize_coin_mixed_formats() {
+        let coins: Vec<Coin> = serde_json::from_str("[\"Penny\", 25, \"10c\"]").unwrap();
+        assert_eq!(coins, vec![Coin::Penny, Coin::Quarter, Coin::Dime]);
+    }
+
+    #[test]
+    fn test_deserialize_coin_rejects_invalid_values() {
+        assert!(serde_json::from_str::<Coin>("3").is_err());
+        assert!(serde_json::from_str::<Coin>("-1").is_err());
+        assert!(serde_json::from_str::<Coin>("\"Euro\"").is_err());
+        assert!(serde_json::from_str::<Coin>("true").is_err());
+    }
+
+    #[test]
+    fn test_coin_json_round_trip() {
+        for coin in Coin::all() {
+            let json = serde_json::to_string(&coin).unwrap();
+            let back: Coin = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, coin);
+        }
+    }
+
     // ========================================================================
     // Tests for total_value()
     // ========================================================================
@@ -182,6 +707,12 @@ mod tests {
         assert_eq!(total_value(&empty), 0);
     }
 
+    #[test]
+    fn test_checked_total_value() {
+        assert_eq!(checked_total_value(&Coin::all()), Ok(Money::from_cents(41)));
+        assert_eq!(checked_total_value(&[]), Ok(Money::ZERO));
+    }
+
     #[test]
     fn test_total_value_single_coin() {
         let coins = vec![Coin::Quarter];
@@ -201,6 +732,17 @@ mod tests {
         assert_eq!(total_value(&coins), 10);
     }
 
+    #[test]
+    fn test_total_value_display() {
+        assert_eq!(total_value(&Coin::all()).to_string(), "$0.41");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_total_value_cents_shim() {
+        assert_eq!(total_value_cents(&Coin::all()), 41u64);
+    }
+
     // ========================================================================
     // Tests for generate_all_combinations()
     // ========================================================================
@@ -263,10 +805,10 @@ mod tests {
         // Check that no two combinations are the same
         for i in 0..combinations.len() {
             for j in (i + 1)..combinations.len() {
-                // Compare by converting to debug strings (simple approach)
+                // Compare by stable ID, which ignores coin order
                 assert_ne!(
-                    format!("{:?}", combinations[i]),
-                    format!("{:?}", combinations[j]),
+                    combinations[i].id(),
+                    combinations[j].id(),
                     "Combinations {} and {} should be different",
                     i,
                     j
@@ -314,6 +856,49 @@ mod tests {
         assert_eq!(coin2, coin3);
     }
 
+    // ========================================================================
+    // Tests for ordering, hashing and sort_by_value()
+    // ========================================================================
+
+    #[test]
+    fn test_coin_ordering_follows_value() {
+        assert!(Coin::Penny < Coin::Nickel);
+        assert!(Coin::Nickel < Coin::Dime);
+        assert!(Coin::Dime < Coin::Quarter);
+
+        let mut coins = vec![Coin::Quarter, Coin::Penny, Coin::Dime, Coin::Nickel];
+        coins.sort();
+        assert_eq!(coins, Coin::all().to_vec());
+        assert_eq!(coins.iter().max(), Some(&Coin::Quarter));
+    }
+
+    #[test]
+    fn test_coin_as_map_keys() {
+        use std::collections::{BTreeMap, HashMap};
+
+        let mut counts: HashMap<Coin, u32> = HashMap::new();
+        for coin in [Coin::Dime, Coin::Penny, Coin::Dime] {
+            *counts.entry(coin).or_insert(0) += 1;
+        }
+        assert_eq!(counts[&Coin::Dime], 2);
+        assert_eq!(counts[&Coin::Penny], 1);
+
+        let ordered: BTreeMap<Coin, u32> = counts.into_iter().collect();
+        let keys: Vec<Coin> = ordered.keys().copied().collect();
+        assert_eq!(keys, vec![Coin::Penny, Coin::Dime]);
+    }
+
+    #[test]
+    fn test_sort_by_value() {
+        let mut combinations = generate_all_combinations();
+        sort_by_value(&mut combinations);
+
+        let values: Vec<Money> = combinations.iter().map(|c| total_value(c)).collect();
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(combinations[0].is_empty());
+        assert_eq!(total_value(&combinations[15]), 41);
+    }
+
     // ========================================================================
     // Tests for generate_random_combination()
     // ========================================================================
@@ -363,13 +948,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_seeded_random_combination_is_reproducible() {
+        for seed in [0, 1, 42, u64::MAX] {
+            assert_eq!(
+                generate_random_combination_seeded(seed),
+                generate_random_combination_seeded(seed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_seeded_random_combination_varies_with_seed() {
+        let mut ids: Vec<u64> = (0..50)
+            .map(|seed| Combination::from(generate_random_combination_seeded(seed)).id())
+            .collect();
+        ids.sort();
+        ids.dedup();
+        assert!(ids.len() >= 2, "Different seeds should give variety");
+    }
+
     #[test]
     fn test_random_combination_produces_variety() {
         // Generate multiple combinations and check they're not all identical
         let mut combinations = Vec::new();
         for _ in 0..50 {
             let combination = generate_random_combination();
-            combinations.push(format!("{:?}", combination));
+            combinations.push(combination);
         }
 
         // Remove duplicates by sorting and deduping
@@ -0,0 +1,242 @@
+// ============================================================================
+// LOCALE MODULE: Locale-Aware Currency Formatting
+// ============================================================================
+// Money's Display always renders plain US style ("$1234.56").
+// This module renders amounts the way a given locale expects:
+// - thousands separator: "," (en-US), "." (de-DE), space (fr-FR)
+// - decimal separator: "." or ","
+// - currency symbol before or after the number
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Money;
+
+/// Supported formatting locales
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    /// United States: $1,234.56
+    #[default]
+    EnUs,
+    /// United Kingdom: £1,234.56
+    EnGb,
+    /// Germany: 1.234,56 €
+    DeDe,
+    /// France: 1 234,56 €
+    FrFr,
+    /// Spain: 1234,56 € (no grouping below 10 000)
+    EsEs,
+}
+
+/// Where the currency symbol goes relative to the number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SymbolPosition {
+    /// "$1.00"
+    Before,
+    /// "1,00 €" (separated by a space)
+    After,
+}
+
+impl Locale {
+    /// Every supported locale
+    pub fn all() -> [Locale; 5] {
+        [
+            Locale::EnUs,
+            Locale::EnGb,
+            Locale::DeDe,
+            Locale::FrFr,
+            Locale::EsEs,
+        ]
+    }
+
+    /// BCP 47 tag, e.g. "en-US"
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::EnUs => "en-US",
+            Locale::EnGb => "en-GB",
+            Locale::DeDe => "de-DE",
+            Locale::FrFr => "fr-FR",
+            Locale::EsEs => "es-ES",
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Locale::EnUs => "$",
+            Locale::EnGb => "£",
+            Locale::DeDe | Locale::FrFr | Locale::EsEs => "€",
+        }
+    }
+
+    fn symbol_position(&self) -> SymbolPosition {
+        match self {
+            Locale::EnUs | Locale::EnGb => SymbolPosition::Before,
+            Locale::DeDe | Locale::FrFr | Locale::EsEs => SymbolPosition::After,
+        }
+    }
+
+    fn thousands_separator(&self) -> &'static str {
+        match self {
+            Locale::EnUs | Locale::EnGb => ",",
+            Locale::DeDe | Locale::EsEs => ".",
+            // French typesets a narrow no-break space between digit groups;
+            // a plain space keeps the output ASCII-friendly for terminals
+            Locale::FrFr => " ",
+        }
+    }
+
+    fn decimal_separator(&self) -> &'static str {
+        match self {
+            Locale::EnUs | Locale::EnGb => ".",
+            Locale::DeDe | Locale::FrFr | Locale::EsEs => ",",
+        }
+    }
+
+    // Spanish only groups digits from five-digit numbers upwards
+    fn min_grouping_digits(&self) -> usize {
+        match self {
+            Locale::EsEs => 5,
+            _ => 4,
+        }
+    }
+
+    /// Formats an amount according to this locale
+    pub fn format(&self, amount: Money) -> String {
+        let dollars = amount.as_dollars();
+        let whole = group_digits(
+            &dollars.whole.to_string(),
+            self.thousands_separator(),
+            self.min_grouping_digits(),
+        );
+        let number = format!("{}{}{:02}", whole, self.decimal_separator(), dollars.cents);
+
+        match self.symbol_position() {
+            SymbolPosition::Before => format!("{}{}", self.symbol(), number),
+            SymbolPosition::After => format!("{} {}", number, self.symbol()),
+        }
+    }
+}
+
+// Inserts `separator` between groups of three digits, counting from the right
+// Numbers shorter than `min_digits` are left ungrouped
+fn group_digits(digits: &str, separator: &str, min_digits: usize) -> String {
+    if digits.len() < min_digits {
+        return digits.to_string();
+    }
+
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Error returned for unrecognised locale tags
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocaleParseError {
+    input: String,
+}
+
+impl fmt::Display for LocaleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let supported: Vec<&str> = Locale::all().iter().map(|l| l.tag()).collect();
+        write!(
+            f,
+            "unknown locale '{}': expected one of {}",
+            self.input,
+            supported.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for LocaleParseError {}
+
+// Accepts "en-US", "en_us", "EN-us", ... (case-insensitive, '-' or '_')
+impl FromStr for Locale {
+    type Err = LocaleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().replace('_', "-");
+        Locale::all()
+            .into_iter()
+            .find(|locale| locale.tag().eq_ignore_ascii_case(&normalized))
+            .ok_or_else(|| LocaleParseError {
+                input: s.to_string(),
+            })
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.tag())
+    }
+}
+
+impl Money {
+    /// Formats this amount for a locale, e.g. "1.234,56 €" for de-DE
+    pub fn format_locale(self, locale: Locale) -> String {
+        locale.format(self)
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AMOUNT: Money = Money::from_cents(123_456);
+
+    #[test]
+    fn test_format_en_us() {
+        assert_eq!(AMOUNT.format_locale(Locale::EnUs), "$1,234.56");
+        assert_eq!(Money::from_cents(41).format_locale(Locale::EnUs), "$0.41");
+    }
+
+    #[test]
+    fn test_format_en_gb() {
+        assert_eq!(AMOUNT.format_locale(Locale::EnGb), "£1,234.56");
+    }
+
+    #[test]
+    fn test_format_de_de() {
+        assert_eq!(AMOUNT.format_locale(Locale::DeDe), "1.234,56 €");
+    }
+
+    #[test]
+    fn test_format_fr_fr() {
+        assert_eq!(AMOUNT.format_locale(Locale::FrFr), "1 234,56 €");
+    }
+
+    #[test]
+    fn test_format_es_es_groups_from_five_digits() {
+        assert_eq!(AMOUNT.format_locale(Locale::EsEs), "1234,56 €");
+        assert_eq!(
+            Money::from_cents(1_234_567).format_locale(Locale::EsEs),
+            "12.345,67 €"
+        );
+    }
+
+    #[test]
+    fn test_group_digits() {
+        assert_eq!(group_digits("0", ",", 4), "0");
+        assert_eq!(group_digits("999", ",", 4), "999");
+        assert_eq!(group_digits("1000", ",", 4), "1,000");
+        assert_eq!(group_digits("1234567", ",", 4), "1,234,567");
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!("en-US".parse::<Locale>(), Ok(Locale::EnUs));
+        assert_eq!("de_de".parse::<Locale>(), Ok(Locale::DeDe));
+        assert_eq!(" FR-fr ".parse::<Locale>(), Ok(Locale::FrFr));
+
+        let err = "xx-YY".parse::<Locale>().unwrap_err();
+        assert!(err.to_string().starts_with("unknown locale 'xx-YY'"));
+    }
+}
@@ -1,16 +1,89 @@
 // ============================================================================
 // MAIN PROGRAM: Coin Combinations Web API
 // ============================================================================
-// This file starts the web server for the coin combinations API
+// This file starts the web server for the coin combinations API, or runs a
+// one-off command:
+//
+//   coins flip [COUNT] [BIAS]   flip a coin COUNT times (default 1), landing
+//                               heads with probability BIAS (default 0.5)
+//
+// Optional environment variables:
+//   COINS_RNG=chacha8   portable generator for seeded draws (default "std")
+//   COINS_SEED=42       seed the server's shared RNG for reproducible runs
 
-use coins::web;
+use coins::web::{self, AppState};
+use coins::{flip_stats, flips, RngAlgorithm, Side};
 
 #[tokio::main]
 async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("flip") {
+        if let Err(e) = run_flip(&args[1..]) {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        }
+        return;
+    }
+
     let addr = "0.0.0.0:8080";
 
-    if let Err(e) = web::run_server(addr).await {
+    let state = match app_state_from_env() {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("Configuration error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = web::run_server_with_state(addr, state).await {
         eprintln!("Server error: {}", e);
         std::process::exit(1);
     }
 }
+
+// Builds the server state from COINS_RNG and COINS_SEED
+fn app_state_from_env() -> Result<AppState, String> {
+    let algorithm: RngAlgorithm = match std::env::var("COINS_RNG") {
+        Ok(name) => name.parse().map_err(|e| format!("COINS_RNG: {}", e))?,
+        Err(_) => RngAlgorithm::default(),
+    };
+    let state = match std::env::var("COINS_SEED") {
+        Ok(seed) => AppState::with_seed(
+            seed.parse()
+                .map_err(|_| format!("COINS_SEED: invalid seed '{}'", seed))?,
+        ),
+        Err(_) => AppState::new(),
+    };
+    Ok(state.with_rng_algorithm(algorithm))
+}
+
+// `coins flip [COUNT] [BIAS]`: prints the flips as H/T and a summary
+fn run_flip(args: &[String]) -> Result<(), String> {
+    let count: usize = match args.first() {
+        Some(count) => count
+            .parse()
+            .map_err(|_| format!("invalid count '{}'", count))?,
+        None => 1,
+    };
+    let bias: f64 = match args.get(1) {
+        Some(bias) => bias
+            .parse()
+            .map_err(|_| format!("invalid bias '{}'", bias))?,
+        None => 0.5,
+    };
+    if args.len() > 2 {
+        return Err("usage: coins flip [COUNT] [BIAS]".to_string());
+    }
+
+    let flips = flips(count, bias).map_err(|e| e.to_string())?;
+    let line: String = flips.iter().map(Side::letter).collect();
+    let stats = flip_stats(&flips);
+
+    println!("{}", line);
+    print!("heads: {} / {}", stats.heads, stats.count);
+    if let Some(side) = stats.longest_streak_side {
+        print!(", longest streak: {} {}", stats.longest_streak, side);
+    }
+    println!();
+    Ok(())
+}
@@ -0,0 +1,413 @@
+// ============================================================================
+// MONEY MODULE: Cent Amounts as a Dedicated Type
+// ============================================================================
+// `Money` wraps an amount in cents so amounts can't be confused with other
+// integers (indexes, counts, weights). Arithmetic never wraps silently:
+// the operators panic on overflow/underflow, and the checked_* methods
+// return None instead for callers that want to handle it.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+use ts_rs::TS;
+
+use crate::Coin;
+
+/// An amount of money in cents
+///
+/// Serializes as a plain integer number of cents.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    TS,
+)]
+#[serde(transparent)]
+// JSON carries cents as a plain number; ts-rs would otherwise map u64 to
+// bigint, which JSON.parse never produces
+#[ts(export, export_to = "Money.d.ts", type = "number")]
+pub struct Money(u64);
+
+impl Money {
+    /// Zero cents
+    pub const ZERO: Money = Money(0);
+
+    /// The largest amount Money can hold
+    pub const MAX: Money = Money(u64::MAX);
+
+    /// Creates an amount from a number of cents
+    pub const fn from_cents(cents: u64) -> Money {
+        Money(cents)
+    }
+
+    /// The amount in cents
+    pub const fn cents(self) -> u64 {
+        self.0
+    }
+
+    /// Addition that returns None on overflow
+    pub fn checked_add(self, other: Money) -> Option<Money> {
+        self.0.checked_add(other.0).map(Money)
+    }
+
+    /// Subtraction that returns None if the result would be negative
+    pub fn checked_sub(self, other: Money) -> Option<Money> {
+        self.0.checked_sub(other.0).map(Money)
+    }
+
+    /// Multiplication by a quantity that returns None on overflow
+    pub fn checked_mul(self, quantity: u64) -> Option<Money> {
+        self.0.checked_mul(quantity).map(Money)
+    }
+
+    /// Adds up amounts, failing instead of panicking if the total overflows
+    pub fn checked_sum<I: IntoIterator<Item = Money>>(
+        amounts: I,
+    ) -> Result<Money, MoneyOverflowError> {
+        amounts.into_iter().try_fold(Money::ZERO, |total, amount| {
+            total.checked_add(amount).ok_or(MoneyOverflowError)
+        })
+    }
+}
+
+/// Error returned when a total is too large for Money
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoneyOverflowError;
+
+impl fmt::Display for MoneyOverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "total is more than {}", Money::MAX)
+    }
+}
+
+impl std::error::Error for MoneyOverflowError {}
+
+// ============================================================================
+// Decimal Dollars
+// ============================================================================
+// Dollar amounts are parsed digit by digit into integer cents, never via f64,
+// so "1.37" is exactly 137 cents instead of 136.99999...
+
+/// A Money amount split into whole dollars and remaining cents
+///
+/// Displays as a plain decimal without a currency symbol, e.g. "1.37".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dollars {
+    pub whole: u64,
+    pub cents: u8,
+}
+
+impl fmt::Display for Dollars {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:02}", self.whole, self.cents)
+    }
+}
+
+/// Error returned when a dollar string cannot be parsed into Money
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoneyParseError {
+    input: String,
+    reason: &'static str,
+}
+
+impl MoneyParseError {
+    fn new(input: &str, reason: &'static str) -> Self {
+        MoneyParseError {
+            input: input.to_string(),
+            reason,
+        }
+    }
+
+    /// The string that failed to parse
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+impl fmt::Display for MoneyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid dollar amount '{}': {}", self.input, self.reason)
+    }
+}
+
+impl std::error::Error for MoneyParseError {}
+
+impl Money {
+    /// Parses a decimal dollar amount such as "1.37", "$1.37", "2" or ".05"
+    ///
+    /// At most two decimal places are accepted; negative amounts and
+    /// amounts that don't fit in cents are rejected.
+    pub fn from_dollars_str(s: &str) -> Result<Money, MoneyParseError> {
+        let trimmed = s.trim();
+        let number = trimmed.strip_prefix('$').unwrap_or(trimmed);
+
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(MoneyParseError::new(s, "no digits"));
+        }
+        if !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+        {
+            return Err(MoneyParseError::new(
+                s,
+                "expected digits and an optional decimal point",
+            ));
+        }
+        if fraction.len() > 2 {
+            return Err(MoneyParseError::new(s, "more than two decimal places"));
+        }
+
+        let overflow = || MoneyParseError::new(s, "amount too large");
+
+        let dollars: u64 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().map_err(|_| overflow())?
+        };
+        // Pad "5" to "50" so ".5" means 50 cents, not 5
+        let cents: u64 = format!("{:0<2}", fraction).parse().unwrap_or(0);
+
+        dollars
+            .checked_mul(100)
+            .and_then(|total| total.checked_add(cents))
+            .map(Money)
+            .ok_or_else(overflow)
+    }
+
+    /// The amount as whole dollars and cents
+    pub fn as_dollars(self) -> Dollars {
+        Dollars {
+            whole: self.0 / 100,
+            cents: (self.0 % 100) as u8,
+        }
+    }
+}
+
+// "1.37".parse::<Money>() is the same as Money::from_dollars_str("1.37")
+impl FromStr for Money {
+    type Err = MoneyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Money::from_dollars_str(s)
+    }
+}
+
+impl From<Coin> for Money {
+    fn from(coin: Coin) -> Money {
+        Money(coin.value_in_cents())
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        self.checked_add(other).expect("Money addition overflowed")
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        self.checked_sub(other)
+            .expect("Money subtraction would be negative")
+    }
+}
+
+impl Mul<u64> for Money {
+    type Output = Money;
+
+    fn mul(self, quantity: u64) -> Money {
+        self.checked_mul(quantity)
+            .expect("Money multiplication overflowed")
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, |total, amount| total + amount)
+    }
+}
+
+// Comparing against plain cent values keeps simple checks readable,
+// e.g. assert_eq!(total_value(&coins), 41)
+impl PartialEq<u64> for Money {
+    fn eq(&self, cents: &u64) -> bool {
+        self.0 == *cents
+    }
+}
+
+impl PartialOrd<u64> for Money {
+    fn partial_cmp(&self, cents: &u64) -> Option<std::cmp::Ordering> {
+        self.0.partial_cmp(cents)
+    }
+}
+
+// Displays as dollars and cents, e.g. "$0.41" or "$12.05"
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "${}", self.as_dollars())
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_formats_dollars_and_cents() {
+        assert_eq!(Money::ZERO.to_string(), "$0.00");
+        assert_eq!(Money::from_cents(41).to_string(), "$0.41");
+        assert_eq!(Money::from_cents(100).to_string(), "$1.00");
+        assert_eq!(Money::from_cents(1205).to_string(), "$12.05");
+    }
+
+    #[test]
+    fn test_operators() {
+        let a = Money::from_cents(25);
+        let b = Money::from_cents(10);
+        assert_eq!(a + b, Money::from_cents(35));
+        assert_eq!(a - b, Money::from_cents(15));
+        assert_eq!(b * 3, Money::from_cents(30));
+    }
+
+    #[test]
+    fn test_checked_operations() {
+        let max = Money::from_cents(u64::MAX);
+        assert_eq!(max.checked_add(Money::from_cents(1)), None);
+        assert_eq!(Money::ZERO.checked_sub(Money::from_cents(1)), None);
+        assert_eq!(max.checked_mul(2), None);
+        assert_eq!(
+            Money::from_cents(5).checked_mul(4),
+            Some(Money::from_cents(20))
+        );
+    }
+
+    #[test]
+    fn test_checked_sum() {
+        let amounts = [Money::from_cents(25), Money::from_cents(10)];
+        assert_eq!(Money::checked_sum(amounts), Ok(Money::from_cents(35)));
+        assert_eq!(Money::checked_sum([]), Ok(Money::ZERO));
+
+        let error = Money::checked_sum([Money::MAX, Money::from_cents(1)]).unwrap_err();
+        assert_eq!(error, MoneyOverflowError);
+        assert_eq!(
+            error.to_string(),
+            "total is more than $184467440737095516.15"
+        );
+    }
+
+    #[test]
+    fn test_amounts_beyond_u32() {
+        // Four billion quarters
+        let total = Money::from(Coin::Quarter) * 4_000_000_000;
+        assert_eq!(total.cents(), 100_000_000_000);
+        assert_eq!(total.to_string(), "$1000000000.00");
+        assert_eq!(Money::from_dollars_str("1000000000.00"), Ok(total));
+    }
+
+    #[test]
+    #[should_panic(expected = "Money subtraction would be negative")]
+    fn test_sub_underflow_panics() {
+        let _ = Money::from_cents(1) - Money::from_cents(2);
+    }
+
+    #[test]
+    fn test_sum_and_from_coin() {
+        let total: Money = Coin::iter().map(Money::from).sum();
+        assert_eq!(total, Money::from_cents(41));
+        assert_eq!(total, 41);
+        assert!(total > 40);
+    }
+
+    #[test]
+    fn test_from_dollars_str_valid() {
+        assert_eq!(Money::from_dollars_str("1.37"), Ok(Money::from_cents(137)));
+        assert_eq!(Money::from_dollars_str("$1.37"), Ok(Money::from_cents(137)));
+        assert_eq!(Money::from_dollars_str(" 2 "), Ok(Money::from_cents(200)));
+        assert_eq!(Money::from_dollars_str("0.5"), Ok(Money::from_cents(50)));
+        assert_eq!(Money::from_dollars_str(".05"), Ok(Money::from_cents(5)));
+        assert_eq!(Money::from_dollars_str("3."), Ok(Money::from_cents(300)));
+        assert_eq!("0.41".parse::<Money>(), Ok(Money::from_cents(41)));
+    }
+
+    #[test]
+    fn test_from_dollars_str_has_no_float_drift() {
+        // 0.29 * 100 is 28.999999999999996 in f64
+        assert_eq!(Money::from_dollars_str("0.29"), Ok(Money::from_cents(29)));
+        assert_eq!(Money::from_dollars_str("4.35"), Ok(Money::from_cents(435)));
+    }
+
+    #[test]
+    fn test_from_dollars_str_invalid() {
+        for input in [
+            "",
+            "$",
+            ".",
+            "abc",
+            "-1.00",
+            "1.234",
+            "1.2.3",
+            "1,00",
+            "999999999999999999.00",
+        ] {
+            let err = Money::from_dollars_str(input).unwrap_err();
+            assert_eq!(err.input(), input);
+        }
+        let err = Money::from_dollars_str("1.234").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid dollar amount '1.234': more than two decimal places"
+        );
+    }
+
+    #[test]
+    fn test_as_dollars() {
+        let dollars = Money::from_cents(1205).as_dollars();
+        assert_eq!(
+            dollars,
+            Dollars {
+                whole: 12,
+                cents: 5
+            }
+        );
+        assert_eq!(dollars.to_string(), "12.05");
+    }
+
+    #[test]
+    fn test_dollars_round_trip() {
+        for cents in [0, 1, 41, 100, 137, 99_999] {
+            let money = Money::from_cents(cents);
+            let text = money.as_dollars().to_string();
+            assert_eq!(Money::from_dollars_str(&text), Ok(money));
+        }
+    }
+
+    #[test]
+    fn test_serializes_as_cents() {
+        let json = serde_json::to_string(&Money::from_cents(41)).unwrap();
+        assert_eq!(json, "41");
+        let back: Money = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, Money::from_cents(41));
+    }
+}
@@ -0,0 +1,204 @@
+// ============================================================================
+// PARALLEL MODULE: Multi-threaded Enumeration (feature "parallel")
+// ============================================================================
+// The power set doubles with every denomination, so enumerating it on one
+// thread stops being practical long before 25 denominations. These helpers
+// split the index space into chunks and let rayon decode and filter them on
+// all cores. Results come back in index order, exactly as the sequential
+// versions produce them.
+//
+// Chunks are sized from 2^n: a few per thread, but never fewer than
+// MIN_CHUNK indices each. The four US coins only have 16 combinations, so
+// par_combinations() and par_run() stay on one task; PowerSet::par_iter()
+// and DenominationSet::par_subsets() are where the cores come in.
+
+use std::ops::Range;
+
+use rayon::prelude::*;
+
+use crate::powerset::{power_set_with_limit, subset_items};
+use crate::{
+    Combination, CombinationMask, CombinationQuery, DenominationSet, PowerSet, PowerSetError,
+};
+
+/// Fewest indices handed to one rayon task; below this, splitting costs more
+/// than it saves
+const MIN_CHUNK: usize = 1 << 12;
+
+/// Tasks per thread, so that a slow chunk doesn't leave the other cores idle
+const CHUNKS_PER_THREAD: usize = 4;
+
+/// Indices per rayon task for an index space of `total`
+fn chunk_len(total: usize) -> usize {
+    (total / (rayon::current_num_threads() * CHUNKS_PER_THREAD)).max(MIN_CHUNK)
+}
+
+/// `indices` as a chunked parallel range
+fn par_indices(indices: Range<u64>) -> impl IndexedParallelIterator<Item = u64> {
+    // Fits: power sets are capped at MAX_ITEMS, so 2^n fits in a usize
+    let range = indices.start as usize..indices.end as usize;
+    let chunk = chunk_len(range.len());
+    range
+        .into_par_iter()
+        .with_min_len(chunk)
+        .map(|index| index as u64)
+}
+
+fn par_masks() -> impl IndexedParallelIterator<Item = CombinationMask> {
+    par_indices(0..u64::from(CombinationMask::FULL.bits()) + 1)
+        .map(|bits| CombinationMask::from_bits(bits as u16).expect("bits within FULL"))
+}
+
+/// Parallel counterpart of combinations()
+pub fn par_combinations() -> impl IndexedParallelIterator<Item = Combination> {
+    par_masks().map(Combination::from)
+}
+
+impl CombinationQuery {
+    /// Parallel counterpart of run(); collects the matches in index order
+    pub fn par_run(self) -> Vec<Combination> {
+        par_masks()
+            .filter(|mask| self.matches_mask(*mask))
+            .map(Combination::from)
+            .collect()
+    }
+}
+
+impl<'a, T: Clone + Send + Sync> PowerSet<'a, T> {
+    /// Parallel counterpart of iterating the power set: the subsets not yet
+    /// yielded, in index order
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = Vec<T>> + 'a {
+        let (items, indices) = self.remaining();
+        par_indices(indices).map(move |index| subset_items(items, index).cloned().collect())
+    }
+}
+
+impl DenominationSet {
+    /// Every subset of the denominations, one coin of each, in index order
+    /// and in parallel; refuses sets longer than `max_items` like
+    /// power_set_with_limit()
+    pub fn par_subsets(
+        &self,
+        max_items: usize,
+    ) -> Result<impl IndexedParallelIterator<Item = Vec<u32>> + '_, PowerSetError> {
+        Ok(power_set_with_limit(self.values(), max_items)?.par_iter())
+    }
+
+    /// Total of every subset, in the same order as par_subsets(), without
+    /// building the subsets themselves
+    pub fn par_subset_values(
+        &self,
+        max_items: usize,
+    ) -> Result<impl IndexedParallelIterator<Item = u64> + '_, PowerSetError> {
+        let (values, indices) = power_set_with_limit(self.values(), max_items)?.remaining();
+        Ok(par_indices(indices)
+            .map(move |index| subset_items(values, index).map(|&d| u64::from(d)).sum()))
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_all_combinations, power_set, Coin};
+
+    /// 20 distinct denominations: 1, 2, 4, ..., 2^19
+    fn twenty_denominations() -> DenominationSet {
+        DenominationSet::new((0..20).map(|j| 1 << j)).unwrap()
+    }
+
+    // ========================================================================
+    // Parallel Enumeration Tests
+    // ========================================================================
+
+    #[test]
+    fn test_par_combinations_matches_sequential() {
+        let parallel: Vec<Combination> = par_combinations().collect();
+        assert_eq!(parallel, generate_all_combinations());
+    }
+
+    #[test]
+    fn test_par_combinations_is_indexed() {
+        assert_eq!(par_combinations().len(), 16);
+    }
+
+    #[test]
+    fn test_par_run_matches_run() {
+        let query = CombinationQuery::new()
+            .min_value(10)
+            .excludes(Coin::Nickel)
+            .max_coins(2);
+        let sequential: Vec<Combination> = query.run().collect();
+        assert_eq!(query.par_run(), sequential);
+    }
+
+    // ========================================================================
+    // Power Set Tests
+    // ========================================================================
+
+    #[test]
+    fn test_chunk_len_scales_with_the_power_set() {
+        assert_eq!(chunk_len(16), MIN_CHUNK);
+        let threads = rayon::current_num_threads();
+        assert_eq!(
+            chunk_len(1 << 25),
+            ((1 << 25) / (threads * 4)).max(MIN_CHUNK)
+        );
+    }
+
+    #[test]
+    fn test_power_set_par_iter_matches_sequential() {
+        let items: Vec<u32> = (1..=14).collect();
+        let set = power_set(&items).unwrap();
+        let parallel: Vec<Vec<u32>> = set.par_iter().collect();
+        assert_eq!(parallel, set.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_power_set_par_iter_skips_yielded_subsets() {
+        let items = ['a', 'b', 'c'];
+        let mut set = power_set(&items).unwrap();
+        set.next();
+        set.next_back();
+        let parallel: Vec<Vec<char>> = set.par_iter().collect();
+        assert_eq!(parallel, set.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_par_subsets_on_twenty_denominations() {
+        let set = twenty_denominations();
+        let subsets = set.par_subsets(20).unwrap();
+        assert_eq!(subsets.len(), 1 << 20);
+        let sums_match = subsets.enumerate().all(|(index, subset)| {
+            subset.iter().map(|&d| u64::from(d)).sum::<u64>() == index as u64
+        });
+        assert!(sums_match);
+    }
+
+    #[test]
+    fn test_par_subset_values_matches_sequential() {
+        let set = twenty_denominations();
+        let parallel: Vec<u64> = set.par_subset_values(20).unwrap().collect();
+        // With powers of two, subset i is worth exactly i
+        assert!(parallel.iter().enumerate().all(|(i, &v)| v == i as u64));
+
+        let custom = DenominationSet::new([1, 3, 4, 7, 12, 30]).unwrap();
+        let sequential: Vec<u64> = power_set(custom.values())
+            .unwrap()
+            .map(|s| s.iter().map(|&d| u64::from(d)).sum())
+            .collect();
+        let parallel: Vec<u64> = custom.par_subset_values(20).unwrap().collect();
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn test_par_subsets_respects_the_limit() {
+        let err = twenty_denominations().par_subsets(19).err().unwrap();
+        assert_eq!(err.items(), 20);
+        assert_eq!(err.max(), 19);
+        assert!(twenty_denominations().par_subset_values(19).is_err());
+    }
+}
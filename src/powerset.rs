@@ -0,0 +1,324 @@
+// ============================================================================
+// POWERSET MODULE: Subsets of Any Denomination List
+// ============================================================================
+// The coin generators are built on this engine, but it works over any slice:
+// the four US coins, a custom list of denominations, or anything Clone.
+//
+// Subset number i holds item j exactly when bit j of i is set, so a list of
+// n items has 2^n subsets. Indices are u64 and n is capped (20 by default,
+// never more than 63) so that 2^n can neither overflow nor quietly turn into
+// an enumeration that runs for days.
+
+use std::fmt;
+use std::iter::FusedIterator;
+#[cfg(feature = "parallel")]
+use std::ops::Range;
+
+/// Largest list power_set() accepts: 2^20 is about a million subsets
+pub const DEFAULT_MAX_ITEMS: usize = 20;
+
+/// Hard ceiling for power_set_with_limit(), so that 2^n fits in a u64 and
+/// every subset count fits in a usize
+pub const MAX_ITEMS: usize = if usize::BITS < 64 {
+    usize::BITS as usize - 1
+} else {
+    63
+};
+
+/// Error returned when a list has more items than the limit allows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PowerSetError {
+    len: usize,
+    max: usize,
+}
+
+impl PowerSetError {
+    /// Number of items that were passed in
+    pub fn items(&self) -> usize {
+        self.len
+    }
+
+    /// The limit that was exceeded
+    pub fn max(&self) -> usize {
+        self.max
+    }
+}
+
+impl fmt::Display for PowerSetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "too many denominations: {} (at most {} are supported)",
+            self.len, self.max
+        )
+    }
+}
+
+impl std::error::Error for PowerSetError {}
+
+/// Lazy iterator over every subset of a list, in index order
+#[derive(Debug, Clone)]
+pub struct PowerSet<'a, T> {
+    items: &'a [T],
+    front: u64,
+    back: u64,
+}
+
+/// All subsets of `items`, refusing lists longer than DEFAULT_MAX_ITEMS
+pub fn power_set<T: Clone>(items: &[T]) -> Result<PowerSet<'_, T>, PowerSetError> {
+    power_set_with_limit(items, DEFAULT_MAX_ITEMS)
+}
+
+/// All subsets of `items`, refusing lists longer than `max_items`
+/// (which is itself clamped to MAX_ITEMS)
+pub fn power_set_with_limit<T: Clone>(
+    items: &[T],
+    max_items: usize,
+) -> Result<PowerSet<'_, T>, PowerSetError> {
+    let max = max_items.min(MAX_ITEMS);
+    if items.len() > max {
+        return Err(PowerSetError {
+            len: items.len(),
+            max,
+        });
+    }
+
+    Ok(PowerSet {
+        items,
+        front: 0,
+        back: 1 << items.len(),
+    })
+}
+
+/// Decodes subset number `index` of `items`; bits beyond the list are ignored
+pub fn subset_at<T: Clone>(items: &[T], index: u64) -> Vec<T> {
+    subset_items(items, index).cloned().collect()
+}
+
+// The items of subset number `index`, for callers that collect them into
+// something other than a Vec
+pub(crate) fn subset_items<T>(items: &[T], index: u64) -> impl Iterator<Item = &T> {
+    items
+        .iter()
+        .enumerate()
+        .filter(move |(j, _)| (index >> j) & 1 == 1)
+        .map(|(_, item)| item)
+}
+
+/// Calls `f` with every subset of `items`, in index order, refusing lists
+/// longer than DEFAULT_MAX_ITEMS
+///
+/// Each subset is built in the same scratch buffer, so memory stays at one
+/// subset no matter how many there are.
+pub fn for_each_subset<T: Clone>(
+    items: &[T],
+    mut f: impl FnMut(&[T]),
+) -> Result<(), PowerSetError> {
+    let set = power_set(items)?;
+    let mut scratch = Vec::with_capacity(items.len());
+
+    for index in 0..set.total() {
+        scratch.clear();
+        scratch.extend(subset_items(items, index).cloned());
+        f(&scratch);
+    }
+    Ok(())
+}
+
+impl<T: Clone> PowerSet<'_, T> {
+    /// Total number of subsets, 2^n
+    pub fn total(&self) -> u64 {
+        1 << self.items.len()
+    }
+
+    /// Subset number `index`, or None if it is past the end
+    pub fn get(&self, index: u64) -> Option<Vec<T>> {
+        (index < self.total()).then(|| subset_at(self.items, index))
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<'a, T> PowerSet<'a, T> {
+    // The list and the indices not yet yielded, for the parallel iterators
+    pub(crate) fn remaining(&self) -> (&'a [T], Range<u64>) {
+        (self.items, self.front..self.back)
+    }
+}
+
+impl<T: Clone> Iterator for PowerSet<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.front == self.back {
+            return None;
+        }
+        let subset = subset_at(self.items, self.front);
+        self.front += 1;
+        Some(subset)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Fits: the length of the list is capped at MAX_ITEMS
+        let remaining = (self.back - self.front) as usize;
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Vec<T>> {
+        self.front = self.front.saturating_add(n as u64).min(self.back);
+        self.next()
+    }
+}
+
+impl<T: Clone> DoubleEndedIterator for PowerSet<'_, T> {
+    fn next_back(&mut self) -> Option<Vec<T>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(subset_at(self.items, self.back))
+    }
+}
+
+impl<T: Clone> ExactSizeIterator for PowerSet<'_, T> {}
+
+impl<T: Clone> FusedIterator for PowerSet<'_, T> {}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========================================================================
+    // Enumeration Tests
+    // ========================================================================
+
+    #[test]
+    fn test_empty_list_has_one_subset() {
+        let subsets: Vec<Vec<u32>> = power_set(&[]).unwrap().collect();
+        assert_eq!(subsets, vec![Vec::<u32>::new()]);
+    }
+
+    #[test]
+    fn test_three_denominations() {
+        let subsets: Vec<Vec<u32>> = power_set(&[1, 2, 5]).unwrap().collect();
+        assert_eq!(
+            subsets,
+            vec![
+                vec![],
+                vec![1],
+                vec![2],
+                vec![1, 2],
+                vec![5],
+                vec![1, 5],
+                vec![2, 5],
+                vec![1, 2, 5]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sizes_other_than_four() {
+        for n in [0, 1, 2, 3, 5, 8, 12] {
+            let items: Vec<usize> = (0..n).collect();
+            let mut set = power_set(&items).unwrap();
+            assert_eq!(set.len(), 1 << n);
+            assert_eq!(set.total(), 1 << n);
+            assert_eq!(set.next_back().unwrap(), items);
+        }
+    }
+
+    #[test]
+    fn test_matches_coin_generator() {
+        let subsets: Vec<Vec<crate::Coin>> = power_set(&crate::Coin::all()).unwrap().collect();
+        assert_eq!(subsets, crate::generate_all_combinations());
+    }
+
+    #[test]
+    fn test_reverse_and_nth() {
+        let items = [1, 5, 10, 25, 50, 100];
+        let mut set = power_set(&items).unwrap();
+        assert_eq!(set.next_back().unwrap(), items.to_vec());
+        assert_eq!(set.nth(3).unwrap(), vec![1, 5]);
+        assert_eq!(set.len(), 63 - 4);
+        assert_eq!(set.nth(1000), None);
+        assert_eq!(set.next(), None);
+    }
+
+    #[test]
+    fn test_get() {
+        let set = power_set(&['a', 'b', 'c']).unwrap();
+        assert_eq!(set.get(5), Some(vec!['a', 'c']));
+        assert_eq!(set.get(8), None);
+    }
+
+    #[test]
+    fn test_for_each_subset_visits_in_order() {
+        let mut seen = Vec::new();
+        for_each_subset(&[1, 2, 5], |subset| seen.push(subset.to_vec())).unwrap();
+        assert_eq!(seen, power_set(&[1, 2, 5]).unwrap().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_for_each_subset_aggregates_without_collecting() {
+        let items: Vec<u64> = (1..=16).collect();
+        let mut total = 0;
+        let mut count = 0;
+        for_each_subset(&items, |subset| {
+            total += subset.iter().sum::<u64>();
+            count += 1;
+        })
+        .unwrap();
+
+        // Each item appears in half of the 2^16 subsets
+        assert_eq!(count, 1 << 16);
+        assert_eq!(total, items.iter().sum::<u64>() << 15);
+    }
+
+    #[test]
+    fn test_for_each_subset_respects_limit() {
+        let items = vec![0u8; DEFAULT_MAX_ITEMS + 1];
+        let mut called = false;
+        assert!(for_each_subset(&items, |_| called = true).is_err());
+        assert!(!called);
+    }
+
+    // ========================================================================
+    // Guard Rail Tests
+    // ========================================================================
+
+    #[test]
+    fn test_default_limit() {
+        let items: Vec<u32> = (0..DEFAULT_MAX_ITEMS as u32).collect();
+        assert!(power_set(&items).is_ok());
+
+        let items: Vec<u32> = (0..=DEFAULT_MAX_ITEMS as u32).collect();
+        let error = power_set(&items).unwrap_err();
+        assert_eq!(error.items(), 21);
+        assert_eq!(error.max(), 20);
+        assert_eq!(
+            error.to_string(),
+            "too many denominations: 21 (at most 20 are supported)"
+        );
+    }
+
+    #[test]
+    fn test_custom_limit() {
+        assert!(power_set_with_limit(&[1, 2, 3], 2).is_err());
+        assert!(power_set_with_limit(&[1, 2, 3], 3).is_ok());
+    }
+
+    #[test]
+    fn test_limit_is_clamped_so_counts_cannot_overflow() {
+        let items = vec![0u8; 64];
+        let error = power_set_with_limit(&items, usize::MAX).unwrap_err();
+        assert_eq!(error.max(), MAX_ITEMS);
+
+        // 63 items is allowed: 2^63 subsets, counted without overflow
+        let set = power_set_with_limit(&items[..63], usize::MAX).unwrap();
+        assert_eq!(set.total(), 1 << 63);
+        assert_eq!(set.clone().next().unwrap(), Vec::<u8>::new());
+    }
+}
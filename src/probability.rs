@@ -0,0 +1,406 @@
+// ============================================================================
+// PROBABILITY MODULE: Exact Answers for Random Draws
+// ============================================================================
+// Closed-form probabilities for generate_random_combination(), worked out by
+// adding up the chances of the 16 combinations rather than by sampling:
+//
+//   probability_contains(Coin::Quarter)    // 0.5
+//   probability_value_at_least(30)         // 6/16 = 0.375
+//
+// These are the numbers simulate() should converge to. RandomOptions and
+// CoinWeights answer the same questions for their own distributions via
+// probability_of(). expected_value() and variance() describe the value of a
+// draw in cents, and are served at GET /stats/random.
+
+use crate::{all_combinations_cached, total_value, Coin, CoinWeights, RandomOptions};
+
+/// Probability that a uniformly random combination satisfies `predicate`
+pub fn probability_of(predicate: impl Fn(&[Coin]) -> bool) -> f64 {
+    RandomOptions::default()
+        .probability_of(predicate)
+        .expect("the default options allow every combination")
+}
+
+/// Probability that a random combination contains `coin`
+pub fn probability_contains(coin: Coin) -> f64 {
+    probability_of(|coins| coins.contains(&coin))
+}
+
+/// Probability that a random combination is worth at least `cents`
+pub fn probability_value_at_least(cents: u64) -> f64 {
+    probability_of(|coins| total_value(coins) >= cents)
+}
+
+/// Probability that a random combination is worth at most `cents`
+pub fn probability_value_at_most(cents: u64) -> f64 {
+    probability_of(|coins| total_value(coins) <= cents)
+}
+
+/// Probability that a random combination is worth exactly `cents`
+pub fn probability_value_exactly(cents: u64) -> f64 {
+    probability_of(|coins| total_value(coins) == cents)
+}
+
+/// Mean value in cents of a uniformly random combination
+pub fn expected_value() -> f64 {
+    RandomOptions::default()
+        .expected_value()
+        .expect("the default options allow every combination")
+}
+
+/// Variance in square cents of the value of a uniformly random combination
+pub fn variance() -> f64 {
+    RandomOptions::default()
+        .variance()
+        .expect("the default options allow every combination")
+}
+
+impl RandomOptions {
+    /// Whether `coins` is one of the combinations these options can draw
+    pub fn allows(&self, coins: &[Coin]) -> bool {
+        self.allows_size_and_value(coins.len(), total_value(coins).cents())
+    }
+
+    /// Whether a combination of `len` coins worth `value` passes these
+    /// options, for combinations of coins other than Coin
+    pub fn allows_size_and_value(&self, len: usize, value: u64) -> bool {
+        (self.allow_empty || len > 0)
+            && self.exact_coins.is_none_or(|k| len == k)
+            && self.min_coins.is_none_or(|min| len >= min)
+            && self.max_coins.is_none_or(|max| len <= max)
+            && self.min_value.is_none_or(|min| value >= min)
+            && self.max_value.is_none_or(|max| value <= max)
+    }
+
+    /// Probability that a combination drawn with these options satisfies
+    /// `predicate`, or None if no combination meets the options
+    ///
+    /// Every allowed combination is equally likely, so this is the share of
+    /// allowed combinations that satisfy the predicate.
+    pub fn probability_of(&self, predicate: impl Fn(&[Coin]) -> bool) -> Option<f64> {
+        let allowed = all_combinations_cached()
+            .iter()
+            .filter(|coins| self.allows(coins));
+        let (total, hits) = allowed.fold((0usize, 0usize), |(total, hits), coins| {
+            (total + 1, hits + usize::from(predicate(coins)))
+        });
+        (total > 0).then(|| hits as f64 / total as f64)
+    }
+
+    /// Mean value in cents of a combination drawn with these options, or
+    /// None if no combination meets the options
+    pub fn expected_value(&self) -> Option<f64> {
+        self.value_moments().map(|(mean, _)| mean)
+    }
+
+    /// Variance in square cents of the value of a combination drawn with
+    /// these options, or None if no combination meets the options
+    pub fn variance(&self) -> Option<f64> {
+        self.value_moments().map(|(_, variance)| variance)
+    }
+
+    /// Mean and population variance of the allowed combinations' values
+    fn value_moments(&self) -> Option<(f64, f64)> {
+        let values: Vec<f64> = all_combinations_cached()
+            .iter()
+            .filter(|coins| self.allows(coins))
+            .map(|coins| total_value(coins).cents() as f64)
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        Some((mean, variance))
+    }
+}
+
+impl CoinWeights {
+    /// Probability that `coins` is exactly the combination drawn
+    pub fn probability_of_combination(&self, coins: &[Coin]) -> f64 {
+        Coin::iter()
+            .map(|coin| {
+                let p = self.probability(coin);
+                if coins.contains(&coin) {
+                    p
+                } else {
+                    1.0 - p
+                }
+            })
+            .product()
+    }
+
+    /// Probability that a combination drawn with these weights satisfies
+    /// `predicate`
+    pub fn probability_of(&self, predicate: impl Fn(&[Coin]) -> bool) -> f64 {
+        all_combinations_cached()
+            .iter()
+            .filter(|coins| predicate(coins))
+            .map(|coins| self.probability_of_combination(coins))
+            .sum()
+    }
+
+    /// Mean value in cents of a combination drawn with these weights
+    pub fn expected_value(&self) -> f64 {
+        Coin::iter()
+            .map(|coin| coin.value_in_cents() as f64 * self.probability(coin))
+            .sum()
+    }
+
+    /// Variance in square cents of the value of a combination drawn with
+    /// these weights
+    ///
+    /// Coins are included independently, so this is the sum of each coin's
+    /// Bernoulli variance v²·p·(1 − p).
+    pub fn variance(&self) -> f64 {
+        Coin::iter()
+            .map(|coin| {
+                let v = coin.value_in_cents() as f64;
+                let p = self.probability(coin);
+                v * v * p * (1.0 - p)
+            })
+            .sum()
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulate_with;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-12,
+            "{actual} is not {expected}"
+        );
+    }
+
+    // ========================================================================
+    // Uniform draws
+    // ========================================================================
+
+    #[test]
+    fn test_every_coin_is_in_half_the_combinations() {
+        for coin in Coin::iter() {
+            assert_close(probability_contains(coin), 0.5);
+        }
+    }
+
+    #[test]
+    fn test_value_thresholds() {
+        assert_close(probability_value_at_least(0), 1.0);
+        assert_close(probability_value_at_least(42), 0.0);
+        // A quarter with a nickel or a dime: 30, 31, 35, 36, 40 and 41
+        assert_close(probability_value_at_least(30), 6.0 / 16.0);
+        assert_close(probability_value_at_most(0), 1.0 / 16.0);
+        assert_close(probability_value_at_most(41), 1.0);
+    }
+
+    #[test]
+    fn test_value_exactly() {
+        // Every combination has a different value
+        assert_close(probability_value_exactly(16), 1.0 / 16.0);
+        assert_close(probability_value_exactly(2), 0.0);
+    }
+
+    #[test]
+    fn test_at_least_and_at_most_are_complementary() {
+        for cents in 0..=42 {
+            assert_close(
+                probability_value_at_least(cents) + probability_value_at_most(cents),
+                1.0 + probability_value_exactly(cents),
+            );
+        }
+    }
+
+    #[test]
+    fn test_simulation_converges_to_exact_answers() {
+        let mut rng = StdRng::seed_from_u64(21);
+        let report = simulate_with(&mut rng, 40_000, &RandomOptions::default()).unwrap();
+        for coin in Coin::iter() {
+            assert!((report.coin_frequency[&coin] - probability_contains(coin)).abs() < 0.01);
+        }
+        assert!((report.empty_rate - probability_value_exactly(0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_uniform_moments_match_stats() {
+        let stats = crate::stats(all_combinations_cached()).unwrap();
+        assert_close(expected_value(), stats.mean);
+        assert_close(variance(), stats.variance);
+        assert_close(variance(), 187.75);
+    }
+
+    // ========================================================================
+    // RandomOptions
+    // ========================================================================
+
+    #[test]
+    fn test_options_moments() {
+        let single = RandomOptions {
+            exact_coins: Some(1),
+            ..Default::default()
+        };
+        // 1, 5, 10 and 25
+        assert_close(single.expected_value().unwrap(), 41.0 / 4.0);
+        let mean: f64 = 41.0 / 4.0;
+        let expected = [1.0, 5.0, 10.0, 25.0]
+            .iter()
+            .map(|v: &f64| (v - mean).powi(2))
+            .sum::<f64>()
+            / 4.0;
+        assert_close(single.variance().unwrap(), expected);
+
+        let non_empty = RandomOptions {
+            allow_empty: false,
+            ..Default::default()
+        };
+        // Same total spread over one fewer combination
+        assert_close(non_empty.expected_value().unwrap(), 328.0 / 15.0);
+
+        let impossible = RandomOptions {
+            allow_empty: false,
+            exact_coins: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(impossible.expected_value(), None);
+        assert_eq!(impossible.variance(), None);
+    }
+
+    #[test]
+    fn test_options_restrict_the_combinations() {
+        let non_empty = RandomOptions {
+            allow_empty: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            non_empty.probability_of(|coins| coins.is_empty()),
+            Some(0.0)
+        );
+        assert_close(
+            non_empty
+                .probability_of(|coins| coins.contains(&Coin::Dime))
+                .unwrap(),
+            8.0 / 15.0,
+        );
+
+        let pairs = RandomOptions {
+            exact_coins: Some(2),
+            ..Default::default()
+        };
+        assert_close(
+            pairs
+                .probability_of(|coins| total_value(coins) >= 30)
+                .unwrap(),
+            // Of 6, 11, 15, 26, 30 and 35
+            2.0 / 6.0,
+        );
+    }
+
+    #[test]
+    fn test_unsatisfiable_options_have_no_probability() {
+        let impossible = RandomOptions {
+            allow_empty: false,
+            exact_coins: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(impossible.probability_of(|_| true), None);
+    }
+
+    #[test]
+    fn test_allows_matches_what_is_drawn() {
+        let options = RandomOptions {
+            allow_empty: false,
+            exact_coins: Some(3),
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..100 {
+            assert!(options.allows(&options.generate_with(&mut rng).unwrap()));
+        }
+        assert!(!options.allows(&[]));
+    }
+
+    #[test]
+    fn test_value_range_options() {
+        let options = RandomOptions {
+            min_value: Some(10),
+            max_value: Some(30),
+            ..Default::default()
+        };
+        // 10, 11, 15, 16, 25, 26 and 30 cents
+        assert_eq!(options.expected_value(), Some(19.0));
+        assert_eq!(
+            options.probability_of(|coins| coins.contains(&Coin::Quarter)),
+            Some(3.0 / 7.0)
+        );
+        assert!(!options.allows(&[Coin::Nickel]));
+    }
+
+    // ========================================================================
+    // CoinWeights
+    // ========================================================================
+
+    #[test]
+    fn test_default_weights_match_uniform() {
+        let weights = CoinWeights::default();
+        for cents in [0, 10, 26, 41] {
+            assert_close(
+                weights.probability_of(|coins| total_value(coins) >= cents),
+                probability_value_at_least(cents),
+            );
+        }
+    }
+
+    #[test]
+    fn test_weighted_probabilities() {
+        let weights = CoinWeights::new(0.5)
+            .unwrap()
+            .with(Coin::Quarter, 0.1)
+            .unwrap();
+        assert_close(
+            weights.probability_of(|coins| coins.contains(&Coin::Quarter)),
+            0.1,
+        );
+        // Empty needs every coin left out
+        assert_close(weights.probability_of_combination(&[]), 0.125 * 0.9);
+        assert_close(weights.probability_of(|_| true), 1.0);
+    }
+
+    #[test]
+    fn test_default_weight_moments_match_uniform() {
+        let weights = CoinWeights::default();
+        assert_close(weights.expected_value(), expected_value());
+        assert_close(weights.variance(), variance());
+    }
+
+    #[test]
+    fn test_weighted_moments_match_enumeration() {
+        let weights = CoinWeights::circulation();
+        let value = |coins: &[Coin]| total_value(coins).cents() as f64;
+        let mean: f64 = all_combinations_cached()
+            .iter()
+            .map(|coins| value(coins) * weights.probability_of_combination(coins))
+            .sum();
+        let variance: f64 = all_combinations_cached()
+            .iter()
+            .map(|coins| (value(coins) - mean).powi(2) * weights.probability_of_combination(coins))
+            .sum();
+        assert!((weights.expected_value() - mean).abs() < 1e-9);
+        assert!((weights.variance() - variance).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_certain_weights_have_no_variance() {
+        let weights = CoinWeights::new(1.0).unwrap();
+        assert_close(weights.expected_value(), 41.0);
+        assert_close(weights.variance(), 0.0);
+    }
+}
@@ -0,0 +1,1867 @@
+// ============================================================================
+// PURSE MODULE: Coins with Quantities
+// ============================================================================
+// A combination holds each coin at most once. A Purse holds any number of
+// each coin, like a real pocket or coin jar.
+//
+// Serialized as a map from coin name to count, e.g. {"Penny": 3, "Dime": 2}.
+//
+// Purse::save() and Purse::load() keep a purse in a JSON or TOML file (chosen
+// by the extension) between runs, wrapped with a format version:
+//
+//   version = 1
+//
+//   [coins]
+//   Penny = 3
+//   Dime = 2
+
+use rand::Rng;
+use schemars::JsonSchema;
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::cmp::Ordering;
+use std::collections::{btree_map, BTreeMap};
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::iter::Sum;
+use std::ops::{AddAssign, Sub};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::csv_out::write_csv;
+use crate::denominations::gcd;
+use crate::{make_change_from, ChangeError, Coin, Money, MoneyOverflowError};
+
+/// A collection of coins, stored as a count per coin type
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "Purse.d.ts")]
+// A newtype (rather than a named field) so serde and ts-rs both treat the
+// purse as the map itself. Only coins with a non-zero count are stored, which
+// is why Deserialize is written by hand below.
+pub struct Purse(BTreeMap<Coin, u32>);
+
+impl Purse {
+    /// Creates an empty purse
+    pub fn new() -> Self {
+        Purse::default()
+    }
+
+    /// How many of `coin` the purse holds
+    pub fn count_of(&self, coin: Coin) -> u32 {
+        self.0.get(&coin).copied().unwrap_or(0)
+    }
+
+    /// Sets the number of `coin` in the purse
+    pub fn set_count(&mut self, coin: Coin, count: u32) {
+        if count == 0 {
+            self.0.remove(&coin);
+        } else {
+            self.0.insert(coin, count);
+        }
+    }
+
+    /// Puts `count` more of `coin` into the purse
+    ///
+    /// Panics if the purse would hold more than u32::MAX of one coin.
+    pub fn add(&mut self, coin: Coin, count: u32) {
+        let total = self
+            .count_of(coin)
+            .checked_add(count)
+            .expect("Purse coin count overflowed");
+        self.set_count(coin, total);
+    }
+
+    /// Takes `count` of `coin` out of the purse, or fails without changing
+    /// anything if the purse holds fewer than that
+    pub fn remove(&mut self, coin: Coin, count: u32) -> Result<(), NotEnoughCoinsError> {
+        let available = self.count_of(coin);
+        if available < count {
+            return Err(NotEnoughCoinsError {
+                coin,
+                requested: count,
+                available,
+            });
+        }
+        self.set_count(coin, available - count);
+        Ok(())
+    }
+
+    /// The coins held and how many of each, lowest value first; coins the
+    /// purse doesn't hold are skipped
+    pub fn iter(&self) -> impl Iterator<Item = (Coin, u32)> + '_ {
+        self.0.iter().map(|(&coin, &count)| (coin, count))
+    }
+
+    /// Every physical coin in the purse, lowest value first, so 2 pennies
+    /// and a dime give Penny, Penny, Dime
+    pub fn coins(&self) -> impl Iterator<Item = Coin> + '_ {
+        self.iter()
+            .flat_map(|(coin, count)| std::iter::repeat_n(coin, count as usize))
+    }
+
+    /// Number of coins in the purse, counting each physical coin
+    pub fn coin_count(&self) -> u64 {
+        self.0.values().map(|&count| u64::from(count)).sum()
+    }
+
+    /// Whether the purse holds no coins
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Total value of every coin in the purse
+    ///
+    /// Panics if the total overflows; see checked_total_value().
+    pub fn total_value(&self) -> Money {
+        self.checked_total_value().expect("Purse total overflowed")
+    }
+
+    /// Total value of every coin in the purse, or an error if it is too
+    /// large for Money
+    pub fn checked_total_value(&self) -> Result<Money, MoneyOverflowError> {
+        let mut subtotals = Vec::with_capacity(self.0.len());
+        for (&coin, &count) in &self.0 {
+            let subtotal = Money::from(coin)
+                .checked_mul(u64::from(count))
+                .ok_or(MoneyOverflowError)?;
+            subtotals.push(subtotal);
+        }
+        Money::checked_sum(subtotals)
+    }
+
+    /// Number of sub-purses, the product of (count + 1) over every coin,
+    /// or None if that does not fit in a u128
+    pub fn sub_purse_count(&self) -> Option<u128> {
+        self.0.values().try_fold(1u128, |total, &count| {
+            total.checked_mul(u128::from(count) + 1)
+        })
+    }
+
+    /// Every collection of coins that can be taken out of this purse,
+    /// from the empty purse up to the whole purse
+    ///
+    /// 3 pennies and 2 dimes give 4 x 3 = 12 sub-purses. The penny count
+    /// ticks fastest, then the next coin up, and so on.
+    pub fn sub_purses(&self) -> SubPurses {
+        SubPurses {
+            limits: self.0.iter().map(|(&coin, &count)| (coin, count)).collect(),
+            counts: vec![0; self.0.len()],
+            done: false,
+        }
+    }
+}
+
+// ============================================================================
+// SubPurses: the power set of a purse
+// ============================================================================
+
+/// Lazy iterator over the sub-purses of a purse, see Purse::sub_purses()
+#[derive(Debug, Clone)]
+pub struct SubPurses {
+    limits: Vec<(Coin, u32)>,
+    counts: Vec<u32>,
+    done: bool,
+}
+
+impl Iterator for SubPurses {
+    type Item = Purse;
+
+    fn next(&mut self) -> Option<Purse> {
+        if self.done {
+            return None;
+        }
+
+        let purse = self
+            .limits
+            .iter()
+            .zip(&self.counts)
+            .map(|(&(coin, _), &count)| (coin, count))
+            .collect();
+
+        // Advance the counts like an odometer whose wheels stop at the
+        // number of coins available
+        self.done = true;
+        for (count, &(_, limit)) in self.counts.iter_mut().zip(&self.limits) {
+            if *count < limit {
+                *count += 1;
+                self.done = false;
+                break;
+            }
+            *count = 0;
+        }
+
+        Some(purse)
+    }
+}
+
+impl std::iter::FusedIterator for SubPurses {}
+
+// Collect (coin, count) pairs into a purse; repeated coins are added up,
+// and like add() this panics if a count overflows u32
+impl FromIterator<(Coin, u32)> for Purse {
+    fn from_iter<I: IntoIterator<Item = (Coin, u32)>>(iter: I) -> Self {
+        let mut purse = Purse::new();
+        for (coin, count) in iter {
+            purse.add(coin, count);
+        }
+        purse
+    }
+}
+
+// Collect individual coins into a purse, counting repeats
+impl FromIterator<Coin> for Purse {
+    fn from_iter<I: IntoIterator<Item = Coin>>(iter: I) -> Self {
+        iter.into_iter().map(|coin| (coin, 1)).collect()
+    }
+}
+
+// Consume a purse as (coin, count) pairs, lowest value first
+impl IntoIterator for Purse {
+    type Item = (Coin, u32);
+    type IntoIter = btree_map::IntoIter<Coin, u32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+// Read the map entry by entry, so {"Penny": 0} gives an empty purse rather
+// than storing the zero, and keys naming the same coin ("penny", "Penny",
+// "1") add up instead of the last one winning
+impl<'de> Deserialize<'de> for Purse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(PurseVisitor)
+    }
+}
+
+struct PurseVisitor;
+
+impl<'de> Visitor<'de> for PurseVisitor {
+    type Value = Purse;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map from coin to count")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Purse, A::Error> {
+        let mut purse = Purse::new();
+        while let Some((coin, count)) = map.next_entry::<Coin, u32>()? {
+            let total = purse
+                .count_of(coin)
+                .checked_add(count)
+                .ok_or_else(|| de::Error::custom(format!("more than {} of {coin:?}", u32::MAX)))?;
+            purse.set_count(coin, total);
+        }
+        Ok(purse)
+    }
+}
+
+// ============================================================================
+// Arithmetic and comparison
+// ============================================================================
+
+impl Purse {
+    /// The coins left after taking out `other`, or an error naming the
+    /// first coin this purse has too few of
+    pub fn checked_sub(&self, other: &Purse) -> Result<Purse, NotEnoughCoinsError> {
+        let mut rest = self.clone();
+        for (coin, count) in other.iter() {
+            rest.remove(coin, count)?;
+        }
+        Ok(rest)
+    }
+}
+
+// Both purses' coins together; panics if a count overflows u32, like add()
+impl std::ops::Add for Purse {
+    type Output = Purse;
+
+    fn add(mut self, other: Purse) -> Purse {
+        self += &other;
+        self
+    }
+}
+
+impl std::ops::Add<&Purse> for &Purse {
+    type Output = Purse;
+
+    fn add(self, other: &Purse) -> Purse {
+        let mut sum = self.clone();
+        sum += other;
+        sum
+    }
+}
+
+impl AddAssign<&Purse> for Purse {
+    fn add_assign(&mut self, other: &Purse) {
+        for (coin, count) in other.iter() {
+            self.add(coin, count);
+        }
+    }
+}
+
+// Taking coins out can fail, so `a - b` is a Result, like checked_sub()
+impl Sub for Purse {
+    type Output = Result<Purse, NotEnoughCoinsError>;
+
+    fn sub(self, other: Purse) -> Self::Output {
+        self.checked_sub(&other)
+    }
+}
+
+impl Sub<&Purse> for &Purse {
+    type Output = Result<Purse, NotEnoughCoinsError>;
+
+    fn sub(self, other: &Purse) -> Self::Output {
+        self.checked_sub(other)
+    }
+}
+
+// Purses compare by total value. Two different purses worth the same (a
+// dime and two nickels) are neither less, greater nor equal, which keeps
+// the ordering consistent with ==. Even u32::MAX of every coin is worth
+// far less than Money::MAX, so total_value() can't panic here.
+impl PartialOrd for Purse {
+    fn partial_cmp(&self, other: &Purse) -> Option<Ordering> {
+        if self == other {
+            return Some(Ordering::Equal);
+        }
+        match self.total_value().cmp(&other.total_value()) {
+            Ordering::Equal => None,
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl Sum for Purse {
+    fn sum<I: Iterator<Item = Purse>>(iter: I) -> Purse {
+        iter.fold(Purse::new(), |sum, purse| sum + purse)
+    }
+}
+
+impl<'a> Sum<&'a Purse> for Purse {
+    fn sum<I: Iterator<Item = &'a Purse>>(iter: I) -> Purse {
+        iter.fold(Purse::new(), |mut sum, purse| {
+            sum += purse;
+            sum
+        })
+    }
+}
+
+// ============================================================================
+// Paying exactly
+// ============================================================================
+
+impl Purse {
+    /// Whether some of the coins in the purse add up to exactly `amount`
+    /// cents
+    pub fn can_pay_exactly(&self, amount: u64) -> bool {
+        make_change_from(self, amount).is_ok()
+    }
+
+    /// Takes the fewest coins worth exactly `amount` cents out of the purse
+    /// and returns them
+    ///
+    /// Fails without changing the purse if it is worth less than the amount
+    /// or no selection of its coins adds up exactly; see make_change_from().
+    pub fn pay_exactly(&mut self, amount: u64) -> Result<Purse, ChangeError> {
+        let payment = make_change_from(self, amount)?;
+        *self = self
+            .checked_sub(&payment)
+            .expect("the payment came from the purse");
+        Ok(payment)
+    }
+}
+
+// ============================================================================
+// Consolidating and exchanging
+// ============================================================================
+
+impl Purse {
+    /// Trades the coins for the fewest coins of the same total value, e.g.
+    /// five pennies for a nickel
+    ///
+    /// US coins are a canonical system, so largest-first is optimal here.
+    /// Panics if the purse would hold more than u32::MAX of one coin.
+    pub fn consolidate(&mut self) {
+        let mut remaining = self.total_value().cents();
+        let mut consolidated = Purse::new();
+        for coin in Coin::iter().rev() {
+            let count = remaining / coin.value_in_cents();
+            remaining %= coin.value_in_cents();
+            let count = u32::try_from(count).expect("Purse coin count overflowed");
+            consolidated.add(coin, count);
+        }
+        *self = consolidated;
+    }
+
+    /// Swaps as many `from` coins as can be traded exactly for `to` coins,
+    /// returning the number of `to` coins received
+    ///
+    /// Only whole swaps are made: dimes become quarters five at a time and
+    /// quarters become dimes two at a time, so an odd quarter is kept.
+    /// Panics if the purse would hold more than u32::MAX of `to`, leaving it
+    /// unchanged.
+    pub fn exchange(&mut self, from: Coin, to: Coin) -> u32 {
+        if from == to {
+            return 0;
+        }
+        let (from_value, to_value) = (from.value_in_cents(), to.value_in_cents());
+        // Every trade is `batch` from coins for `batch * from / to` to coins
+        let batch = to_value / gcd(from_value, to_value);
+        let traded = u64::from(self.count_of(from)) / batch * batch;
+        let received =
+            u32::try_from(traded * from_value / to_value).expect("Purse coin count overflowed");
+        // Checked before anything moves, so a panic can't lose the from coins
+        let to_count = self
+            .count_of(to)
+            .checked_add(received)
+            .expect("Purse coin count overflowed");
+
+        self.remove(from, traded as u32)
+            .expect("no more than the purse holds");
+        self.set_count(to, to_count);
+        received
+    }
+}
+
+// ============================================================================
+// Random draws
+// ============================================================================
+
+impl Purse {
+    /// Takes `n` coins out at random and returns them, like pulling coins
+    /// out of a jar without looking
+    ///
+    /// Every physical coin is equally likely to be picked, so a purse of 9
+    /// pennies and 1 quarter gives a quarter 10% of the time for n = 1. If
+    /// the purse holds fewer than `n` coins, all of them are taken.
+    pub fn draw(&mut self, n: u64) -> Purse {
+        self.draw_with(&mut rand::thread_rng(), n)
+    }
+
+    /// Like draw(), choosing the coins with `rng`
+    ///
+    /// Each coin's count is drawn in one go, from the hypergeometric
+    /// distribution of the coins not yet decided, so the cost grows with
+    /// the square root of `n` rather than with `n`.
+    pub fn draw_with<R: Rng + ?Sized>(&mut self, rng: &mut R, n: u64) -> Purse {
+        let mut population = self.coin_count();
+        if n >= population {
+            return std::mem::take(self);
+        }
+
+        let mut left = n;
+        let mut drawn = Purse::new();
+        for coin in Coin::iter() {
+            let count = u64::from(self.count_of(coin));
+            let taken = hypergeometric(rng, population, count, left);
+            population -= count;
+            left -= taken;
+            // Fits: taken is at most count, which came from a u32
+            self.set_count(coin, (count - taken) as u32);
+            drawn.set_count(coin, taken as u32);
+        }
+        drawn
+    }
+}
+
+/// Weights below this fraction of the mode's are left out of a draw
+const NEGLIGIBLE_WEIGHT: f64 = 1e-20;
+
+// Number of marked items among `draws` taken without replacement from
+// `population` items of which `successes` are marked
+fn hypergeometric<R: Rng + ?Sized>(
+    rng: &mut R,
+    population: u64,
+    successes: u64,
+    draws: u64,
+) -> u64 {
+    // Draw at most half the population, at most half of it marked; the
+    // other cases follow by symmetry
+    if draws > population / 2 {
+        return successes - hypergeometric(rng, population, successes, population - draws);
+    }
+    if successes > population / 2 {
+        return draws - hypergeometric(rng, population, population - successes, draws);
+    }
+    if draws == 0 || successes == 0 {
+        return 0;
+    }
+
+    // Walk out from the mode with the ratio of neighbouring probabilities,
+    // once to add up the weights and once to pick one, so nothing needs
+    // factorials and only about sqrt(draws) values are visited
+    let weights = HypergeometricWeights {
+        population,
+        successes,
+        draws,
+    };
+    let total: f64 = weights.visit(|_, _| false).1;
+    let mut target = rng.gen::<f64>() * total;
+    weights
+        .visit(|_, weight| {
+            target -= weight;
+            target < 0.0
+        })
+        .0
+}
+
+// Probabilities of a hypergeometric distribution with draws + successes
+// <= population, relative to the most likely value
+struct HypergeometricWeights {
+    population: u64,
+    successes: u64,
+    draws: u64,
+}
+
+impl HypergeometricWeights {
+    // Calls `stop` with each value and its weight, the mode first, then
+    // downwards, then upwards, until `stop` returns true or the weights
+    // become negligible. Returns the last value visited and the sum of the
+    // weights visited.
+    fn visit(&self, mut stop: impl FnMut(u64, f64) -> bool) -> (u64, f64) {
+        let (n, k, rest) = (
+            self.draws,
+            self.successes,
+            self.population - self.successes - self.draws,
+        );
+        // Computed in u128, since (n + 1)(k + 1) can exceed a u64
+        let mode =
+            ((u128::from(n) + 1) * (u128::from(k) + 1) / (u128::from(self.population) + 2)) as u64;
+        let highest = n.min(k);
+
+        let mut sum = 1.0;
+        if stop(mode, 1.0) {
+            return (mode, sum);
+        }
+        let mut last = mode;
+
+        // P(i - 1) / P(i) = i (rest + i) / ((k - i + 1)(n - i + 1))
+        let mut weight = 1.0;
+        for i in (1..=mode).rev() {
+            weight *= (i as f64 * (rest + i) as f64) / ((k - i + 1) as f64 * (n - i + 1) as f64);
+            if weight < NEGLIGIBLE_WEIGHT {
+                break;
+            }
+            sum += weight;
+            last = i - 1;
+            if stop(last, weight) {
+                return (last, sum);
+            }
+        }
+
+        // P(i + 1) / P(i) = (k - i)(n - i) / ((i + 1)(rest + i + 1))
+        let mut weight = 1.0;
+        for i in mode..highest {
+            weight *= ((k - i) as f64 * (n - i) as f64) / ((i + 1) as f64 * (rest + i + 1) as f64);
+            if weight < NEGLIGIBLE_WEIGHT {
+                break;
+            }
+            sum += weight;
+            last = i + 1;
+            if stop(last, weight) {
+                return (last, sum);
+            }
+        }
+        (last, sum)
+    }
+}
+
+// ============================================================================
+// Reconciliation
+// ============================================================================
+
+/// Expected and counted numbers of one coin type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "ReconciliationLine.d.ts")]
+pub struct ReconciliationLine {
+    pub coin: Coin,
+    pub expected: u32,
+    pub counted: u32,
+    /// Counted minus expected: positive for a surplus, negative for a
+    /// shortage
+    #[ts(type = "number")]
+    pub difference: i64,
+    /// The difference in cents
+    #[ts(type = "number")]
+    pub value_difference: i64,
+}
+
+/// How a count of coins compares with what was expected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "ReconciliationReport.d.ts")]
+pub struct ReconciliationReport {
+    /// One line per coin type, in ascending value order
+    pub lines: Vec<ReconciliationLine>,
+    /// Coins counted beyond those expected
+    pub surplus: Purse,
+    /// Coins expected but not counted
+    pub shortage: Purse,
+    /// Counted value minus expected value, in cents
+    #[ts(type = "number")]
+    pub value_delta: i64,
+    /// Whether every count matches
+    pub balanced: bool,
+}
+
+impl Purse {
+    /// Compares this purse, as counted, with the coins `expected`, e.g. a
+    /// cash drawer at the end of the day against its transaction log
+    ///
+    /// The value delta can be zero while the report is unbalanced, when a
+    /// dime was given out in place of two nickels.
+    pub fn reconcile(&self, expected: &Purse) -> ReconciliationReport {
+        let mut surplus = Purse::new();
+        let mut shortage = Purse::new();
+        let lines: Vec<ReconciliationLine> = Coin::iter()
+            .map(|coin| {
+                let (expected, counted) = (expected.count_of(coin), self.count_of(coin));
+                surplus.set_count(coin, counted.saturating_sub(expected));
+                shortage.set_count(coin, expected.saturating_sub(counted));
+                let difference = i64::from(counted) - i64::from(expected);
+                let value_difference = i64::try_from(coin.value_in_cents())
+                    .ok()
+                    .and_then(|cents| difference.checked_mul(cents))
+                    .expect("Reconciliation value difference overflowed");
+                ReconciliationLine {
+                    coin,
+                    expected,
+                    counted,
+                    difference,
+                    value_difference,
+                }
+            })
+            .collect();
+        ReconciliationReport {
+            value_delta: lines.iter().map(|line| line.value_difference).sum(),
+            balanced: surplus.is_empty() && shortage.is_empty(),
+            lines,
+            surplus,
+            shortage,
+        }
+    }
+}
+
+// ============================================================================
+// Capacity
+// ============================================================================
+
+/// Limits on how many coins a purse or cash drawer can hold
+///
+/// A till has one slot per coin type that takes only so many coins, and a
+/// coin box may also hold only so many in all. The default is unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capacity {
+    per_coin: BTreeMap<Coin, u32>,
+    total: Option<u64>,
+}
+
+impl Capacity {
+    /// No limits at all
+    pub fn unlimited() -> Self {
+        Capacity::default()
+    }
+
+    /// Also limits `coin` to at most `max` coins
+    pub fn with_coin_limit(mut self, coin: Coin, max: u32) -> Self {
+        self.per_coin.insert(coin, max);
+        self
+    }
+
+    /// Also limits the number of coins of all types together to `max`
+    pub fn with_total_limit(mut self, max: u64) -> Self {
+        self.total = Some(max);
+        self
+    }
+
+    /// Most of `coin` that fit, or None if there is no limit
+    pub fn coin_limit(&self, coin: Coin) -> Option<u32> {
+        self.per_coin.get(&coin).copied()
+    }
+
+    /// Most coins that fit in all, or None if there is no limit
+    pub fn total_limit(&self) -> Option<u64> {
+        self.total
+    }
+
+    /// Whether the coins in `purse` fit
+    pub fn fits(&self, purse: &Purse) -> bool {
+        self.overflow(&Purse::new(), purse).is_empty()
+    }
+
+    /// The coins from `incoming` that don't fit alongside those `held`
+    ///
+    /// Per-coin limits are applied first. If the total limit is then
+    /// exceeded, the lowest-value coins are the ones turned away.
+    pub fn overflow(&self, held: &Purse, incoming: &Purse) -> Purse {
+        let mut rejected = Purse::new();
+        let mut fitting = Vec::new();
+        for (coin, count) in incoming.iter() {
+            let room = self
+                .coin_limit(coin)
+                .map_or(u32::MAX, |max| max.saturating_sub(held.count_of(coin)));
+            let fits = count.min(room);
+            rejected.set_count(coin, count - fits);
+            fitting.push((coin, fits));
+        }
+
+        if let Some(max) = self.total {
+            let mut room = max.saturating_sub(held.coin_count());
+            for (coin, fits) in fitting.into_iter().rev() {
+                let kept = u64::from(fits).min(room);
+                room -= kept;
+                rejected.add(coin, fits - kept as u32);
+            }
+        }
+        rejected
+    }
+}
+
+impl Purse {
+    /// Puts `coins` into the purse if they all fit within `capacity`
+    ///
+    /// Otherwise fails without changing anything, and the error holds the
+    /// coins that don't fit; the rest could be added on their own.
+    pub fn add_within(&mut self, coins: &Purse, capacity: &Capacity) -> Result<(), OverflowError> {
+        let rejected = capacity.overflow(self, coins);
+        if !rejected.is_empty() {
+            return Err(OverflowError { rejected });
+        }
+        *self += coins;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// CSV
+// ============================================================================
+
+// One row of a purse CSV
+#[derive(Serialize, Deserialize)]
+struct PurseCsvRow {
+    coin: Coin,
+    count: u32,
+}
+
+impl Purse {
+    /// The purse as CSV: a `coin,count` header and one row per coin held,
+    /// in ascending value order
+    pub fn to_csv(&self) -> String {
+        write_csv(
+            &["coin", "count"],
+            self.iter().map(|(coin, count)| PurseCsvRow { coin, count }),
+        )
+    }
+
+    /// Reads a purse from CSV with `coin` and `count` columns, as written by
+    /// to_csv()
+    ///
+    /// Coins can be given by name or value ("Dime" or 10), in any order,
+    /// but each only once. Other columns are ignored. The error names the
+    /// line of the first row that can't be read.
+    pub fn from_csv(text: &str) -> Result<Purse, PurseCsvError> {
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        let headers = reader
+            .headers()
+            .map_err(|error| PurseCsvError::from_csv(error, 1))?
+            .clone();
+        for column in ["coin", "count"] {
+            if !headers.iter().any(|header| header == column) {
+                return Err(PurseCsvError {
+                    line: 1,
+                    message: format!("missing column '{}'", column),
+                });
+            }
+        }
+
+        let mut purse = Purse::new();
+        let mut seen = Vec::new();
+        let mut record = csv::StringRecord::new();
+        while reader
+            .read_record(&mut record)
+            .map_err(|error| PurseCsvError::from_csv(error, 1))?
+        {
+            let line = record.position().map_or(1, |position| position.line());
+            let row: PurseCsvRow = record
+                .deserialize(Some(&headers))
+                .map_err(|error| PurseCsvError::from_csv(error, line))?;
+            if seen.contains(&row.coin) {
+                return Err(PurseCsvError {
+                    line,
+                    message: format!("{:?} is listed more than once", row.coin),
+                });
+            }
+            seen.push(row.coin);
+            purse.set_count(row.coin, row.count);
+        }
+        Ok(purse)
+    }
+}
+
+/// Error returned when a purse CSV can't be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PurseCsvError {
+    /// Line of the offending row, from 1 (the header)
+    pub line: u64,
+    pub message: String,
+}
+
+impl PurseCsvError {
+    // `line` is used when the error doesn't carry a position
+    fn from_csv(error: csv::Error, line: u64) -> Self {
+        let line = error.position().map_or(line, |position| position.line());
+        let message = match error.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => err.to_string(),
+            _ => error.to_string(),
+        };
+        PurseCsvError { line, message }
+    }
+}
+
+impl fmt::Display for PurseCsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for PurseCsvError {}
+
+// ============================================================================
+// Purse files
+// ============================================================================
+
+/// Version written by Purse::save() and the only one Purse::load() reads
+pub const PURSE_FILE_VERSION: u32 = 1;
+
+/// On-disk layout of a purse file
+#[derive(Serialize, Deserialize)]
+struct PurseFile {
+    version: u32,
+    coins: Purse,
+}
+
+/// Just the version, read first so files from a newer release are reported
+/// as such rather than as malformed
+#[derive(Deserialize)]
+struct PurseFileVersion {
+    version: u32,
+}
+
+/// File format of a purse file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PurseFileFormat {
+    Json,
+    Toml,
+}
+
+impl PurseFileFormat {
+    /// TOML for a `.toml` extension, JSON for anything else
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => PurseFileFormat::Toml,
+            _ => PurseFileFormat::Json,
+        }
+    }
+}
+
+/// Error returned by Purse::save() and Purse::load()
+#[derive(Debug)]
+pub enum PurseFileError {
+    /// The file could not be read or written
+    Io { path: PathBuf, source: io::Error },
+    /// The file is not a valid purse file
+    Parse { path: PathBuf, message: String },
+    /// The file was written in a format version this release can't read
+    UnsupportedVersion { path: PathBuf, version: u32 },
+    /// The contents can't be written in the file's format, e.g. a value
+    /// above i64::MAX in TOML
+    Serialize { path: PathBuf, message: String },
+}
+
+impl fmt::Display for PurseFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PurseFileError::Io { path, source } => {
+                write!(f, "cannot access purse file {}: {source}", path.display())
+            }
+            PurseFileError::Parse { path, message } => {
+                write!(f, "invalid purse file {}: {message}", path.display())
+            }
+            PurseFileError::UnsupportedVersion { path, version } => write!(
+                f,
+                "purse file {} has version {version}, but only version {PURSE_FILE_VERSION} is supported",
+                path.display()
+            ),
+            PurseFileError::Serialize { path, message } => {
+                write!(f, "cannot write purse file {}: {message}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for PurseFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PurseFileError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Purse {
+    /// Writes the purse to `path`, as TOML if it ends in `.toml` and as
+    /// JSON otherwise
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), PurseFileError> {
+        write_purse_file(
+            path.as_ref(),
+            &PurseFile {
+                version: PURSE_FILE_VERSION,
+                coins: self.clone(),
+            },
+        )
+    }
+
+    /// Reads a purse written by save(), picking the format the same way
+    pub fn load(path: impl AsRef<Path>) -> Result<Purse, PurseFileError> {
+        read_purse_file(path.as_ref()).map(|file: PurseFile| file.coins)
+    }
+}
+
+/// Writes `file`, which must have a `version` field, to `path` as TOML or
+/// JSON by its extension
+///
+/// Shared with other files that extend the purse file layout, such as
+/// piggy banks, so Purse::load() can read their coins too.
+pub(crate) fn write_purse_file<T: Serialize>(path: &Path, file: &T) -> Result<(), PurseFileError> {
+    let text = match PurseFileFormat::from_path(path) {
+        PurseFileFormat::Json => serde_json::to_string_pretty(file).map_err(|e| e.to_string()),
+        PurseFileFormat::Toml => toml::to_string(file).map_err(|e| e.to_string()),
+    }
+    .map_err(|message| PurseFileError::Serialize {
+        path: path.to_path_buf(),
+        message,
+    })?;
+    // Written next to the target and renamed over it, so a failed write
+    // leaves the old file in place rather than a truncated one
+    let temp = temp_path_for(path);
+    std::fs::write(&temp, text)
+        .and_then(|()| std::fs::rename(&temp, path))
+        .map_err(|source| {
+            let _ = std::fs::remove_file(&temp);
+            PurseFileError::Io {
+                path: path.to_path_buf(),
+                source,
+            }
+        })
+}
+
+/// Hidden sibling of `path` that write_purse_file() writes first
+fn temp_path_for(path: &Path) -> PathBuf {
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(format!(".{}.tmp", std::process::id()));
+    path.with_file_name(name)
+}
+
+/// Reads a file written by write_purse_file(), checking its version first
+pub(crate) fn read_purse_file<T: for<'de> Deserialize<'de>>(
+    path: &Path,
+) -> Result<T, PurseFileError> {
+    let text = std::fs::read_to_string(path).map_err(|source| PurseFileError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let format = PurseFileFormat::from_path(path);
+    let parse_error = |message: String| PurseFileError::Parse {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let PurseFileVersion { version } = parse_purse_file(format, &text).map_err(parse_error)?;
+    if version != PURSE_FILE_VERSION {
+        return Err(PurseFileError::UnsupportedVersion {
+            path: path.to_path_buf(),
+            version,
+        });
+    }
+    parse_purse_file(format, &text).map_err(parse_error)
+}
+
+fn parse_purse_file<T: for<'de> Deserialize<'de>>(
+    format: PurseFileFormat,
+    text: &str,
+) -> Result<T, String> {
+    match format {
+        PurseFileFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+        PurseFileFormat::Toml => toml::from_str(text).map_err(|e| e.message().to_string()),
+    }
+}
+
+/// Error returned when taking more coins out of a purse than it holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotEnoughCoinsError {
+    pub coin: Coin,
+    pub requested: u32,
+    pub available: u32,
+}
+
+impl fmt::Display for NotEnoughCoinsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot remove {} {:?} from a purse holding {}",
+            self.requested, self.coin, self.available
+        )
+    }
+}
+
+impl std::error::Error for NotEnoughCoinsError {}
+
+/// Error returned when coins don't fit within a Capacity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverflowError {
+    /// The coins there is no room for
+    pub rejected: Purse,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.rejected.coin_count();
+        let noun = if count == 1 { "coin" } else { "coins" };
+        write!(
+            f,
+            "no room for {} {} worth {}",
+            count,
+            noun,
+            self.rejected.total_value()
+        )
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_empty_purse() {
+        let purse = Purse::new();
+        assert_eq!(purse.count_of(Coin::Penny), 0);
+        assert_eq!(purse.total_value(), 0);
+    }
+
+    #[test]
+    fn test_coin_count() {
+        let purse: Purse = [(Coin::Penny, 3), (Coin::Quarter, u32::MAX)]
+            .into_iter()
+            .collect();
+        assert_eq!(purse.coin_count(), 3 + u64::from(u32::MAX));
+        assert!(!purse.is_empty());
+        assert!(Purse::new().is_empty());
+        assert_eq!(Purse::new().coin_count(), 0);
+    }
+
+    #[test]
+    fn test_set_count_and_total_value() {
+        let mut purse = Purse::new();
+        purse.set_count(Coin::Quarter, 3);
+        purse.set_count(Coin::Penny, 2);
+        assert_eq!(purse.count_of(Coin::Quarter), 3);
+        assert_eq!(purse.total_value(), 77);
+
+        purse.set_count(Coin::Quarter, 0);
+        assert_eq!(purse, [(Coin::Penny, 2)].into_iter().collect());
+    }
+
+    #[test]
+    fn test_total_value_beyond_u32() {
+        let purse: Purse = Coin::iter().map(|coin| (coin, u32::MAX)).collect();
+        let expected = 41 * u64::from(u32::MAX);
+        assert_eq!(purse.total_value(), expected);
+        assert_eq!(purse.checked_total_value(), Ok(Money::from_cents(expected)));
+    }
+
+    #[test]
+    fn test_from_iterator_adds_repeated_coins() {
+        let purse: Purse = [(Coin::Dime, 2), (Coin::Dime, 3)].into_iter().collect();
+        assert_eq!(purse.count_of(Coin::Dime), 5);
+    }
+
+    #[test]
+    fn test_add_and_remove() {
+        let mut purse = Purse::new();
+        purse.add(Coin::Dime, 2);
+        purse.add(Coin::Dime, 3);
+        purse.add(Coin::Penny, 0);
+        assert_eq!(purse.count_of(Coin::Dime), 5);
+        assert_eq!(purse.iter().count(), 1);
+
+        assert_eq!(purse.remove(Coin::Dime, 4), Ok(()));
+        assert_eq!(purse.count_of(Coin::Dime), 1);
+        assert_eq!(purse.remove(Coin::Dime, 1), Ok(()));
+        assert!(purse.is_empty());
+    }
+
+    #[test]
+    fn test_remove_more_than_held() {
+        let mut purse: Purse = [(Coin::Quarter, 1)].into_iter().collect();
+        let error = purse.remove(Coin::Quarter, 3).unwrap_err();
+        assert_eq!(
+            error,
+            NotEnoughCoinsError {
+                coin: Coin::Quarter,
+                requested: 3,
+                available: 1,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "cannot remove 3 Quarter from a purse holding 1"
+        );
+        // Nothing was taken
+        assert_eq!(purse.count_of(Coin::Quarter), 1);
+        assert!(purse.remove(Coin::Penny, 1).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed")]
+    fn test_add_overflow_panics() {
+        let mut purse: Purse = [(Coin::Penny, u32::MAX)].into_iter().collect();
+        purse.add(Coin::Penny, 1);
+    }
+
+    // ========================================================================
+    // Arithmetic Tests
+    // ========================================================================
+
+    fn purse(counts: &[(Coin, u32)]) -> Purse {
+        counts.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_add_purses() {
+        let a = purse(&[(Coin::Penny, 2), (Coin::Dime, 1)]);
+        let b = purse(&[(Coin::Dime, 3), (Coin::Quarter, 1)]);
+        let expected = purse(&[(Coin::Penny, 2), (Coin::Dime, 4), (Coin::Quarter, 1)]);
+        assert_eq!(&a + &b, expected);
+        assert_eq!(a.clone() + b.clone(), expected);
+        assert_eq!((&a + &b).total_value(), a.total_value() + b.total_value());
+
+        let mut c = a.clone();
+        c += &b;
+        assert_eq!(c, expected);
+        assert_eq!(a.clone() + Purse::new(), a);
+    }
+
+    #[test]
+    fn test_sub_purses() {
+        let a = purse(&[(Coin::Penny, 2), (Coin::Dime, 4)]);
+        let b = purse(&[(Coin::Dime, 3)]);
+        assert_eq!(&a - &b, Ok(purse(&[(Coin::Penny, 2), (Coin::Dime, 1)])));
+        assert_eq!(a.clone() - a.clone(), Ok(Purse::new()));
+
+        let error = (b.clone() - a.clone()).unwrap_err();
+        assert_eq!(error.coin, Coin::Penny);
+        assert_eq!(error.available, 0);
+        assert_eq!(b.checked_sub(&a), Err(error));
+    }
+
+    #[test]
+    fn test_add_then_sub_round_trips() {
+        let a = purse(&[(Coin::Nickel, 7), (Coin::Quarter, 2)]);
+        let b = purse(&[(Coin::Penny, 1), (Coin::Nickel, 3)]);
+        assert_eq!(&(&a + &b) - &b, Ok(a));
+    }
+
+    #[test]
+    fn test_compare_by_value() {
+        let dime = purse(&[(Coin::Dime, 1)]);
+        let nickels = purse(&[(Coin::Nickel, 2)]);
+        let quarter = purse(&[(Coin::Quarter, 1)]);
+
+        assert!(dime < quarter);
+        assert!(quarter > nickels);
+        assert!(dime <= dime.clone());
+        // Same value, different coins: not comparable
+        assert_eq!(dime.partial_cmp(&nickels), None);
+        assert_eq!((dime <= nickels, dime >= nickels), (false, false));
+        assert_eq!(dime.total_value(), nickels.total_value());
+    }
+
+    #[test]
+    fn test_compare_huge_purses() {
+        let full: Purse = Coin::iter().map(|coin| (coin, u32::MAX)).collect();
+        let fewer_pennies = purse(&[
+            (Coin::Penny, u32::MAX - 1),
+            (Coin::Nickel, u32::MAX),
+            (Coin::Dime, u32::MAX),
+            (Coin::Quarter, u32::MAX),
+        ]);
+        assert!(fewer_pennies < full);
+        assert!(purse(&[(Coin::Penny, 1)]) < full);
+    }
+
+    #[test]
+    fn test_sum_purses() {
+        let purses = vec![
+            purse(&[(Coin::Penny, 1)]),
+            purse(&[(Coin::Penny, 2), (Coin::Dime, 1)]),
+            purse(&[(Coin::Quarter, 3)]),
+        ];
+        let expected = purse(&[(Coin::Penny, 3), (Coin::Dime, 1), (Coin::Quarter, 3)]);
+        assert_eq!(purses.iter().sum::<Purse>(), expected);
+        assert_eq!(purses.into_iter().sum::<Purse>(), expected);
+        assert_eq!(Vec::<Purse>::new().into_iter().sum::<Purse>(), Purse::new());
+    }
+
+    // ========================================================================
+    // Paying Tests
+    // ========================================================================
+
+    #[test]
+    fn test_can_pay_exactly() {
+        let quarters_and_dimes = purse(&[(Coin::Dime, 2), (Coin::Quarter, 3)]);
+        assert!(quarters_and_dimes.can_pay_exactly(45));
+        assert!(quarters_and_dimes.can_pay_exactly(0));
+        assert!(quarters_and_dimes.can_pay_exactly(95));
+        // Worth enough, but nothing adds up to it
+        assert!(!quarters_and_dimes.can_pay_exactly(30));
+        assert!(!quarters_and_dimes.can_pay_exactly(5));
+        // Worth too little
+        assert!(!quarters_and_dimes.can_pay_exactly(100));
+    }
+
+    #[test]
+    fn test_pay_exactly_takes_the_coins() {
+        let mut wallet = purse(&[(Coin::Penny, 4), (Coin::Nickel, 1), (Coin::Quarter, 2)]);
+        let payment = wallet.pay_exactly(31).unwrap();
+        assert_eq!(
+            payment,
+            purse(&[(Coin::Penny, 1), (Coin::Nickel, 1), (Coin::Quarter, 1)])
+        );
+        assert_eq!(wallet, purse(&[(Coin::Penny, 3), (Coin::Quarter, 1)]));
+        assert_eq!(wallet.pay_exactly(0), Ok(Purse::new()));
+    }
+
+    #[test]
+    fn test_pay_exactly_failure_keeps_the_coins() {
+        let mut wallet = purse(&[(Coin::Quarter, 2)]);
+        assert_eq!(
+            wallet.pay_exactly(30),
+            Err(ChangeError::NoExactChange {
+                amount: 30,
+                closest_below: 25
+            })
+        );
+        assert_eq!(
+            wallet.pay_exactly(75),
+            Err(ChangeError::Insufficient {
+                amount: 75,
+                available: 50
+            })
+        );
+        assert_eq!(wallet, purse(&[(Coin::Quarter, 2)]));
+    }
+
+    // ========================================================================
+    // Consolidation Tests
+    // ========================================================================
+
+    #[test]
+    fn test_consolidate() {
+        let mut jar = purse(&[(Coin::Penny, 57), (Coin::Nickel, 9), (Coin::Dime, 3)]);
+        jar.consolidate();
+        // 57 + 45 + 30 = 132 cents
+        assert_eq!(
+            jar,
+            purse(&[(Coin::Penny, 2), (Coin::Nickel, 1), (Coin::Quarter, 5)])
+        );
+
+        let mut empty = Purse::new();
+        empty.consolidate();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_consolidate_keeps_value_and_never_adds_coins() {
+        for jar in [
+            purse(&[(Coin::Dime, 3)]),
+            purse(&[(Coin::Penny, 4), (Coin::Quarter, 7)]),
+            purse(&[(Coin::Nickel, 1_000_001)]),
+        ] {
+            let mut consolidated = jar.clone();
+            consolidated.consolidate();
+            assert_eq!(consolidated.total_value(), jar.total_value());
+            assert!(consolidated.coin_count() <= jar.coin_count());
+        }
+    }
+
+    #[test]
+    fn test_exchange_small_for_large() {
+        let mut jar = purse(&[(Coin::Penny, 23), (Coin::Dime, 12)]);
+        assert_eq!(jar.exchange(Coin::Penny, Coin::Nickel), 4);
+        // Dimes go for quarters five at a time
+        assert_eq!(jar.exchange(Coin::Dime, Coin::Quarter), 4);
+        assert_eq!(
+            jar,
+            purse(&[
+                (Coin::Penny, 3),
+                (Coin::Nickel, 4),
+                (Coin::Dime, 2),
+                (Coin::Quarter, 4)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_exchange_large_for_small() {
+        let mut jar = purse(&[(Coin::Quarter, 3)]);
+        // Quarters go for dimes two at a time
+        assert_eq!(jar.exchange(Coin::Quarter, Coin::Dime), 5);
+        assert_eq!(jar, purse(&[(Coin::Dime, 5), (Coin::Quarter, 1)]));
+        assert_eq!(jar.exchange(Coin::Quarter, Coin::Penny), 25);
+        assert_eq!(jar.exchange(Coin::Dime, Coin::Dime), 0);
+        assert_eq!(jar.exchange(Coin::Nickel, Coin::Penny), 0);
+        assert_eq!(jar, purse(&[(Coin::Penny, 25), (Coin::Dime, 5)]));
+    }
+
+    #[test]
+    fn test_exchange_overflow_keeps_the_purse() {
+        let before = purse(&[(Coin::Penny, u32::MAX), (Coin::Quarter, 1)]);
+        let mut jar = before.clone();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            jar.exchange(Coin::Quarter, Coin::Penny)
+        }));
+        assert!(result.is_err());
+        assert_eq!(jar, before);
+    }
+
+    // ========================================================================
+    // Random draw Tests
+    // ========================================================================
+
+    #[test]
+    fn test_purse_draw_moves_coins() {
+        let mut purse: Purse = [(Coin::Penny, 5), (Coin::Dime, 3), (Coin::Quarter, 2)]
+            .into_iter()
+            .collect();
+        let before = purse.clone();
+        let drawn = purse.draw_with(&mut StdRng::seed_from_u64(3), 4);
+
+        assert_eq!(drawn.coin_count(), 4);
+        assert_eq!(purse.coin_count(), 6);
+        for coin in Coin::iter() {
+            assert_eq!(
+                drawn.count_of(coin) + purse.count_of(coin),
+                before.count_of(coin)
+            );
+        }
+    }
+
+    #[test]
+    fn test_purse_draw_weights_by_count() {
+        let jar: Purse = [(Coin::Penny, 9), (Coin::Quarter, 1)].into_iter().collect();
+        let mut rng = StdRng::seed_from_u64(4);
+        let trials = 10_000;
+        let quarters = (0..trials)
+            .filter(|_| jar.clone().draw_with(&mut rng, 1).count_of(Coin::Quarter) == 1)
+            .count();
+        let rate = quarters as f64 / trials as f64;
+        assert!((rate - 0.1).abs() < 0.01, "quarter rate {rate}");
+    }
+
+    #[test]
+    fn test_purse_draw_is_hypergeometric() {
+        // Two coins from 2 dimes and 2 nickels: both dimes 1/6 of the time
+        let jar: Purse = [(Coin::Nickel, 2), (Coin::Dime, 2)].into_iter().collect();
+        let mut rng = StdRng::seed_from_u64(5);
+        let trials = 12_000;
+        let both_dimes = (0..trials)
+            .filter(|_| jar.clone().draw_with(&mut rng, 2).count_of(Coin::Dime) == 2)
+            .count();
+        let rate = both_dimes as f64 / trials as f64;
+        assert!((rate - 1.0 / 6.0).abs() < 0.015, "both dimes rate {rate}");
+    }
+
+    #[test]
+    fn test_purse_draw_more_than_held_takes_everything() {
+        let mut purse: Purse = [(Coin::Nickel, 2)].into_iter().collect();
+        let drawn = purse.draw(5);
+        assert_eq!(drawn.count_of(Coin::Nickel), 2);
+        assert!(purse.is_empty());
+        assert!(purse.draw(1).is_empty());
+        assert!(Purse::new().draw(0).is_empty());
+    }
+
+    #[test]
+    fn test_purse_draw_from_a_huge_purse() {
+        let full: Purse = Coin::iter().map(|coin| (coin, u32::MAX)).collect();
+        let mut rng = StdRng::seed_from_u64(6);
+        for n in [1, 1_000, 1_000_000_000, full.coin_count() / 2] {
+            let mut purse = full.clone();
+            let drawn = purse.draw_with(&mut rng, n);
+            assert_eq!(drawn.coin_count(), n);
+            assert_eq!(&drawn + &purse, full);
+        }
+
+        // A quarter of a billion-coin draw is pennies, give or take a
+        // few standard deviations (about 12,000 coins)
+        let pennies = full
+            .clone()
+            .draw_with(&mut rng, 1_000_000_000)
+            .count_of(Coin::Penny);
+        assert!(pennies.abs_diff(250_000_000) < 100_000, "{pennies} pennies");
+    }
+
+    #[test]
+    fn test_hypergeometric_bounds() {
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(hypergeometric(&mut rng, 0, 0, 0), 0);
+        assert_eq!(hypergeometric(&mut rng, 10, 10, 4), 4);
+        assert_eq!(hypergeometric(&mut rng, 10, 0, 4), 0);
+        assert_eq!(hypergeometric(&mut rng, 10, 3, 10), 3);
+        // 8 of 10 marked, 7 drawn: at least 5 are marked
+        for _ in 0..200 {
+            assert!((5..=7).contains(&hypergeometric(&mut rng, 10, 8, 7)));
+        }
+    }
+
+    #[test]
+    fn test_hypergeometric_mean() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let trials = 2_000;
+        let mean = (0..trials)
+            .map(|_| hypergeometric(&mut rng, 1_000, 300, 50) as f64)
+            .sum::<f64>()
+            / trials as f64;
+        // Expected 15, with a standard error of about 0.07
+        assert!((mean - 15.0).abs() < 0.3, "mean {mean}");
+    }
+
+    // ========================================================================
+    // Reconciliation Tests
+    // ========================================================================
+
+    #[test]
+    fn test_reconcile_surplus_and_shortage() {
+        let expected = purse(&[(Coin::Penny, 10), (Coin::Dime, 5), (Coin::Quarter, 8)]);
+        let counted = purse(&[(Coin::Penny, 12), (Coin::Dime, 5), (Coin::Quarter, 7)]);
+        let report = counted.reconcile(&expected);
+
+        assert!(!report.balanced);
+        assert_eq!(report.surplus, purse(&[(Coin::Penny, 2)]));
+        assert_eq!(report.shortage, purse(&[(Coin::Quarter, 1)]));
+        assert_eq!(report.value_delta, 2 - 25);
+
+        let quarters = &report.lines[3];
+        assert_eq!((quarters.expected, quarters.counted), (8, 7));
+        assert_eq!(quarters.difference, -1);
+        assert_eq!(quarters.value_difference, -25);
+        assert_eq!(report.lines[2].difference, 0);
+        assert_eq!(report.lines.len(), Coin::COUNT);
+    }
+
+    #[test]
+    fn test_reconcile_balanced() {
+        let drawer = purse(&[(Coin::Nickel, 3), (Coin::Quarter, 2)]);
+        let report = drawer.reconcile(&drawer);
+        assert!(report.balanced);
+        assert_eq!(report.value_delta, 0);
+        assert!(report.surplus.is_empty() && report.shortage.is_empty());
+        assert!(Purse::new().reconcile(&Purse::new()).balanced);
+    }
+
+    #[test]
+    fn test_reconcile_same_value_different_coins() {
+        let expected = purse(&[(Coin::Nickel, 2)]);
+        let report = purse(&[(Coin::Dime, 1)]).reconcile(&expected);
+        assert_eq!(report.value_delta, 0);
+        assert!(!report.balanced);
+        assert_eq!(report.shortage, expected);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["surplus"]["Dime"], 1);
+        assert_eq!(json["lines"][1]["difference"], -2);
+    }
+
+    // ========================================================================
+    // Capacity Tests
+    // ========================================================================
+
+    #[test]
+    fn test_unlimited_capacity() {
+        let mut wallet = purse(&[(Coin::Penny, u32::MAX - 1)]);
+        assert!(Capacity::unlimited().fits(&wallet));
+        assert_eq!(Capacity::default(), Capacity::unlimited());
+        wallet
+            .add_within(&purse(&[(Coin::Penny, 1)]), &Capacity::unlimited())
+            .unwrap();
+        assert_eq!(wallet.count_of(Coin::Penny), u32::MAX);
+    }
+
+    #[test]
+    fn test_coin_limits() {
+        let slots = Capacity::unlimited()
+            .with_coin_limit(Coin::Quarter, 40)
+            .with_coin_limit(Coin::Dime, 50);
+        assert_eq!(slots.coin_limit(Coin::Quarter), Some(40));
+        assert_eq!(slots.coin_limit(Coin::Penny), None);
+
+        let mut till = purse(&[(Coin::Quarter, 38), (Coin::Dime, 10)]);
+        let error = till
+            .add_within(
+                &purse(&[(Coin::Penny, 500), (Coin::Dime, 5), (Coin::Quarter, 4)]),
+                &slots,
+            )
+            .unwrap_err();
+        assert_eq!(error.rejected, purse(&[(Coin::Quarter, 2)]));
+        assert_eq!(error.to_string(), "no room for 2 coins worth $0.50");
+        // Nothing went in
+        assert_eq!(till, purse(&[(Coin::Quarter, 38), (Coin::Dime, 10)]));
+
+        till.add_within(&purse(&[(Coin::Quarter, 2)]), &slots)
+            .unwrap();
+        assert_eq!(till.count_of(Coin::Quarter), 40);
+    }
+
+    #[test]
+    fn test_total_limit_turns_away_small_coins() {
+        let coin_box = Capacity::unlimited().with_total_limit(10);
+        assert_eq!(coin_box.total_limit(), Some(10));
+
+        let held = purse(&[(Coin::Dime, 4)]);
+        let incoming = purse(&[(Coin::Penny, 3), (Coin::Nickel, 2), (Coin::Quarter, 3)]);
+        assert_eq!(
+            coin_box.overflow(&held, &incoming),
+            purse(&[(Coin::Penny, 2)])
+        );
+        assert!(coin_box.fits(&(&held + &purse(&[(Coin::Quarter, 6)]))));
+        assert!(!coin_box.fits(&(&held + &incoming)));
+    }
+
+    #[test]
+    fn test_coin_and_total_limits_together() {
+        let capacity = Capacity::unlimited()
+            .with_coin_limit(Coin::Quarter, 2)
+            .with_total_limit(4);
+        let incoming = purse(&[(Coin::Nickel, 3), (Coin::Quarter, 3)]);
+        // One quarter is over its slot; of the two left that fit, and the
+        // three nickels, one nickel is over the total
+        assert_eq!(
+            capacity.overflow(&Purse::new(), &incoming),
+            purse(&[(Coin::Nickel, 1), (Coin::Quarter, 1)])
+        );
+    }
+
+    #[test]
+    fn test_iteration() {
+        let purse: Purse = [Coin::Dime, Coin::Penny, Coin::Dime].into_iter().collect();
+        assert_eq!(
+            purse.iter().collect::<Vec<_>>(),
+            vec![(Coin::Penny, 1), (Coin::Dime, 2)]
+        );
+        assert_eq!(
+            purse.coins().collect::<Vec<_>>(),
+            vec![Coin::Penny, Coin::Dime, Coin::Dime]
+        );
+        assert_eq!(purse.clone().into_iter().collect::<Purse>(), purse);
+    }
+
+    // ========================================================================
+    // CSV Tests
+    // ========================================================================
+
+    #[test]
+    fn test_csv_round_trip() {
+        let csv = sample_purse().to_csv();
+        assert_eq!(csv, "coin,count\nPenny,3\nDime,2\nQuarter,40\n");
+        assert_eq!(Purse::from_csv(&csv), Ok(sample_purse()));
+        assert_eq!(Purse::new().to_csv(), "coin,count\n");
+        assert_eq!(Purse::from_csv("coin,count\n"), Ok(Purse::new()));
+    }
+
+    #[test]
+    fn test_csv_from_a_spreadsheet() {
+        // Any column order, coins by value, extra columns and zero counts
+        let csv = "count,note,coin\n40,roll,25\n0,,Nickel\n3,jar,penny\n";
+        assert_eq!(
+            Purse::from_csv(csv),
+            Ok(purse(&[(Coin::Penny, 3), (Coin::Quarter, 40)]))
+        );
+    }
+
+    #[test]
+    fn test_csv_errors_name_the_line() {
+        let error = Purse::from_csv("coin,count\nDime,2\nDollar,1\n").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert!(error.to_string().starts_with("line 3: "), "{error}");
+
+        let error = Purse::from_csv("coin,count\nDime,2\nPenny,-1\n").unwrap_err();
+        assert_eq!(error.line, 3);
+
+        let error = Purse::from_csv("coin,count\nDime,2\nPenny,1\n10,4\n").unwrap_err();
+        assert_eq!(
+            error,
+            PurseCsvError {
+                line: 4,
+                message: "Dime is listed more than once".to_string()
+            }
+        );
+
+        let error = Purse::from_csv("coin,count\nDime\n").unwrap_err();
+        assert_eq!(error.line, 2);
+
+        let error = Purse::from_csv("coin,amount\nDime,2\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 1: missing column 'count'");
+    }
+
+    // ========================================================================
+    // Purse file Tests
+    // ========================================================================
+
+    // A path in the temp directory that no other test uses
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("coins-{}-{name}", std::process::id()))
+    }
+
+    fn sample_purse() -> Purse {
+        [(Coin::Penny, 3), (Coin::Dime, 2), (Coin::Quarter, 40)]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_save_and_load_json() {
+        let path = temp_path("wallet.json");
+        sample_purse().save(&path).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json["version"], 1);
+        assert_eq!(json["coins"]["Quarter"], 40);
+
+        assert_eq!(Purse::load(&path).unwrap(), sample_purse());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_save_and_load_toml() {
+        let path = temp_path("wallet.toml");
+        sample_purse().save(&path).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("version = 1\n"), "{text}");
+        assert!(text.contains("[coins]\nPenny = 3\n"), "{text}");
+
+        assert_eq!(Purse::load(&path).unwrap(), sample_purse());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_save_replaces_the_file_in_one_step() {
+        let path = temp_path("replace.json");
+        purse(&[(Coin::Dime, 1)]).save(&path).unwrap();
+        sample_purse().save(&path).unwrap();
+
+        let loaded = Purse::load(&path);
+        let temp = temp_path_for(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), sample_purse());
+        assert!(!temp.exists(), "{} was left behind", temp.display());
+        assert_eq!(temp.parent(), path.parent());
+    }
+
+    #[test]
+    fn test_failed_save_keeps_the_old_file() {
+        let dir = temp_path("occupied");
+        std::fs::create_dir_all(&dir).unwrap();
+        // The rename fails because the target is a non-empty directory
+        std::fs::write(dir.join("keep"), "").unwrap();
+        let error = sample_purse().save(&dir).unwrap_err();
+        let kept = dir.join("keep").exists();
+        let temp = temp_path_for(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(error, PurseFileError::Io { .. }), "{error}");
+        assert!(kept);
+        assert!(!temp.exists());
+    }
+
+    #[test]
+    fn test_load_accepts_coin_aliases() {
+        let path = temp_path("aliases.toml");
+        std::fs::write(&path, "version = 1\n[coins]\npenny = 3\n10 = 2\n").unwrap();
+        let loaded = Purse::load(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(
+            loaded,
+            [(Coin::Penny, 3), (Coin::Dime, 2)].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let path = temp_path("missing.json");
+        let error = Purse::load(&path).unwrap_err();
+        assert!(matches!(&error, PurseFileError::Io { source, .. }
+            if source.kind() == io::ErrorKind::NotFound));
+        assert!(error
+            .to_string()
+            .starts_with(&format!("cannot access purse file {}: ", path.display())));
+    }
+
+    #[test]
+    fn test_load_malformed_file() {
+        let path = temp_path("malformed.json");
+        for text in [
+            "not json",
+            r#"{"coins": {}}"#,
+            r#"{"version": 1, "coins": {"Euro": 1}}"#,
+        ] {
+            std::fs::write(&path, text).unwrap();
+            let error = Purse::load(&path).unwrap_err();
+            assert!(
+                matches!(error, PurseFileError::Parse { .. }),
+                "{text}: {error}"
+            );
+            assert!(error.to_string().starts_with("invalid purse file"));
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_load_newer_version() {
+        let path = temp_path("future.json");
+        std::fs::write(&path, r#"{"version": 2, "pockets": []}"#).unwrap();
+        let error = Purse::load(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            error,
+            PurseFileError::UnsupportedVersion { version: 2, .. }
+        ));
+        assert!(error
+            .to_string()
+            .ends_with("has version 2, but only version 1 is supported"));
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            PurseFileFormat::from_path(Path::new("a.TOML")),
+            PurseFileFormat::Toml
+        );
+        assert_eq!(
+            PurseFileFormat::from_path(Path::new("a.json")),
+            PurseFileFormat::Json
+        );
+        assert_eq!(
+            PurseFileFormat::from_path(Path::new("wallet")),
+            PurseFileFormat::Json
+        );
+    }
+
+    // ========================================================================
+    // Sub-purse Tests
+    // ========================================================================
+
+    #[test]
+    fn test_sub_purses_of_pennies_and_dimes() {
+        let purse: Purse = [(Coin::Penny, 3), (Coin::Dime, 2)].into_iter().collect();
+        let subs: Vec<Purse> = purse.sub_purses().collect();
+
+        assert_eq!(subs.len(), 12);
+        assert_eq!(purse.sub_purse_count(), Some(12));
+        assert_eq!(subs[0], Purse::new());
+        assert_eq!(subs[1], [(Coin::Penny, 1)].into_iter().collect());
+        assert_eq!(subs[4], [(Coin::Dime, 1)].into_iter().collect());
+        assert_eq!(subs[11], purse);
+    }
+
+    #[test]
+    fn test_sub_purses_are_distinct_and_contained() {
+        let purse: Purse = [(Coin::Nickel, 2), (Coin::Dime, 1), (Coin::Quarter, 3)]
+            .into_iter()
+            .collect();
+        let subs: Vec<Purse> = purse.sub_purses().collect();
+        assert_eq!(subs.len(), 3 * 2 * 4);
+
+        for (i, sub) in subs.iter().enumerate() {
+            assert!(Coin::iter().all(|coin| sub.count_of(coin) <= purse.count_of(coin)));
+            assert!(!subs[..i].contains(sub));
+        }
+    }
+
+    #[test]
+    fn test_sub_purses_of_empty_purse() {
+        let subs: Vec<Purse> = Purse::new().sub_purses().collect();
+        assert_eq!(subs, vec![Purse::new()]);
+    }
+
+    #[test]
+    fn test_sub_purse_count_overflow() {
+        let purse: Purse = Coin::iter().map(|coin| (coin, u32::MAX)).collect();
+        assert_eq!(purse.sub_purse_count(), None);
+    }
+
+    #[test]
+    fn test_purse_json_round_trip() {
+        let purse: Purse = [(Coin::Penny, 3), (Coin::Dime, 2)].into_iter().collect();
+        let json = serde_json::to_string(&purse).unwrap();
+        assert_eq!(json, r#"{"Penny":3,"Dime":2}"#);
+
+        let back: Purse = serde_json::from_str(r#"{"penny": 3, "10": 2}"#).unwrap();
+        assert_eq!(back, purse);
+    }
+
+    #[test]
+    fn test_purse_deserialize_drops_zero_counts() {
+        let empty: Purse = serde_json::from_str(r#"{"Penny": 0}"#).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty, Purse::new());
+        assert_eq!(serde_json::to_string(&empty).unwrap(), "{}");
+
+        let back: Purse = serde_json::from_str(r#"{"Penny": 0, "Dime": 2}"#).unwrap();
+        assert_eq!(back, [(Coin::Dime, 2)].into_iter().collect());
+        assert_eq!(back.iter().count(), 1);
+    }
+
+    #[test]
+    fn test_purse_deserialize_adds_up_equivalent_keys() {
+        let purse: Purse = serde_json::from_str(r#"{"penny": 2, "Penny": 3, "1": 1}"#).unwrap();
+        assert_eq!(purse, [(Coin::Penny, 6)].into_iter().collect());
+
+        let json = format!(r#"{{"Dime": {}, "dime": 1}}"#, u32::MAX);
+        let error = serde_json::from_str::<Purse>(&json).unwrap_err();
+        assert!(
+            error.to_string().starts_with("more than 4294967295 of "),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_load_drops_zero_counts() {
+        let path = temp_path("zero.toml");
+        std::fs::write(&path, "version = 1\n\n[coins]\nPenny = 0\n").unwrap();
+        let loaded = Purse::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), Purse::new());
+    }
+
+    #[test]
+    fn test_load_adds_up_equivalent_keys() {
+        let path = temp_path("repeated.toml");
+        std::fs::write(&path, "version = 1\n[coins]\npenny = 3\nPenny = 2\n").unwrap();
+        let loaded = Purse::load(&path);
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(loaded.unwrap(), [(Coin::Penny, 5)].into_iter().collect());
+    }
+}
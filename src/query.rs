@@ -0,0 +1,195 @@
+// ============================================================================
+// QUERY MODULE: Filtered Combination Search
+// ============================================================================
+// CombinationQuery collects filters and applies them while combinations()
+// enumerates, so nothing is built for combinations that get rejected:
+//
+//   CombinationQuery::new()
+//       .min_value(10)
+//       .max_value(30)
+//       .contains(Coin::Dime)
+//       .max_coins(3)
+//       .run()                      // lazy iterator of Vec<Coin>
+//
+// Filters combine with AND; a query with no filters yields all 16.
+
+use crate::{Coin, Combination, CombinationMask, Money};
+
+/// Builder for a filtered enumeration of combinations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CombinationQuery {
+    min_value: Option<Money>,
+    max_value: Option<Money>,
+    min_coins: Option<usize>,
+    max_coins: Option<usize>,
+    required: CombinationMask,
+    excluded: CombinationMask,
+}
+
+impl CombinationQuery {
+    /// A query that matches every combination
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep combinations worth at least this many cents
+    pub fn min_value(mut self, cents: u64) -> Self {
+        self.min_value = Some(Money::from_cents(cents));
+        self
+    }
+
+    /// Keep combinations worth at most this many cents
+    pub fn max_value(mut self, cents: u64) -> Self {
+        self.max_value = Some(Money::from_cents(cents));
+        self
+    }
+
+    /// Keep combinations with at least this many coins
+    pub fn min_coins(mut self, count: usize) -> Self {
+        self.min_coins = Some(count);
+        self
+    }
+
+    /// Keep combinations with at most this many coins
+    pub fn max_coins(mut self, count: usize) -> Self {
+        self.max_coins = Some(count);
+        self
+    }
+
+    /// Keep combinations that include this coin (repeatable)
+    pub fn contains(mut self, coin: Coin) -> Self {
+        self.required = self.required.with(coin);
+        self
+    }
+
+    /// Keep combinations that leave this coin out (repeatable)
+    pub fn excludes(mut self, coin: Coin) -> Self {
+        self.excluded = self.excluded.with(coin);
+        self
+    }
+
+    /// Whether a combination passes every filter
+    pub fn matches(&self, coins: &[Coin]) -> bool {
+        let mask = CombinationMask::from(coins);
+        self.matches_mask(mask)
+    }
+
+    pub(crate) fn matches_mask(&self, mask: CombinationMask) -> bool {
+        let value = mask.value();
+        let len = mask.len();
+
+        mask.bits() & self.required.bits() == self.required.bits()
+            && mask.bits() & self.excluded.bits() == 0
+            && self.min_value.is_none_or(|min| value >= min)
+            && self.max_value.is_none_or(|max| value <= max)
+            && self.min_coins.is_none_or(|min| len >= min)
+            && self.max_coins.is_none_or(|max| len <= max)
+    }
+
+    /// Lazily yields the matching combinations, in index order
+    pub fn run(self) -> impl Iterator<Item = Combination> {
+        (0..=CombinationMask::FULL.bits())
+            .filter_map(CombinationMask::from_bits)
+            .filter(move |mask| self.matches_mask(*mask))
+            .map(Combination::from)
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========================================================================
+    // Builder Tests
+    // ========================================================================
+
+    #[test]
+    fn test_empty_query_matches_everything() {
+        let all: Vec<Combination> = CombinationQuery::new().run().collect();
+        assert_eq!(all, crate::generate_all_combinations());
+    }
+
+    #[test]
+    fn test_value_range() {
+        let results: Vec<Combination> = CombinationQuery::new()
+            .min_value(10)
+            .max_value(30)
+            .run()
+            .collect();
+
+        assert!(!results.is_empty());
+        for coins in &results {
+            let value = crate::total_value(coins).cents();
+            assert!((10..=30).contains(&value));
+        }
+    }
+
+    #[test]
+    fn test_example_from_docs() {
+        let results: Vec<Combination> = CombinationQuery::new()
+            .min_value(10)
+            .max_value(30)
+            .contains(Coin::Dime)
+            .max_coins(3)
+            .run()
+            .collect();
+
+        assert_eq!(
+            results,
+            vec![
+                vec![Coin::Dime],
+                vec![Coin::Penny, Coin::Dime],
+                vec![Coin::Nickel, Coin::Dime],
+                vec![Coin::Penny, Coin::Nickel, Coin::Dime],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_coin_count_bounds() {
+        let pairs = CombinationQuery::new().min_coins(2).max_coins(2).run();
+        assert_eq!(pairs.count(), 6);
+    }
+
+    #[test]
+    fn test_excludes() {
+        let results: Vec<Combination> = CombinationQuery::new()
+            .excludes(Coin::Penny)
+            .excludes(Coin::Quarter)
+            .run()
+            .collect();
+
+        assert_eq!(
+            results,
+            vec![
+                vec![],
+                vec![Coin::Nickel],
+                vec![Coin::Dime],
+                vec![Coin::Nickel, Coin::Dime]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_contradictory_filters_match_nothing() {
+        let query = CombinationQuery::new()
+            .contains(Coin::Dime)
+            .excludes(Coin::Dime);
+        assert_eq!(query.run().count(), 0);
+
+        let query = CombinationQuery::new().min_value(50);
+        assert_eq!(query.run().count(), 0);
+    }
+
+    #[test]
+    fn test_matches_single_combination() {
+        let query = CombinationQuery::new().contains(Coin::Quarter).max_coins(1);
+        assert!(query.matches(&[Coin::Quarter]));
+        assert!(!query.matches(&[Coin::Penny, Coin::Quarter]));
+        assert!(!query.matches(&[]));
+    }
+}
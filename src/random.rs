@@ -0,0 +1,1737 @@
+// ============================================================================
+// RANDOM MODULE: rand Integration
+// ============================================================================
+// Lets callers draw coins and combinations with their own RNG:
+//
+//   let coin: Coin = rng.gen();
+//   let combination = rng.sample(UniformCombination);
+//
+// The generate_*_with() functions take the RNG as a parameter, so tests can
+// pass a seeded or mock RNG; generate_random_combination() and friends are
+// thin wrappers that use thread_rng(). RngAlgorithm picks the generator
+// behind seeded draws, including a portable one whose output never changes.
+
+use chrono::Datelike;
+use rand::distributions::{Distribution, Standard};
+use rand::rngs::StdRng;
+use rand::seq::{index, SliceRandom};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use num_bigint::RandBigInt;
+
+// Re-exported so callers of daily_combination() don't need chrono themselves
+pub use chrono::NaiveDate;
+
+use crate::change::coin_from_value;
+use crate::{
+    all_combinations_cached, combination_from_bits, total_value, BigUint, ChangeError, Coin,
+    Combination, DenominationSet, Purse,
+};
+
+// rng.gen::<Coin>() picks each coin type with equal probability
+impl Distribution<Coin> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Coin {
+        let coins = Coin::all();
+        coins[rng.gen_range(0..Coin::COUNT)]
+    }
+}
+
+/// Samples a combination uniformly from all 2^n subsets of coins
+///
+/// Every combination, including the empty one, has probability 1/16.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UniformCombination;
+
+impl Distribution<Vec<Coin>> for UniformCombination {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<Coin> {
+        let total_combinations = 1 << Coin::COUNT;
+        combination_from_bits(rng.gen_range(0..total_combinations)).into_vec()
+    }
+}
+
+/// Samples a combination using real-world circulation frequencies
+///
+/// Each coin is included independently with probability equal to its
+/// circulation share (see Coin::circulation_share), so pennies appear in
+/// about 56% of draws and nickels in about 9%.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CirculationWeighted;
+
+impl Distribution<Vec<Coin>> for CirculationWeighted {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<Coin> {
+        Coin::iter()
+            .filter(|coin| rng.gen_bool(coin.circulation_share()))
+            .collect()
+    }
+}
+
+/// Uniformly random combination drawn from `rng`
+///
+/// Every combination, including the empty one, has probability 1/16.
+pub fn generate_random_combination_with<R: Rng + ?Sized>(rng: &mut R) -> Vec<Coin> {
+    rng.sample(UniformCombination)
+}
+
+/// Uniformly random combination of exactly `k` different coins, or None if
+/// there are fewer than `k` coin types
+///
+/// Each of the C(4, k) combinations of that size is equally likely.
+pub fn generate_random_combination_of_size(k: usize) -> Option<Vec<Coin>> {
+    generate_random_combination_of_size_with(&mut rand::thread_rng(), k)
+}
+
+/// `n` uniformly random combinations
+///
+/// Locks the thread RNG once and allocates the result up front, so it is
+/// cheaper than calling generate_random_combination() in a loop.
+pub fn generate_random_combinations(n: usize) -> Vec<Combination> {
+    generate_random_combinations_with(&mut rand::thread_rng(), n)
+}
+
+/// `n` uniformly random combinations drawn from `rng`
+pub fn generate_random_combinations_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    n: usize,
+) -> Vec<Combination> {
+    let mut combinations = Vec::with_capacity(n);
+    for _ in 0..n {
+        combinations.push(combination_from_bits(rng.gen_range(0..1 << Coin::COUNT)));
+    }
+    combinations
+}
+
+// ============================================================================
+// Shuffled enumeration
+// ============================================================================
+
+/// Every combination exactly once, in random order
+///
+/// Created by generate_all_combinations_shuffled(). The order is a
+/// Fisher–Yates shuffle of the combination indices done one step per
+/// item, so taking only the first few combinations costs only a few draws.
+#[derive(Debug, Clone)]
+pub struct ShuffledCombinations<R> {
+    rng: R,
+    // Indices not yet yielded live in order[next..]
+    order: Vec<usize>,
+    next: usize,
+}
+
+/// Walks all 2^n combinations once each in a random order chosen by `rng`,
+/// e.g. for a quiz that asks about every combination without repeats
+///
+/// Pass `&mut rng` to keep using the RNG afterwards.
+pub fn generate_all_combinations_shuffled<R: Rng>(rng: R) -> ShuffledCombinations<R> {
+    ShuffledCombinations {
+        rng,
+        order: (0..1 << Coin::COUNT).collect(),
+        next: 0,
+    }
+}
+
+impl<R: Rng> Iterator for ShuffledCombinations<R> {
+    type Item = Combination;
+
+    fn next(&mut self) -> Option<Combination> {
+        if self.next >= self.order.len() {
+            return None;
+        }
+        let pick = self.rng.gen_range(self.next..self.order.len());
+        self.order.swap(self.next, pick);
+        let combination = combination_from_bits(self.order[self.next]);
+        self.next += 1;
+        Some(combination)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.order.len() - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<R: Rng> ExactSizeIterator for ShuffledCombinations<R> {}
+
+impl<R: Rng> FusedIterator for ShuffledCombinations<R> {}
+
+// ============================================================================
+// Endless streams
+// ============================================================================
+
+/// Endless random combinations meeting `options`, drawn with thread_rng()
+///
+/// Yields nothing at all if no combination meets the options. The web
+/// module's random_combination_stream() is the async, paced version.
+pub fn random_stream(options: RandomOptions) -> impl Iterator<Item = Combination> {
+    random_stream_with(rand::thread_rng(), options)
+}
+
+/// Like random_stream(), drawing from `rng`
+pub fn random_stream_with<R: Rng>(
+    mut rng: R,
+    options: RandomOptions,
+) -> impl Iterator<Item = Combination> {
+    std::iter::from_fn(move || options.generate_with(&mut rng).map(Combination::from))
+}
+
+// ============================================================================
+// Random options
+// ============================================================================
+
+/// Restrictions on a uniformly random combination
+///
+/// The default allows every combination. Options are set with the builder
+/// methods, so new ones can be added without breaking callers:
+///
+///   RandomOptions::new().allow_empty(false).max_coins(2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RandomOptions {
+    /// Whether the empty combination can be drawn
+    pub allow_empty: bool,
+    /// Only draw combinations with exactly this many coins
+    pub exact_coins: Option<usize>,
+    /// Only draw combinations with at least this many coins
+    pub min_coins: Option<usize>,
+    /// Only draw combinations with at most this many coins
+    pub max_coins: Option<usize>,
+    /// Only draw combinations worth at least this many cents
+    pub min_value: Option<u64>,
+    /// Only draw combinations worth at most this many cents
+    pub max_value: Option<u64>,
+    /// Generator used by generate_seeded()
+    pub rng: RngAlgorithm,
+}
+
+impl Default for RandomOptions {
+    fn default() -> Self {
+        RandomOptions {
+            allow_empty: true,
+            exact_coins: None,
+            min_coins: None,
+            max_coins: None,
+            min_value: None,
+            max_value: None,
+            rng: RngAlgorithm::default(),
+        }
+    }
+}
+
+impl RandomOptions {
+    /// Options that allow every combination
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow or refuse the empty combination
+    pub fn allow_empty(mut self, allow: bool) -> Self {
+        self.allow_empty = allow;
+        self
+    }
+
+    /// Only draw combinations with exactly this many coins
+    pub fn exact_coins(mut self, count: usize) -> Self {
+        self.exact_coins = Some(count);
+        self
+    }
+
+    /// Only draw combinations with at least this many coins
+    pub fn min_coins(mut self, count: usize) -> Self {
+        self.min_coins = Some(count);
+        self
+    }
+
+    /// Only draw combinations with at most this many coins
+    pub fn max_coins(mut self, count: usize) -> Self {
+        self.max_coins = Some(count);
+        self
+    }
+
+    /// Only draw combinations worth at least this many cents
+    pub fn min_value(mut self, cents: u64) -> Self {
+        self.min_value = Some(cents);
+        self
+    }
+
+    /// Only draw combinations worth at most this many cents
+    pub fn max_value(mut self, cents: u64) -> Self {
+        self.max_value = Some(cents);
+        self
+    }
+
+    /// Seed generate_seeded() draws with this generator
+    pub fn rng(mut self, rng: RngAlgorithm) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Whether any combination meets these options
+    pub fn is_satisfiable(&self) -> bool {
+        all_combinations_cached()
+            .iter()
+            .any(|coins| self.allows(coins))
+    }
+
+    /// A combination drawn uniformly among those meeting the options, or
+    /// None if none do (see is_satisfiable())
+    pub fn generate(&self) -> Option<Vec<Coin>> {
+        self.generate_with(&mut rand::thread_rng())
+    }
+
+    /// Like generate(), drawing from a generator of kind `self.rng` seeded
+    /// with `seed`, so the same seed always gives the same combination
+    pub fn generate_seeded(&self, seed: u64) -> Option<Vec<Coin>> {
+        self.generate_with(&mut self.rng.seeded(seed))
+    }
+
+    /// Like generate(), drawing from `rng`
+    ///
+    /// With the default options this draws exactly what
+    /// generate_random_combination_with() would from the same RNG.
+    pub fn generate_with<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Vec<Coin>> {
+        if !self.is_satisfiable() {
+            return None;
+        }
+        let ranged = self.min_coins.is_some()
+            || self.max_coins.is_some()
+            || self.min_value.is_some()
+            || self.max_value.is_some();
+        if ranged {
+            // No shortcut for a range: pick among the allowed combinations
+            let allowed: Vec<&Combination> = all_combinations_cached()
+                .iter()
+                .filter(|coins| self.allows(coins))
+                .collect();
+            return allowed.choose(rng).map(|coins| coins.to_vec());
+        }
+        match self.exact_coins {
+            Some(k) => generate_random_combination_of_size_with(rng, k),
+            None if self.allow_empty => Some(generate_random_combination_with(rng)),
+            // Skip subset 0, the empty one
+            None => Some(combination_from_bits(rng.gen_range(1..1 << Coin::COUNT)).into_vec()),
+        }
+    }
+}
+
+/// Random combination of exactly `k` coins drawn from `rng`, see
+/// generate_random_combination_of_size()
+pub fn generate_random_combination_of_size_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    k: usize,
+) -> Option<Vec<Coin>> {
+    if k > Coin::COUNT {
+        return None;
+    }
+    let mut positions = index::sample(rng, Coin::COUNT, k).into_vec();
+    // Lowest value first, like every other combination
+    positions.sort_unstable();
+    let coins = Coin::all();
+    Some(positions.into_iter().map(|i| coins[i]).collect())
+}
+
+// ============================================================================
+// Seeded generators
+// ============================================================================
+
+/// Pseudo-random generator used for seeded draws
+///
+/// StdRng is the default for speed, but rand only promises that it gives the
+/// same sequence within one build. ChaCha8 is a fixed algorithm, so a seed
+/// gives the same draws on every platform for a given rand version (rand
+/// may change how ranges and shuffles sample between versions), which is
+/// what seeds stored in bug reports or tests need.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RngAlgorithm {
+    /// rand's StdRng
+    #[default]
+    Std,
+    /// ChaCha with 8 rounds, from rand_chacha
+    ChaCha8,
+}
+
+impl RngAlgorithm {
+    /// Every algorithm, in declaration order
+    pub fn all() -> [RngAlgorithm; 2] {
+        [RngAlgorithm::Std, RngAlgorithm::ChaCha8]
+    }
+
+    /// Name accepted by FromStr, e.g. "chacha8"
+    pub fn name(&self) -> &'static str {
+        match self {
+            RngAlgorithm::Std => "std",
+            RngAlgorithm::ChaCha8 => "chacha8",
+        }
+    }
+
+    /// A generator of this kind, seeded with `seed`
+    pub fn seeded(self, seed: u64) -> Box<dyn RngCore + Send> {
+        match self {
+            RngAlgorithm::Std => Box::new(StdRng::seed_from_u64(seed)),
+            RngAlgorithm::ChaCha8 => Box::new(ChaCha8Rng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl fmt::Display for RngAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error returned for unknown RNG algorithm names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RngAlgorithmParseError {
+    input: String,
+}
+
+impl fmt::Display for RngAlgorithmParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = RngAlgorithm::all().iter().map(|a| a.name()).collect();
+        write!(
+            f,
+            "unknown RNG '{}': expected one of {}",
+            self.input,
+            names.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for RngAlgorithmParseError {}
+
+// Case-insensitive, so "ChaCha8" works as well as "chacha8"
+impl FromStr for RngAlgorithm {
+    type Err = RngAlgorithmParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RngAlgorithm::all()
+            .into_iter()
+            .find(|algorithm| algorithm.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| RngAlgorithmParseError {
+                input: s.to_string(),
+            })
+    }
+}
+
+// ============================================================================
+// Random change for an amount
+// ============================================================================
+// Picks uniformly among every handful of coins worth the amount. The counting
+// table from count_ways() is kept for every denomination, then the handful
+// is built largest coin first: the number of quarters is chosen with
+// probability proportional to how many ways the rest can be paid, and so on
+// down to the pennies, which take whatever is left.
+
+impl DenominationSet {
+    /// A handful of coins worth exactly `amount`, smallest first, chosen
+    /// uniformly among all such handfuls (unlimited coins of each value)
+    ///
+    /// Builds a table of (denominations x amount) counts, so memory grows
+    /// with the amount.
+    pub fn random_change_with<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        amount: u64,
+    ) -> Result<Vec<u32>, ChangeError> {
+        let values = self.values();
+        let size = amount as usize + 1;
+
+        // ways[i][a]: handfuls worth a using only the first i + 1 values
+        let mut ways: Vec<Vec<BigUint>> = Vec::with_capacity(values.len());
+        let smallest = values[0] as usize;
+        ways.push(
+            (0..size)
+                .map(|a| BigUint::from(u8::from(a % smallest == 0)))
+                .collect(),
+        );
+        for &d in &values[1..] {
+            let d = d as usize;
+            let mut level = ways.last().expect("at least one level").clone();
+            for a in d..size {
+                let (lower, upper) = level.split_at_mut(a);
+                upper[0] += &lower[a - d];
+            }
+            ways.push(level);
+        }
+
+        let mut remaining = size - 1;
+        if ways[values.len() - 1][remaining] == BigUint::ZERO {
+            return Err(ChangeError::Unpayable { amount });
+        }
+
+        let mut change = Vec::new();
+        for i in (1..values.len()).rev() {
+            let d = values[i] as usize;
+            let mut pick = rng.gen_biguint_below(&ways[i][remaining]);
+            let mut count = 0;
+            // Each count of this coin is weighted by the ways to pay the rest
+            while pick >= ways[i - 1][remaining - count * d] {
+                pick -= &ways[i - 1][remaining - count * d];
+                count += 1;
+            }
+            change.extend(std::iter::repeat_n(values[i], count));
+            remaining -= count * d;
+        }
+        change.extend(std::iter::repeat_n(values[0], remaining / smallest));
+        change.reverse();
+        Ok(change)
+    }
+}
+
+/// A uniformly random handful of US coins worth exactly `amount` cents,
+/// lowest value first
+///
+/// Every amount can be paid in US coins, so this never actually fails; it
+/// returns a Result like DenominationSet::random_change_with(). Useful for
+/// payment test fixtures: unlike make_change(), it doesn't always pick the
+/// fewest coins.
+pub fn generate_random_change_for(amount: u64) -> Result<Vec<Coin>, ChangeError> {
+    generate_random_change_for_with(&mut rand::thread_rng(), amount)
+}
+
+/// Random change for `amount` cents drawn from `rng`, see
+/// generate_random_change_for()
+pub fn generate_random_change_for_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    amount: u64,
+) -> Result<Vec<Coin>, ChangeError> {
+    let values = DenominationSet::us().random_change_with(rng, amount)?;
+    Ok(values.into_iter().map(coin_from_value).collect())
+}
+
+// ============================================================================
+// Custom inclusion probabilities
+// ============================================================================
+
+/// Error returned when a probability (of inclusion, of heads, ...) is not
+/// between 0 and 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProbabilityError {
+    value: f64,
+}
+
+impl ProbabilityError {
+    /// The rejected value
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+}
+
+impl fmt::Display for ProbabilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid probability {}: must be between 0 and 1",
+            self.value
+        )
+    }
+}
+
+impl std::error::Error for ProbabilityError {}
+
+/// Independent inclusion probability for each coin
+///
+/// Samples a combination by including each coin with its own probability,
+/// e.g. to model a pocket where quarters are rarer than pennies. The
+/// default gives every coin 0.5, which makes all 16 combinations equally
+/// likely.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoinWeights([f64; Coin::COUNT]);
+
+impl CoinWeights {
+    /// Every coin included with the same probability
+    pub fn new(probability: f64) -> Result<Self, ProbabilityError> {
+        Ok(CoinWeights([check_probability(probability)?; Coin::COUNT]))
+    }
+
+    /// The probabilities CirculationWeighted uses, see
+    /// Coin::circulation_share()
+    pub fn circulation() -> Self {
+        CoinWeights(Coin::all().map(|coin| coin.circulation_share()))
+    }
+
+    /// These weights with `coin` included with `probability`
+    pub fn with(mut self, coin: Coin, probability: f64) -> Result<Self, ProbabilityError> {
+        self.0[coin_position(coin)] = check_probability(probability)?;
+        Ok(self)
+    }
+
+    /// Probability that `coin` is included in a draw
+    pub fn probability(&self, coin: Coin) -> f64 {
+        self.0[coin_position(coin)]
+    }
+}
+
+impl Default for CoinWeights {
+    fn default() -> Self {
+        CoinWeights([0.5; Coin::COUNT])
+    }
+}
+
+impl Distribution<Vec<Coin>> for CoinWeights {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<Coin> {
+        Coin::iter()
+            .filter(|&coin| rng.gen_bool(self.probability(coin)))
+            .collect()
+    }
+}
+
+pub(crate) fn check_probability(probability: f64) -> Result<f64, ProbabilityError> {
+    // Also rejects NaN, which fails every comparison
+    if (0.0..=1.0).contains(&probability) {
+        Ok(probability)
+    } else {
+        Err(ProbabilityError { value: probability })
+    }
+}
+
+/// Combinations with each coin included independently, as a plug-in for
+/// the rand ecosystem
+///
+/// Built from explicit per-coin probabilities, from CoinWeights, or from
+/// how often each coin appears in observed combinations, and samples
+/// Combination as well as Vec<Coin>:
+///
+///   let fitted = CoinDistribution::from_frequencies(&observed)?;
+///   let more: Vec<Combination> = rng.sample_iter(&fitted).take(100).collect();
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CoinDistribution {
+    weights: CoinWeights,
+}
+
+impl CoinDistribution {
+    /// Distribution including each coin with the probability in `weights`
+    pub fn new(weights: CoinWeights) -> Self {
+        CoinDistribution { weights }
+    }
+
+    /// Distribution from (coin, probability) pairs; unlisted coins are
+    /// never included
+    pub fn from_probabilities<I>(probabilities: I) -> Result<Self, ProbabilityError>
+    where
+        I: IntoIterator<Item = (Coin, f64)>,
+    {
+        let weights = probabilities
+            .into_iter()
+            .try_fold(CoinWeights::new(0.0)?, |weights, (coin, p)| {
+                weights.with(coin, p)
+            })?;
+        Ok(CoinDistribution::new(weights))
+    }
+
+    /// Distribution including each coin as often as it appears in
+    /// `observed`, or None if nothing was observed
+    ///
+    /// A coin in 30 of 100 observed combinations is included with
+    /// probability 0.3. Repeats of a coin within one combination count
+    /// once.
+    pub fn from_frequencies<I, C>(observed: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = C>,
+        C: AsRef<[Coin]>,
+    {
+        let mut seen = [0u64; Coin::COUNT];
+        let mut total = 0u64;
+        for combination in observed {
+            let coins = combination.as_ref();
+            for (count, coin) in seen.iter_mut().zip(Coin::iter()) {
+                *count += u64::from(coins.contains(&coin));
+            }
+            total += 1;
+        }
+        if total == 0 {
+            return None;
+        }
+        let weights = CoinWeights(seen.map(|count| count as f64 / total as f64));
+        Some(CoinDistribution::new(weights))
+    }
+
+    /// The per-coin inclusion probabilities
+    pub fn weights(&self) -> &CoinWeights {
+        &self.weights
+    }
+}
+
+impl From<CoinWeights> for CoinDistribution {
+    fn from(weights: CoinWeights) -> Self {
+        CoinDistribution::new(weights)
+    }
+}
+
+impl Distribution<Combination> for CoinDistribution {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Combination {
+        Coin::iter()
+            .filter(|&coin| rng.gen_bool(self.weights.probability(coin)))
+            .collect()
+    }
+}
+
+impl Distribution<Vec<Coin>> for CoinDistribution {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<Coin> {
+        self.weights.sample(rng)
+    }
+}
+
+fn coin_position(coin: Coin) -> usize {
+    Coin::iter()
+        .position(|c| c == coin)
+        .expect("every coin is in Coin::iter()")
+}
+
+/// Random combination with each coin included independently with the
+/// probability given by `weights`
+pub fn generate_random_combination_weighted(weights: &CoinWeights) -> Vec<Coin> {
+    generate_random_combination_weighted_with(&mut rand::thread_rng(), weights)
+}
+
+/// Like generate_random_combination_weighted(), drawing from `rng`
+pub fn generate_random_combination_weighted_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    weights: &CoinWeights,
+) -> Vec<Coin> {
+    rng.sample(weights)
+}
+
+/// Random combination weighted by how often each coin circulates
+///
+/// Unlike generate_random_combination(), combinations are not equally
+/// likely: ones containing pennies are far more common than ones
+/// containing nickels.
+pub fn generate_weighted_random_combination() -> Vec<Coin> {
+    generate_weighted_random_combination_with(&mut rand::thread_rng())
+}
+
+/// Circulation-weighted random combination drawn from `rng`, see
+/// generate_weighted_random_combination()
+pub fn generate_weighted_random_combination_with<R: Rng + ?Sized>(rng: &mut R) -> Vec<Coin> {
+    rng.sample(CirculationWeighted)
+}
+
+// ============================================================================
+// Stratified sampling
+// ============================================================================
+
+/// Error returned when a value bucket contains no combination
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmptyBucketError {
+    bucket: RangeInclusive<u64>,
+}
+
+impl EmptyBucketError {
+    /// The bucket no combination falls into
+    pub fn bucket(&self) -> &RangeInclusive<u64> {
+        &self.bucket
+    }
+}
+
+impl fmt::Display for EmptyBucketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no combination is worth between {} and {} cents",
+            self.bucket.start(),
+            self.bucket.end()
+        )
+    }
+}
+
+impl std::error::Error for EmptyBucketError {}
+
+/// `per_bucket` random combinations worth an amount in each of `buckets`
+///
+/// The result has one group per bucket, in the same order, so every value
+/// range is covered however rare it is among all combinations:
+///
+///   sample_stratified(&[0..=10, 11..=25, 26..=41], 5)   // 3 groups of 5
+///
+/// Within a bucket combinations are drawn uniformly and with replacement,
+/// since a bucket may hold fewer than `per_bucket` combinations. Fails if
+/// any bucket holds none.
+pub fn sample_stratified(
+    buckets: &[RangeInclusive<u64>],
+    per_bucket: usize,
+) -> Result<Vec<Vec<Combination>>, EmptyBucketError> {
+    sample_stratified_with(&mut rand::thread_rng(), buckets, per_bucket)
+}
+
+/// Like sample_stratified(), drawing from `rng`
+pub fn sample_stratified_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    buckets: &[RangeInclusive<u64>],
+    per_bucket: usize,
+) -> Result<Vec<Vec<Combination>>, EmptyBucketError> {
+    // Check every bucket before drawing anything
+    let members: Vec<Vec<&Combination>> = buckets
+        .iter()
+        .map(|bucket| {
+            let members: Vec<&Combination> = all_combinations_cached()
+                .iter()
+                .filter(|coins| bucket.contains(&total_value(coins).cents()))
+                .collect();
+            if members.is_empty() {
+                Err(EmptyBucketError {
+                    bucket: bucket.clone(),
+                })
+            } else {
+                Ok(members)
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(members
+        .into_iter()
+        .map(|members| {
+            (0..per_bucket)
+                .map(|_| members[rng.gen_range(0..members.len())].clone())
+                .collect()
+        })
+        .collect())
+}
+
+// ============================================================================
+// Random multisets
+// ============================================================================
+
+/// Random handful with between 0 and `max_per_coin` of each coin
+///
+/// The random counterpart of generate_combinations_with_counts() with the
+/// same limit for every coin: each of its (max_per_coin + 1)^4 handfuls is
+/// equally likely, and coins come out
+/// lowest value first. A limit of 1 draws like generate_random_combination().
+pub fn generate_random_multiset(max_per_coin: usize) -> Combination {
+    generate_random_multiset_with(&mut rand::thread_rng(), max_per_coin)
+}
+
+/// Like generate_random_multiset(), drawing from `rng`
+pub fn generate_random_multiset_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    max_per_coin: usize,
+) -> Combination {
+    let mut coins = Combination::new();
+    for coin in Coin::iter() {
+        coins.extend(std::iter::repeat_n(coin, rng.gen_range(0..=max_per_coin)));
+    }
+    coins
+}
+
+// ============================================================================
+// Random purses
+// ============================================================================
+
+/// Samples a purse holding between 0 and `max_per_coin` of each coin
+///
+/// Each coin's count is drawn independently and uniformly, so unlike a
+/// combination a purse can hold several of the same coin, like a real
+/// pocket or till.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RandomPurse {
+    pub max_per_coin: u32,
+}
+
+impl Distribution<Purse> for RandomPurse {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Purse {
+        Coin::iter()
+            .map(|coin| (coin, rng.gen_range(0..=self.max_per_coin)))
+            .collect()
+    }
+}
+
+/// Random purse with 0 to `max_per_coin` of each coin, e.g. 0 to 10 for
+/// seeding cash-register and wallet simulations
+pub fn generate_random_purse(max_per_coin: u32) -> Purse {
+    generate_random_purse_with(&mut rand::thread_rng(), max_per_coin)
+}
+
+/// Random purse drawn from `rng`, see generate_random_purse()
+pub fn generate_random_purse_with<R: Rng + ?Sized>(rng: &mut R, max_per_coin: u32) -> Purse {
+    rng.sample(RandomPurse { max_per_coin })
+}
+
+// ============================================================================
+// Combination of the day
+// ============================================================================
+
+/// The combination picked for `date`
+///
+/// Seeded by the number of days since 1 January of year 1, so everyone
+/// asking about the same date gets the same combination, and consecutive
+/// days are unrelated draws. Always drawn with ChaCha8, whose output doesn't
+/// depend on the platform the way StdRng's may.
+pub fn daily_combination(date: NaiveDate) -> Vec<Coin> {
+    generate_random_combination_with(&mut RngAlgorithm::ChaCha8.seeded(daily_seed(date)))
+}
+
+fn daily_seed(date: NaiveDate) -> u64 {
+    // Dates before year 1 wrap to huge seeds, which are just as good
+    i64::from(date.num_days_from_ce()) as u64
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_all_combinations, total_value, Combination};
+    use rand::rngs::mock::StepRng;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn test_gen_coin_covers_all_coins() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let seen: HashSet<Coin> = (0..200).map(|_| rng.gen::<Coin>()).collect();
+        assert_eq!(seen.len(), Coin::COUNT);
+    }
+
+    #[test]
+    fn test_uniform_combination_yields_valid_combinations() {
+        let all = generate_all_combinations();
+        let mut rng = StdRng::seed_from_u64(42);
+        for combination in UniformCombination.sample_iter(&mut rng).take(100) {
+            assert!(all.contains(&Combination::from(combination)));
+        }
+    }
+
+    #[test]
+    fn test_uniform_combination_reaches_every_subset() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let seen: HashSet<Vec<Coin>> = (0..1000).map(|_| rng.sample(UniformCombination)).collect();
+        assert_eq!(seen.len(), 1 << Coin::COUNT);
+    }
+
+    #[test]
+    fn test_circulation_weighted_frequencies() {
+        let mut rng = StdRng::seed_from_u64(2024);
+        let trials = 20_000;
+        let draws: Vec<Vec<Coin>> = (0..trials)
+            .map(|_| rng.sample(CirculationWeighted))
+            .collect();
+
+        for coin in Coin::iter() {
+            let hits = draws.iter().filter(|d| d.contains(&coin)).count();
+            let observed = hits as f64 / trials as f64;
+            assert!(
+                (observed - coin.circulation_share()).abs() < 0.02,
+                "{:?} appeared in {:.3} of draws",
+                coin,
+                observed
+            );
+        }
+    }
+
+    #[test]
+    fn test_weighted_random_combination_is_valid() {
+        let all = generate_all_combinations();
+        for _ in 0..20 {
+            let combination = generate_weighted_random_combination();
+            assert!(all.contains(&Combination::from(combination)));
+        }
+    }
+
+    // ========================================================================
+    // RNG Injection Tests
+    // ========================================================================
+
+    #[test]
+    fn test_random_combination_with_mock_rng() {
+        // gen_range(0..16) takes the top four bits of a constant stream, so
+        // zero always picks subset 0 and 15 << 60 always picks subset 15
+        // (u64::MAX would be rejected forever by the unbiasing step)
+        assert_eq!(
+            generate_random_combination_with(&mut StepRng::new(0, 0)),
+            Vec::<Coin>::new()
+        );
+        assert_eq!(
+            generate_random_combination_with(&mut StepRng::new(15 << 60, 0)),
+            Coin::all().to_vec()
+        );
+    }
+
+    #[test]
+    fn test_weighted_random_combination_with_mock_rng() {
+        // gen_bool(p) is true when the next value is below p * 2^64
+        assert_eq!(
+            generate_weighted_random_combination_with(&mut StepRng::new(0, 0)),
+            Coin::all().to_vec()
+        );
+        assert_eq!(
+            generate_weighted_random_combination_with(&mut StepRng::new(u64::MAX, 0)),
+            Vec::<Coin>::new()
+        );
+    }
+
+    #[test]
+    fn test_with_functions_accept_dyn_rng() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let expected = generate_random_combination_with(&mut rng.clone());
+        let dyn_rng: &mut dyn rand::RngCore = &mut rng;
+        assert_eq!(generate_random_combination_with(dyn_rng), expected);
+    }
+
+    // ========================================================================
+    // Fixed-Size Tests
+    // ========================================================================
+
+    #[test]
+    fn test_of_size_has_exactly_k_distinct_coins() {
+        let mut rng = StdRng::seed_from_u64(8);
+        for k in 0..=Coin::COUNT {
+            for _ in 0..50 {
+                let combination = generate_random_combination_of_size_with(&mut rng, k).unwrap();
+                assert_eq!(combination.len(), k);
+                assert!(combination.windows(2).all(|pair| pair[0] < pair[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_of_size_is_uniform() {
+        // The six 2-coin combinations should each come up about 1/6 of the time
+        let mut rng = StdRng::seed_from_u64(2);
+        let trials = 12_000;
+        let mut counts: HashMap<Vec<Coin>, usize> = HashMap::new();
+        for _ in 0..trials {
+            let combination = generate_random_combination_of_size_with(&mut rng, 2).unwrap();
+            *counts.entry(combination).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 6);
+        for (combination, count) in counts {
+            let observed = count as f64 / trials as f64;
+            assert!(
+                (observed - 1.0 / 6.0).abs() < 0.02,
+                "{combination:?} appeared in {observed:.3} of draws"
+            );
+        }
+    }
+
+    #[test]
+    fn test_of_size_too_large() {
+        assert_eq!(generate_random_combination_of_size(Coin::COUNT + 1), None);
+        assert_eq!(
+            generate_random_combination_of_size(Coin::COUNT),
+            Some(Coin::all().to_vec())
+        );
+        assert_eq!(generate_random_combination_of_size(0), Some(vec![]));
+    }
+
+    // ========================================================================
+    // Random Change Tests
+    // ========================================================================
+
+    #[test]
+    fn test_random_change_adds_up() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for amount in [0, 1, 7, 30, 99, 250] {
+            for _ in 0..10 {
+                let change = generate_random_change_for_with(&mut rng, amount).unwrap();
+                assert_eq!(total_value(&change), amount);
+                assert!(change.windows(2).all(|pair| pair[0] <= pair[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_change_is_uniform() {
+        // 10 cents can be paid 4 ways: D, NN, NPPPPP, PPPPPPPPPP
+        let mut rng = StdRng::seed_from_u64(3);
+        let trials = 8_000;
+        let mut counts: HashMap<Vec<Coin>, usize> = HashMap::new();
+        for _ in 0..trials {
+            let change = generate_random_change_for_with(&mut rng, 10).unwrap();
+            *counts.entry(change).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 4);
+        for (change, count) in counts {
+            let observed = count as f64 / trials as f64;
+            assert!(
+                (observed - 0.25).abs() < 0.02,
+                "{change:?} appeared in {observed:.3} of draws"
+            );
+        }
+    }
+
+    #[test]
+    fn test_random_change_reaches_every_way() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let seen: HashSet<Vec<Coin>> = (0..2_000)
+            .map(|_| generate_random_change_for_with(&mut rng, 30).unwrap())
+            .collect();
+        assert_eq!(BigUint::from(seen.len()), crate::count_ways_in_coins(30));
+    }
+
+    #[test]
+    fn test_random_change_for_custom_denominations() {
+        let system = DenominationSet::new([5, 10]).unwrap();
+        let mut rng = StdRng::seed_from_u64(6);
+        assert_eq!(
+            system.random_change_with(&mut rng, 12),
+            Err(ChangeError::Unpayable { amount: 12 })
+        );
+        let change = system.random_change_with(&mut rng, 40).unwrap();
+        assert_eq!(change.iter().sum::<u32>(), 40);
+
+        // [3, 5] pays 15 only as 5 threes or 3 fives
+        let system = DenominationSet::new([3, 5]).unwrap();
+        let seen: HashSet<Vec<u32>> = (0..100)
+            .map(|_| system.random_change_with(&mut rng, 15).unwrap())
+            .collect();
+        assert_eq!(seen.len(), 2);
+    }
+
+    // ========================================================================
+    // Bulk Generation Tests
+    // ========================================================================
+
+    #[test]
+    fn test_random_combinations_count_and_capacity() {
+        let combinations = generate_random_combinations(100);
+        assert_eq!(combinations.len(), 100);
+        assert_eq!(combinations.capacity(), 100);
+        assert!(generate_random_combinations(0).is_empty());
+    }
+
+    #[test]
+    fn test_random_combinations_match_single_draws() {
+        // Bulk draws consume the RNG exactly like repeated single draws
+        let bulk = generate_random_combinations_with(&mut StdRng::seed_from_u64(31), 25);
+        let mut rng = StdRng::seed_from_u64(31);
+        let singles: Vec<Combination> = (0..25)
+            .map(|_| Combination::from(generate_random_combination_with(&mut rng)))
+            .collect();
+        assert_eq!(bulk, singles);
+    }
+
+    // ========================================================================
+    // Random Options Tests
+    // ========================================================================
+
+    #[test]
+    fn test_options_default_matches_plain_draw() {
+        let mut a = StdRng::seed_from_u64(21);
+        let mut b = StdRng::seed_from_u64(21);
+        for _ in 0..20 {
+            assert_eq!(
+                RandomOptions::default().generate_with(&mut a),
+                Some(generate_random_combination_with(&mut b))
+            );
+        }
+    }
+
+    #[test]
+    fn test_options_exclude_empty() {
+        let options = RandomOptions {
+            allow_empty: false,
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(13);
+        let seen: HashSet<Vec<Coin>> = (0..1000)
+            .map(|_| options.generate_with(&mut rng).unwrap())
+            .collect();
+        assert_eq!(seen.len(), (1 << Coin::COUNT) - 1);
+        assert!(!seen.contains(&vec![]));
+    }
+
+    #[test]
+    fn test_options_builder_sets_fields() {
+        let options = RandomOptions::new()
+            .allow_empty(false)
+            .exact_coins(2)
+            .min_coins(1)
+            .max_coins(3)
+            .min_value(10)
+            .max_value(30)
+            .rng(RngAlgorithm::ChaCha8);
+        assert_eq!(
+            options,
+            RandomOptions {
+                allow_empty: false,
+                exact_coins: Some(2),
+                min_coins: Some(1),
+                max_coins: Some(3),
+                min_value: Some(10),
+                max_value: Some(30),
+                rng: RngAlgorithm::ChaCha8,
+            }
+        );
+        assert_eq!(RandomOptions::new(), RandomOptions::default());
+    }
+
+    #[test]
+    fn test_options_exact_coins() {
+        let options = RandomOptions {
+            exact_coins: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(options.generate().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_options_unsatisfiable() {
+        let empty_only = RandomOptions {
+            allow_empty: false,
+            exact_coins: Some(0),
+            ..Default::default()
+        };
+        assert!(!empty_only.is_satisfiable());
+        assert_eq!(empty_only.generate(), None);
+
+        let too_many = RandomOptions {
+            exact_coins: Some(5),
+            ..Default::default()
+        };
+        assert!(!too_many.is_satisfiable());
+        assert_eq!(too_many.generate(), None);
+
+        let too_rich = RandomOptions {
+            min_value: Some(42),
+            ..Default::default()
+        };
+        assert!(!too_rich.is_satisfiable());
+        assert_eq!(too_rich.generate(), None);
+
+        // Pairs are worth 6 to 35 cents
+        let pairs_in_gap = RandomOptions {
+            exact_coins: Some(2),
+            min_value: Some(36),
+            ..Default::default()
+        };
+        assert!(!pairs_in_gap.is_satisfiable());
+    }
+
+    #[test]
+    fn test_options_value_range() {
+        let options = RandomOptions {
+            min_value: Some(10),
+            max_value: Some(30),
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(17);
+        let seen: HashSet<Vec<Coin>> = (0..1000)
+            .map(|_| options.generate_with(&mut rng).unwrap())
+            .collect();
+        // Every combination worth 10 to 30 cents turns up
+        let expected: HashSet<Vec<Coin>> = all_combinations_cached()
+            .iter()
+            .filter(|coins| (10..=30).contains(&total_value(coins).cents()))
+            .map(|coins| coins.to_vec())
+            .collect();
+        assert_eq!(seen, expected);
+        assert_eq!(seen.len(), 7);
+
+        // Seeded draws stay reproducible
+        assert_eq!(options.generate_seeded(3), options.generate_seeded(3));
+    }
+
+    #[test]
+    fn test_options_coin_count_range() {
+        let options = RandomOptions {
+            min_coins: Some(1),
+            max_coins: Some(2),
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(23);
+        let seen: HashSet<Vec<Coin>> = (0..1000)
+            .map(|_| options.generate_with(&mut rng).unwrap())
+            .collect();
+        // 4 singles and 6 pairs
+        assert_eq!(seen.len(), 10);
+        assert!(seen.iter().all(|coins| (1..=2).contains(&coins.len())));
+
+        let backwards = RandomOptions {
+            min_coins: Some(3),
+            max_coins: Some(2),
+            ..Default::default()
+        };
+        assert!(!backwards.is_satisfiable());
+        assert_eq!(backwards.generate(), None);
+    }
+
+    #[test]
+    fn test_options_value_range_with_other_restrictions() {
+        let options = RandomOptions {
+            allow_empty: false,
+            exact_coins: Some(1),
+            max_value: Some(10),
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(19);
+        for _ in 0..100 {
+            let coins = options.generate_with(&mut rng).unwrap();
+            assert_eq!(coins.len(), 1);
+            assert!(coins[0] != Coin::Quarter);
+        }
+
+        let exactly_one = RandomOptions {
+            min_value: Some(41),
+            max_value: Some(41),
+            ..Default::default()
+        };
+        assert_eq!(exactly_one.generate(), Some(Coin::all().to_vec()));
+    }
+
+    // ========================================================================
+    // Custom Weight Tests
+    // ========================================================================
+
+    #[test]
+    fn test_coin_weights_frequencies() {
+        let weights = CoinWeights::new(0.9)
+            .unwrap()
+            .with(Coin::Quarter, 0.1)
+            .unwrap()
+            .with(Coin::Dime, 0.0)
+            .unwrap();
+        let mut rng = StdRng::seed_from_u64(17);
+        let trials = 20_000;
+        let draws: Vec<Vec<Coin>> = (0..trials)
+            .map(|_| generate_random_combination_weighted_with(&mut rng, &weights))
+            .collect();
+
+        for coin in Coin::iter() {
+            let hits = draws.iter().filter(|d| d.contains(&coin)).count();
+            let observed = hits as f64 / trials as f64;
+            assert!(
+                (observed - weights.probability(coin)).abs() < 0.02,
+                "{coin:?} appeared in {observed:.3} of draws"
+            );
+        }
+    }
+
+    #[test]
+    fn test_coin_weights_extremes() {
+        let always = CoinWeights::new(1.0).unwrap();
+        assert_eq!(
+            generate_random_combination_weighted(&always),
+            Coin::all().to_vec()
+        );
+        let never = CoinWeights::new(0.0).unwrap();
+        assert!(generate_random_combination_weighted(&never).is_empty());
+    }
+
+    #[test]
+    fn test_circulation_weights_match_circulation_weighted() {
+        let first: Vec<Vec<Coin>> = CirculationWeighted
+            .sample_iter(StdRng::seed_from_u64(5))
+            .take(20)
+            .collect();
+        let second: Vec<Vec<Coin>> = CoinWeights::circulation()
+            .sample_iter(StdRng::seed_from_u64(5))
+            .take(20)
+            .collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_coin_weights_reject_invalid_probabilities() {
+        for value in [-0.1, 1.5, f64::NAN, f64::INFINITY] {
+            assert!(CoinWeights::new(value).is_err(), "{value} was accepted");
+        }
+        let error = CoinWeights::default().with(Coin::Penny, 2.0).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid probability 2: must be between 0 and 1"
+        );
+        assert_eq!(CoinWeights::default().probability(Coin::Dime), 0.5);
+    }
+
+    // ========================================================================
+    // Seeded generators
+    // ========================================================================
+
+    #[test]
+    fn test_chacha8_draws_are_pinned() {
+        // Fixed for rand 0.8: a change here means seeds stored elsewhere break
+        assert_eq!(
+            RngAlgorithm::ChaCha8.seeded(42).gen::<u64>(),
+            12578764544318200737
+        );
+        let options = RandomOptions {
+            rng: RngAlgorithm::ChaCha8,
+            ..Default::default()
+        };
+        let draws: Vec<Vec<Coin>> = (0..4)
+            .map(|seed| options.generate_seeded(seed).unwrap())
+            .collect();
+        assert_eq!(
+            draws,
+            vec![
+                vec![Coin::Penny, Coin::Nickel, Coin::Quarter],
+                vec![Coin::Nickel, Coin::Dime],
+                vec![Coin::Nickel, Coin::Dime, Coin::Quarter],
+                vec![Coin::Penny],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_generate_seeded_matches_seeded_combination() {
+        let options = RandomOptions::default();
+        for seed in [0, 7, u64::MAX] {
+            assert_eq!(
+                options.generate_seeded(seed),
+                Some(crate::generate_random_combination_seeded(seed))
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_seeded_respects_options() {
+        let options = RandomOptions {
+            exact_coins: Some(2),
+            rng: RngAlgorithm::ChaCha8,
+            ..Default::default()
+        };
+        for seed in 0..20 {
+            assert_eq!(options.generate_seeded(seed).unwrap().len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_rng_algorithm_parse() {
+        for algorithm in RngAlgorithm::all() {
+            assert_eq!(algorithm.name().parse(), Ok(algorithm));
+            assert_eq!(algorithm.to_string(), algorithm.name());
+        }
+        assert_eq!("ChaCha8".parse(), Ok(RngAlgorithm::ChaCha8));
+        let error = "mersenne".parse::<RngAlgorithm>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown RNG 'mersenne': expected one of std, chacha8"
+        );
+    }
+
+    // ========================================================================
+    // Shuffled enumeration
+    // ========================================================================
+
+    #[test]
+    fn test_shuffled_yields_every_combination_once() {
+        let shuffled: Vec<Combination> =
+            generate_all_combinations_shuffled(StdRng::seed_from_u64(1)).collect();
+        let mut sorted = shuffled.clone();
+        sorted.sort();
+        let mut all = generate_all_combinations();
+        all.sort();
+        assert_eq!(sorted, all);
+        // With 16! orders, a seeded shuffle is not the identity
+        assert_ne!(shuffled, generate_all_combinations());
+    }
+
+    #[test]
+    fn test_shuffled_is_reproducible_and_sized() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut first = generate_all_combinations_shuffled(&mut rng);
+        assert_eq!(first.len(), 16);
+        first.next();
+        assert_eq!(first.len(), 15);
+        assert_eq!(first.by_ref().count(), 15);
+        assert_eq!(first.next(), None);
+
+        let a: Vec<_> = generate_all_combinations_shuffled(StdRng::seed_from_u64(3)).collect();
+        let b: Vec<_> = generate_all_combinations_shuffled(StdRng::seed_from_u64(3)).collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_shuffled_first_item_is_uniform() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut firsts: HashMap<Combination, usize> = HashMap::new();
+        for _ in 0..16_000 {
+            let first = generate_all_combinations_shuffled(&mut rng).next().unwrap();
+            *firsts.entry(first).or_default() += 1;
+        }
+        assert_eq!(firsts.len(), 16);
+        for count in firsts.values() {
+            assert!((800..1200).contains(count), "count {count}");
+        }
+    }
+
+    // ========================================================================
+    // Endless streams
+    // ========================================================================
+
+    #[test]
+    fn test_random_stream_matches_repeated_draws() {
+        let options = RandomOptions {
+            allow_empty: false,
+            ..Default::default()
+        };
+        let streamed: Vec<Combination> = random_stream_with(StdRng::seed_from_u64(5), options)
+            .take(50)
+            .collect();
+        let mut rng = StdRng::seed_from_u64(5);
+        let drawn: Vec<Combination> = (0..50)
+            .map(|_| options.generate_with(&mut rng).unwrap().into())
+            .collect();
+        assert_eq!(streamed, drawn);
+    }
+
+    #[test]
+    fn test_random_stream_keeps_going() {
+        let count = random_stream(RandomOptions::default()).take(10_000).count();
+        assert_eq!(count, 10_000);
+    }
+
+    #[test]
+    fn test_random_stream_of_unsatisfiable_options_is_empty() {
+        let impossible = RandomOptions {
+            allow_empty: false,
+            exact_coins: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(random_stream(impossible).next(), None);
+    }
+
+    // ========================================================================
+    // CoinDistribution
+    // ========================================================================
+
+    #[test]
+    fn test_coin_distribution_from_probabilities() {
+        let distribution =
+            CoinDistribution::from_probabilities([(Coin::Penny, 1.0), (Coin::Dime, 0.25)]).unwrap();
+        let weights = distribution.weights();
+        assert_eq!(weights.probability(Coin::Penny), 1.0);
+        assert_eq!(weights.probability(Coin::Nickel), 0.0);
+        assert_eq!(weights.probability(Coin::Dime), 0.25);
+
+        let mut rng = StdRng::seed_from_u64(6);
+        for _ in 0..100 {
+            let combination: Combination = rng.sample(distribution);
+            assert!(combination.contains(&Coin::Penny));
+            assert!(!combination.contains(&Coin::Quarter));
+        }
+    }
+
+    #[test]
+    fn test_coin_distribution_rejects_bad_probabilities() {
+        let error = CoinDistribution::from_probabilities([(Coin::Quarter, 1.5)]).unwrap_err();
+        assert_eq!(error.value(), 1.5);
+    }
+
+    #[test]
+    fn test_coin_distribution_from_frequencies() {
+        let observed = [
+            vec![Coin::Penny, Coin::Dime],
+            vec![Coin::Penny],
+            vec![Coin::Penny, Coin::Quarter],
+            vec![],
+        ];
+        let weights = *CoinDistribution::from_frequencies(&observed)
+            .unwrap()
+            .weights();
+        assert_eq!(weights.probability(Coin::Penny), 0.75);
+        assert_eq!(weights.probability(Coin::Nickel), 0.0);
+        assert_eq!(weights.probability(Coin::Dime), 0.25);
+        assert_eq!(weights.probability(Coin::Quarter), 0.25);
+
+        assert_eq!(
+            CoinDistribution::from_frequencies(Vec::<Combination>::new()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_coin_distribution_refits_its_own_samples() {
+        let original = CoinDistribution::new(CoinWeights::circulation());
+        let samples: Vec<Combination> = original
+            .sample_iter(StdRng::seed_from_u64(7))
+            .take(20_000)
+            .collect();
+        let fitted = CoinDistribution::from_frequencies(&samples).unwrap();
+        for coin in Coin::iter() {
+            let expected = original.weights().probability(coin);
+            let actual = fitted.weights().probability(coin);
+            assert!((expected - actual).abs() < 0.01, "{coin:?}: {actual}");
+        }
+    }
+
+    #[test]
+    fn test_coin_distribution_samples_match_coin_weights() {
+        let weights = CoinWeights::circulation();
+        let distribution = CoinDistribution::from(weights);
+        let as_vec: Vec<Coin> = distribution.sample(&mut StdRng::seed_from_u64(8));
+        let as_combination: Combination = distribution.sample(&mut StdRng::seed_from_u64(8));
+        assert_eq!(as_combination, as_vec);
+        assert_eq!(as_vec, weights.sample(&mut StdRng::seed_from_u64(8)));
+        assert_eq!(
+            CoinDistribution::default().weights(),
+            &CoinWeights::default()
+        );
+    }
+
+    // ========================================================================
+    // Stratified sampling
+    // ========================================================================
+
+    #[test]
+    fn test_stratified_covers_every_bucket() {
+        let buckets = [0..=10, 11..=25, 26..=41];
+        let mut rng = StdRng::seed_from_u64(10);
+        let groups = sample_stratified_with(&mut rng, &buckets, 5).unwrap();
+
+        assert_eq!(groups.len(), 3);
+        for (group, bucket) in groups.iter().zip(&buckets) {
+            assert_eq!(group.len(), 5);
+            for combination in group {
+                assert!(bucket.contains(&total_value(combination).cents()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_stratified_reaches_every_member_of_a_bucket() {
+        // 0, 1, 5, 6 and 10
+        let groups =
+            sample_stratified_with(&mut StdRng::seed_from_u64(11), &[0..=10], 200).unwrap();
+        let values: HashSet<u64> = groups[0]
+            .iter()
+            .map(|combination| total_value(combination).cents())
+            .collect();
+        assert_eq!(values, HashSet::from([0, 1, 5, 6, 10]));
+    }
+
+    #[test]
+    fn test_stratified_rejects_empty_bucket() {
+        let error = sample_stratified(&[0..=10, 2..=4], 3).unwrap_err();
+        assert_eq!(error.bucket(), &(2..=4));
+        assert_eq!(
+            error.to_string(),
+            "no combination is worth between 2 and 4 cents"
+        );
+        // Reversed ranges are empty too
+        assert!(sample_stratified(&[RangeInclusive::new(10, 0)], 1).is_err());
+    }
+
+    #[test]
+    fn test_stratified_edge_sizes() {
+        assert_eq!(sample_stratified(&[], 3), Ok(vec![]));
+        assert_eq!(sample_stratified(&[41..=41], 0), Ok(vec![vec![]]));
+        let full = sample_stratified(&[41..=41], 2).unwrap();
+        assert_eq!(full[0][0], Coin::all());
+    }
+
+    // ========================================================================
+    // Random multisets
+    // ========================================================================
+
+    #[test]
+    fn test_random_multiset_is_a_counted_combination() {
+        let all = crate::generate_combinations_with_counts(|_| 3);
+        let mut rng = StdRng::seed_from_u64(12);
+        for _ in 0..200 {
+            assert!(all.contains(&generate_random_multiset_with(&mut rng, 3)));
+        }
+    }
+
+    #[test]
+    fn test_random_multiset_reaches_every_handful() {
+        let mut rng = StdRng::seed_from_u64(13);
+        let seen: HashSet<Combination> = (0..2_000)
+            .map(|_| generate_random_multiset_with(&mut rng, 2))
+            .collect();
+        assert_eq!(seen.len(), 81);
+    }
+
+    #[test]
+    fn test_random_multiset_limits() {
+        assert!(generate_random_multiset(0).is_empty());
+        let mut rng = StdRng::seed_from_u64(14);
+        for _ in 0..50 {
+            let coins = generate_random_multiset_with(&mut rng, 1);
+            assert!(all_combinations_cached().contains(&coins));
+        }
+    }
+
+    // ========================================================================
+    // Random purses
+    // ========================================================================
+
+    #[test]
+    fn test_random_purse_stays_within_limit() {
+        let mut rng = StdRng::seed_from_u64(12);
+        for _ in 0..200 {
+            let purse = generate_random_purse_with(&mut rng, 10);
+            for coin in Coin::iter() {
+                assert!(purse.count_of(coin) <= 10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_purse_reaches_every_count() {
+        let mut rng = StdRng::seed_from_u64(13);
+        let mut seen = HashSet::new();
+        for _ in 0..500 {
+            seen.insert(generate_random_purse_with(&mut rng, 3).count_of(Coin::Dime));
+        }
+        assert_eq!(seen, HashSet::from([0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn test_random_purse_mean_count() {
+        let mut rng = StdRng::seed_from_u64(14);
+        let trials = 4_000;
+        let pennies: u32 = (0..trials)
+            .map(|_| generate_random_purse_with(&mut rng, 10).count_of(Coin::Penny))
+            .sum();
+        let mean = f64::from(pennies) / f64::from(trials);
+        assert!((mean - 5.0).abs() < 0.2, "mean {mean}");
+    }
+
+    #[test]
+    fn test_random_purse_with_zero_limit_is_empty() {
+        assert_eq!(generate_random_purse(0), Purse::new());
+        // The largest limit neither overflows the range nor the total
+        let purse = generate_random_purse_with(&mut StdRng::seed_from_u64(1), u32::MAX);
+        assert!(purse.checked_total_value().is_ok());
+    }
+
+    // ========================================================================
+    // Combination of the day
+    // ========================================================================
+
+    #[test]
+    fn test_daily_combination_is_stable_for_a_date() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        assert_eq!(daily_combination(date), daily_combination(date));
+        // Pinned like the ChaCha8 draws: everyone must agree on today's pick
+        assert_eq!(
+            daily_combination(date),
+            vec![Coin::Penny, Coin::Dime, Coin::Quarter]
+        );
+    }
+
+    #[test]
+    fn test_daily_combination_changes_between_days() {
+        let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let picks: HashSet<Vec<Coin>> = start.iter_days().take(60).map(daily_combination).collect();
+        assert!(picks.len() > 8, "only {} distinct picks", picks.len());
+    }
+
+    #[test]
+    fn test_daily_seed_counts_days() {
+        let day = |y, m, d| daily_seed(NaiveDate::from_ymd_opt(y, m, d).unwrap());
+        assert_eq!(day(1, 1, 1), 1);
+        assert_eq!(day(2025, 1, 2), day(2025, 1, 1) + 1);
+    }
+
+    #[test]
+    fn test_same_seed_same_samples() {
+        let first: Vec<Vec<Coin>> = UniformCombination
+            .sample_iter(StdRng::seed_from_u64(99))
+            .take(10)
+            .collect();
+        let second: Vec<Vec<Coin>> = UniformCombination
+            .sample_iter(StdRng::seed_from_u64(99))
+            .take(10)
+            .collect();
+        assert_eq!(first, second);
+    }
+}
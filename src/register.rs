@@ -0,0 +1,603 @@
+// ============================================================================
+// REGISTER MODULE: Making Change from a Cash Drawer
+// ============================================================================
+// A cash drawer holds a limited supply of each coin, so change has to come
+// out of what is actually in it:
+//
+//   let mut drawer = CashDrawer::new(float);
+//   let change = drawer.sale(65, &tendered)?;   // 65¢ paid with a dollar's worth
+//   drawer.balance()                            // up by 65¢
+//
+// The customer's coins go into the drawer before change is counted out, so
+// they can be handed straight back as part of the change, as at a real till.
+//
+// Deposits, withdrawals and sales are recorded in the drawer's
+// TransactionLog; replaying it over the opening float gives the coins in
+// the drawer now. undo() and redo() step back and forth through them, and
+// are logged too.
+//
+// A drawer made with_capacity() has only so much room per coin, and in all;
+// deposits and sales that would overfill it are refused.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt;
+use ts_rs::TS;
+
+use crate::{
+    make_change_from, Capacity, ChangeError, Coin, Money, NotEnoughCoinsError, Operation,
+    OverflowError, Purse, ReconciliationReport, TransactionLog,
+};
+
+/// Error returned when a sale can't go through
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaleError {
+    /// The coins tendered are worth less than the price
+    Underpaid { price: u64, tendered: u64 },
+    /// The drawer can't make the change exactly
+    NoChange(ChangeError),
+    /// The drawer has no room for the coins tendered
+    Full(OverflowError),
+}
+
+impl fmt::Display for SaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaleError::Underpaid { price, tendered } => write!(
+                f,
+                "{} tendered for a price of {} (short by {})",
+                tendered,
+                price,
+                price - tendered
+            ),
+            SaleError::NoChange(error) => write!(f, "cannot give change: {}", error),
+            SaleError::Full(error) => write!(f, "drawer is full: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SaleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaleError::NoChange(error) => Some(error),
+            SaleError::Full(error) => Some(error),
+            SaleError::Underpaid { .. } => None,
+        }
+    }
+}
+
+/// The coins in a till, which sales are paid into and change comes out of
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CashDrawer {
+    float: Purse,
+    coins: Purse,
+    capacity: Capacity,
+    log: TransactionLog,
+    // Operations undo() can reverse, most recent last
+    undoable: Vec<Operation>,
+    // Operations undo() reversed that redo() can repeat, most recent last
+    redoable: Vec<Operation>,
+}
+
+impl CashDrawer {
+    /// A drawer starting with the coins in `float`, with unlimited room
+    pub fn new(float: Purse) -> Self {
+        CashDrawer {
+            coins: float.clone(),
+            float,
+            capacity: Capacity::unlimited(),
+            log: TransactionLog::new(),
+            undoable: Vec::new(),
+            redoable: Vec::new(),
+        }
+    }
+
+    /// A drawer starting with the coins in `float` that holds no more than
+    /// `capacity`, or an error holding the coins of the float that don't fit
+    pub fn with_capacity(float: Purse, capacity: Capacity) -> Result<Self, OverflowError> {
+        let rejected = capacity.overflow(&Purse::new(), &float);
+        if !rejected.is_empty() {
+            return Err(OverflowError { rejected });
+        }
+        Ok(CashDrawer {
+            capacity,
+            ..CashDrawer::new(float)
+        })
+    }
+
+    /// How many coins the drawer can hold
+    pub fn capacity(&self) -> &Capacity {
+        &self.capacity
+    }
+
+    /// The coins currently in the drawer
+    pub fn coins(&self) -> &Purse {
+        &self.coins
+    }
+
+    /// The coins the drawer started with
+    pub fn float(&self) -> &Purse {
+        &self.float
+    }
+
+    /// Every deposit, withdrawal and sale so far
+    pub fn log(&self) -> &TransactionLog {
+        &self.log
+    }
+
+    /// Total value of the coins in the drawer
+    pub fn balance(&self) -> Money {
+        self.coins.total_value()
+    }
+
+    /// Puts coins into the drawer without a sale, e.g. to top up the float
+    ///
+    /// Fails without changing anything if they don't all fit, and the error
+    /// holds the coins there is no room for.
+    pub fn deposit(&mut self, coins: &Purse) -> Result<(), OverflowError> {
+        let rejected = self.capacity.overflow(&self.coins, coins);
+        if !rejected.is_empty() {
+            return Err(OverflowError { rejected });
+        }
+        self.apply(Operation::Add {
+            coins: coins.clone(),
+        })
+        .expect("adding coins can't fail");
+        Ok(())
+    }
+
+    /// Takes coins out of the drawer without a sale, or fails without
+    /// changing anything if the drawer doesn't hold them all
+    pub fn withdraw(&mut self, coins: &Purse) -> Result<(), NotEnoughCoinsError> {
+        self.apply(Operation::Remove {
+            coins: coins.clone(),
+        })
+    }
+
+    /// Sells something costing `price` cents for the coins `tendered`,
+    /// returning the change handed back
+    ///
+    /// The change is the fewest coins the drawer can make it with. If the
+    /// coins tendered fall short of the price, or the drawer can't make the
+    /// change exactly, or there is no room for the coins it keeps, the sale
+    /// is refused and the drawer is left as it was.
+    pub fn sale(&mut self, price: u64, tendered: &Purse) -> Result<Purse, SaleError> {
+        let paid = tendered.total_value().cents();
+        if paid < price {
+            return Err(SaleError::Underpaid {
+                price,
+                tendered: paid,
+            });
+        }
+
+        let mut available = self.coins.clone();
+        Operation::Add {
+            coins: tendered.clone(),
+        }
+        .apply(&mut available)
+        .expect("adding coins can't fail");
+        let change = make_change_from(&available, paid - price).map_err(SaleError::NoChange)?;
+        let kept = available
+            .checked_sub(&change)
+            .expect("change is made from coins in the drawer");
+        let rejected = self.capacity.overflow(&Purse::new(), &kept);
+        if !rejected.is_empty() {
+            return Err(SaleError::Full(OverflowError { rejected }));
+        }
+
+        self.apply(Operation::Sale {
+            price,
+            tendered: tendered.clone(),
+            change: change.clone(),
+        })
+        .expect("change is made from coins in the drawer");
+        Ok(change)
+    }
+
+    /// Reverses the most recent deposit, withdrawal or sale not already
+    /// undone, returning it, or None if there is nothing to undo
+    ///
+    /// The reversal is appended to the log as an Operation::Undo rather than
+    /// erasing the original entry. A new deposit, withdrawal or sale clears
+    /// what redo() could repeat.
+    pub fn undo(&mut self) -> Option<Operation> {
+        let operation = self.undoable.pop()?;
+        self.perform(operation.clone().undo())
+            .expect("the drawer is as the operation left it");
+        self.redoable.push(operation.clone());
+        Some(operation)
+    }
+
+    /// Repeats the most recently undone operation, returning it, or None if
+    /// there is nothing to redo
+    pub fn redo(&mut self) -> Option<Operation> {
+        let operation = self.redoable.pop()?;
+        self.perform(operation.clone())
+            .expect("the drawer is as it was before the operation");
+        self.undoable.push(operation.clone());
+        Some(operation)
+    }
+
+    /// Whether undo() would do anything
+    pub fn can_undo(&self) -> bool {
+        !self.undoable.is_empty()
+    }
+
+    /// Whether redo() would do anything
+    pub fn can_redo(&self) -> bool {
+        !self.redoable.is_empty()
+    }
+
+    // Applies a new `operation`, or does nothing if it fails
+    fn apply(&mut self, operation: Operation) -> Result<(), NotEnoughCoinsError> {
+        self.perform(operation.clone())?;
+        self.undoable.push(operation);
+        self.redoable.clear();
+        Ok(())
+    }
+
+    // Applies `operation` to the coins and logs it, or does neither
+    fn perform(&mut self, operation: Operation) -> Result<(), NotEnoughCoinsError> {
+        operation.apply(&mut self.coins)?;
+        let balance = self.balance();
+        self.log.record(operation, balance);
+        Ok(())
+    }
+
+    /// Compares `counted`, a count of the coins actually in the drawer,
+    /// with what its transactions say it should hold
+    pub fn reconcile(&self, counted: &Purse) -> ReconciliationReport {
+        counted.reconcile(&self.coins)
+    }
+
+    /// Count and value of each coin in the drawer
+    pub fn report(&self) -> DrawerReport {
+        let lines: Vec<DrawerLine> = Coin::iter()
+            .map(|coin| {
+                let count = self.coins.count_of(coin);
+                DrawerLine {
+                    coin,
+                    count,
+                    value: Money::from(coin) * u64::from(count),
+                }
+            })
+            .collect();
+        DrawerReport {
+            lines,
+            coin_count: self.coins.coin_count(),
+            balance: self.balance(),
+        }
+    }
+}
+
+impl From<Purse> for CashDrawer {
+    fn from(float: Purse) -> Self {
+        CashDrawer::new(float)
+    }
+}
+
+/// Count and value of one coin type in a drawer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "DrawerLine.d.ts")]
+pub struct DrawerLine {
+    pub coin: Coin,
+    pub count: u32,
+    pub value: Money,
+}
+
+/// Contents of a cash drawer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "DrawerReport.d.ts")]
+pub struct DrawerReport {
+    /// One line per coin type, in ascending value order
+    pub lines: Vec<DrawerLine>,
+    /// Number of physical coins in the drawer
+    #[ts(type = "number")]
+    pub coin_count: u64,
+    /// Total value of the drawer
+    pub balance: Money,
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn purse(counts: &[(Coin, u32)]) -> Purse {
+        counts.iter().copied().collect()
+    }
+
+    fn float() -> Purse {
+        purse(&[
+            (Coin::Penny, 10),
+            (Coin::Nickel, 4),
+            (Coin::Dime, 5),
+            (Coin::Quarter, 4),
+        ])
+    }
+
+    // ========================================================================
+    // Sales
+    // ========================================================================
+
+    #[test]
+    fn test_sale_gives_fewest_coins_change() {
+        let mut drawer = CashDrawer::new(float());
+        let before = drawer.balance();
+
+        let change = drawer.sale(59, &purse(&[(Coin::Quarter, 3)])).unwrap();
+        // 16¢: a dime, a nickel and a penny
+        assert_eq!(
+            change,
+            purse(&[(Coin::Penny, 1), (Coin::Nickel, 1), (Coin::Dime, 1)])
+        );
+        assert_eq!(drawer.balance(), before + Money::from_cents(59));
+        assert_eq!(drawer.coins().count_of(Coin::Quarter), 7);
+        assert_eq!(drawer.coins().count_of(Coin::Dime), 4);
+    }
+
+    #[test]
+    fn test_exact_payment_needs_no_change() {
+        let mut drawer = CashDrawer::default();
+        let change = drawer.sale(30, &purse(&[(Coin::Quarter, 1), (Coin::Nickel, 1)]));
+        assert_eq!(change, Ok(Purse::new()));
+        assert_eq!(drawer.balance(), 30);
+    }
+
+    #[test]
+    fn test_tendered_coins_can_be_given_back() {
+        // An empty drawer can still hand back the customer's own nickel
+        let mut drawer = CashDrawer::default();
+        let change = drawer
+            .sale(25, &purse(&[(Coin::Quarter, 1), (Coin::Nickel, 1)]))
+            .unwrap();
+        assert_eq!(change, purse(&[(Coin::Nickel, 1)]));
+        assert_eq!(drawer.coins(), &purse(&[(Coin::Quarter, 1)]));
+    }
+
+    #[test]
+    fn test_underpaid_sale_is_refused() {
+        let mut drawer = CashDrawer::new(float());
+        let error = drawer.sale(50, &purse(&[(Coin::Quarter, 1)])).unwrap_err();
+        assert_eq!(
+            error,
+            SaleError::Underpaid {
+                price: 50,
+                tendered: 25
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "25 tendered for a price of 50 (short by 25)"
+        );
+        assert_eq!(drawer, CashDrawer::new(float()));
+    }
+
+    #[test]
+    fn test_sale_without_exact_change_is_refused() {
+        // Only quarters to give 5¢ change with
+        let mut drawer = CashDrawer::new(purse(&[(Coin::Quarter, 10)]));
+        let error = drawer.sale(20, &purse(&[(Coin::Quarter, 1)])).unwrap_err();
+        assert_eq!(
+            error,
+            SaleError::NoChange(ChangeError::NoExactChange {
+                amount: 5,
+                closest_below: 0
+            })
+        );
+        assert!(error.to_string().starts_with("cannot give change: "));
+        // Nothing was taken in or paid out
+        assert_eq!(drawer.coins(), &purse(&[(Coin::Quarter, 10)]));
+    }
+
+    // ========================================================================
+    // Stocking and reporting
+    // ========================================================================
+
+    #[test]
+    fn test_deposit_and_withdraw() {
+        let mut drawer = CashDrawer::default();
+        drawer.deposit(&float()).unwrap();
+        assert_eq!(drawer.coins(), &float());
+
+        assert!(drawer.withdraw(&purse(&[(Coin::Dime, 5)])).is_ok());
+        assert_eq!(drawer.coins().count_of(Coin::Dime), 0);
+
+        // All or nothing
+        let error = drawer
+            .withdraw(&purse(&[(Coin::Penny, 1), (Coin::Dime, 1)]))
+            .unwrap_err();
+        assert_eq!(error.coin, Coin::Dime);
+        assert_eq!(drawer.coins().count_of(Coin::Penny), 10);
+    }
+
+    #[test]
+    fn test_operations_are_logged() {
+        let mut drawer = CashDrawer::new(float());
+        drawer.deposit(&purse(&[(Coin::Quarter, 4)])).unwrap();
+        drawer.sale(59, &purse(&[(Coin::Quarter, 3)])).unwrap();
+        drawer.withdraw(&purse(&[(Coin::Dime, 2)])).unwrap();
+        // Refused sales and withdrawals leave no trace
+        assert!(drawer.sale(500, &purse(&[(Coin::Penny, 1)])).is_err());
+        assert!(drawer.withdraw(&purse(&[(Coin::Nickel, 100)])).is_err());
+
+        let log = drawer.log();
+        assert_eq!(log.len(), 3);
+        assert!(matches!(log.entries()[0].operation, Operation::Add { .. }));
+        assert!(matches!(
+            log.entries()[1].operation,
+            Operation::Sale { price: 59, .. }
+        ));
+        assert_eq!(log.entries()[1].balance, 180 + 100 + 59);
+        assert_eq!(log.last().unwrap().balance, drawer.balance());
+        assert_eq!(log.replay(drawer.float()).as_ref(), Ok(drawer.coins()));
+    }
+
+    #[test]
+    fn test_end_of_day_reconciliation() {
+        let mut drawer = CashDrawer::new(float());
+        drawer.sale(59, &purse(&[(Coin::Quarter, 3)])).unwrap();
+        assert!(drawer.reconcile(&drawer.coins().clone()).balanced);
+
+        // A dime short at the end of the day
+        let mut counted = drawer.coins().clone();
+        counted.remove(Coin::Dime, 1).unwrap();
+        let report = drawer.reconcile(&counted);
+        assert_eq!(report.shortage, purse(&[(Coin::Dime, 1)]));
+        assert_eq!(report.value_delta, -10);
+    }
+
+    // ========================================================================
+    // Capacity
+    // ========================================================================
+
+    fn slots() -> Capacity {
+        Capacity::unlimited()
+            .with_coin_limit(Coin::Quarter, 5)
+            .with_total_limit(30)
+    }
+
+    #[test]
+    fn test_float_must_fit() {
+        let drawer = CashDrawer::with_capacity(float(), slots()).unwrap();
+        assert_eq!(drawer.capacity(), &slots());
+        assert_eq!(CashDrawer::new(float()).capacity(), &Capacity::unlimited());
+
+        let error = CashDrawer::with_capacity(purse(&[(Coin::Quarter, 7)]), slots()).unwrap_err();
+        assert_eq!(error.rejected, purse(&[(Coin::Quarter, 2)]));
+    }
+
+    #[test]
+    fn test_deposit_that_overfills_is_refused() {
+        let mut drawer = CashDrawer::with_capacity(float(), slots()).unwrap();
+        let error = drawer
+            .deposit(&purse(&[(Coin::Penny, 4), (Coin::Quarter, 2)]))
+            .unwrap_err();
+        assert_eq!(error.rejected, purse(&[(Coin::Quarter, 1)]));
+        assert_eq!(drawer.coins(), &float());
+        assert!(drawer.log().is_empty());
+
+        // 23 coins held: room for 7 more
+        let error = drawer.deposit(&purse(&[(Coin::Penny, 8)])).unwrap_err();
+        assert_eq!(error.rejected, purse(&[(Coin::Penny, 1)]));
+        assert!(drawer.deposit(&purse(&[(Coin::Penny, 7)])).is_ok());
+    }
+
+    #[test]
+    fn test_sale_that_overfills_is_refused() {
+        let mut drawer = CashDrawer::with_capacity(float(), slots()).unwrap();
+        // Two quarters in for a 50¢ item: one more quarter than the slot takes
+        let error = drawer.sale(50, &purse(&[(Coin::Quarter, 2)])).unwrap_err();
+        assert_eq!(
+            error,
+            SaleError::Full(OverflowError {
+                rejected: purse(&[(Coin::Quarter, 1)])
+            })
+        );
+        assert_eq!(
+            error.to_string(),
+            "drawer is full: no room for 1 coin worth $0.25"
+        );
+        assert_eq!(drawer.coins(), &float());
+
+        // Change going out makes room for what comes in
+        let change = drawer.sale(25, &purse(&[(Coin::Quarter, 2)])).unwrap();
+        assert_eq!(change, purse(&[(Coin::Quarter, 1)]));
+        assert_eq!(drawer.coins().count_of(Coin::Quarter), 5);
+    }
+
+    // ========================================================================
+    // Undo and redo
+    // ========================================================================
+
+    #[test]
+    fn test_undo_and_redo_a_sale() {
+        let mut drawer = CashDrawer::new(float());
+        let change = drawer.sale(59, &purse(&[(Coin::Quarter, 3)])).unwrap();
+        let after_sale = drawer.coins().clone();
+
+        let undone = drawer.undo().unwrap();
+        assert!(matches!(undone, Operation::Sale { price: 59, .. }));
+        assert_eq!(drawer.coins(), &float());
+        assert!(!drawer.can_undo());
+        assert!(drawer.can_redo());
+
+        let redone = drawer.redo().unwrap();
+        assert_eq!(redone, undone);
+        assert_eq!(drawer.coins(), &after_sale);
+        assert!(matches!(&redone, Operation::Sale { change: c, .. } if c == &change));
+        assert_eq!(drawer.redo(), None);
+    }
+
+    #[test]
+    fn test_undo_steps_back_in_order() {
+        let mut drawer = CashDrawer::default();
+        drawer.deposit(&purse(&[(Coin::Dime, 3)])).unwrap();
+        drawer.withdraw(&purse(&[(Coin::Dime, 1)])).unwrap();
+        drawer.deposit(&purse(&[(Coin::Penny, 4)])).unwrap();
+
+        drawer.undo();
+        assert_eq!(drawer.coins(), &purse(&[(Coin::Dime, 2)]));
+        drawer.undo();
+        assert_eq!(drawer.coins(), &purse(&[(Coin::Dime, 3)]));
+        drawer.undo();
+        assert!(drawer.coins().is_empty());
+        assert_eq!(drawer.undo(), None);
+
+        drawer.redo();
+        drawer.redo();
+        assert_eq!(drawer.coins(), &purse(&[(Coin::Dime, 2)]));
+    }
+
+    #[test]
+    fn test_new_operation_clears_redo() {
+        let mut drawer = CashDrawer::default();
+        drawer.deposit(&purse(&[(Coin::Dime, 1)])).unwrap();
+        drawer.undo();
+        drawer.deposit(&purse(&[(Coin::Nickel, 1)])).unwrap();
+        assert!(!drawer.can_redo());
+        assert_eq!(drawer.redo(), None);
+        assert_eq!(drawer.coins(), &purse(&[(Coin::Nickel, 1)]));
+    }
+
+    #[test]
+    fn test_undo_and_redo_are_logged() {
+        let mut drawer = CashDrawer::new(float());
+        drawer.deposit(&purse(&[(Coin::Quarter, 2)])).unwrap();
+        drawer.undo();
+        drawer.redo();
+        drawer.undo();
+
+        let log = drawer.log();
+        assert_eq!(log.len(), 4);
+        assert!(matches!(log.entries()[1].operation, Operation::Undo { .. }));
+        assert!(matches!(log.entries()[2].operation, Operation::Add { .. }));
+        assert_eq!(log.last().unwrap().balance, 180);
+        assert_eq!(log.replay(drawer.float()).as_ref(), Ok(drawer.coins()));
+    }
+
+    #[test]
+    fn test_report() {
+        let report = CashDrawer::from(float()).report();
+        assert_eq!(report.lines.len(), 4);
+        assert_eq!(report.lines[3].coin, Coin::Quarter);
+        assert_eq!(report.lines[3].count, 4);
+        assert_eq!(report.lines[3].value, 100);
+        assert_eq!(report.coin_count, 23);
+        assert_eq!(report.balance, 10 + 20 + 50 + 100);
+        assert_eq!(
+            report.lines.iter().map(|line| line.value).sum::<Money>(),
+            report.balance
+        );
+    }
+
+    #[test]
+    fn test_report_of_empty_drawer() {
+        let report = CashDrawer::default().report();
+        assert!(report.lines.iter().all(|line| line.count == 0));
+        assert_eq!(report.balance, 0);
+    }
+}
@@ -0,0 +1,145 @@
+// ============================================================================
+// ROLLS MODULE: Coin Roll Packaging
+// ============================================================================
+// Banks accept loose coins in paper rolls of a standard size:
+//
+//   Coin     Coins per roll   Roll value
+//   Penny    50               $0.50
+//   Nickel   40               $2.00
+//   Dime     50               $5.00
+//   Quarter  40               $10.00
+//
+// pack() works out how many full rolls a purse fills and what is left over.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::{Coin, Money, Purse};
+
+impl Coin {
+    /// Number of coins in a standard US bank roll
+    pub fn coins_per_roll(&self) -> u32 {
+        match self {
+            Coin::Penny => 50,
+            Coin::Nickel => 40,
+            Coin::Dime => 50,
+            Coin::Quarter => 40,
+        }
+    }
+
+    /// Face value of a full roll
+    pub fn roll_value(&self) -> Money {
+        Money::from(*self) * u64::from(self.coins_per_roll())
+    }
+}
+
+/// Rolls packed for a single coin type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "RollLine.d.ts")]
+pub struct RollLine {
+    pub coin: Coin,
+    pub coins_per_roll: u32,
+    /// Complete rolls filled
+    pub rolls: u32,
+    /// Coins left over that don't fill a roll
+    pub loose: u32,
+    /// Value of the complete rolls
+    pub rolled_value: Money,
+    /// Value of the loose coins
+    pub loose_value: Money,
+}
+
+/// Result of packing a purse into rolls
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "RollReport.d.ts")]
+pub struct RollReport {
+    /// One line per coin type, in ascending value order
+    pub lines: Vec<RollLine>,
+    pub total_rolls: u32,
+    pub total_rolled_value: Money,
+    pub total_loose_value: Money,
+}
+
+/// Packs the coins in a purse into standard bank rolls
+pub fn pack(counts: &Purse) -> RollReport {
+    let lines: Vec<RollLine> = Coin::iter()
+        .map(|coin| {
+            let count = counts.count_of(coin);
+            let per_roll = coin.coins_per_roll();
+            let rolls = count / per_roll;
+            let loose = count % per_roll;
+            RollLine {
+                coin,
+                coins_per_roll: per_roll,
+                rolls,
+                loose,
+                rolled_value: coin.roll_value() * u64::from(rolls),
+                loose_value: Money::from(coin) * u64::from(loose),
+            }
+        })
+        .collect();
+
+    RollReport {
+        total_rolls: lines.iter().map(|line| line.rolls).sum(),
+        total_rolled_value: lines.iter().map(|line| line.rolled_value).sum(),
+        total_loose_value: lines.iter().map(|line| line.loose_value).sum(),
+        lines,
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roll_values() {
+        assert_eq!(Coin::Penny.roll_value(), 50);
+        assert_eq!(Coin::Nickel.roll_value(), 200);
+        assert_eq!(Coin::Dime.roll_value(), 500);
+        assert_eq!(Coin::Quarter.roll_value(), 1000);
+    }
+
+    #[test]
+    fn test_pack_empty_purse() {
+        let report = pack(&Purse::new());
+        assert_eq!(report.lines.len(), Coin::COUNT);
+        assert_eq!(report.total_rolls, 0);
+        assert_eq!(report.total_rolled_value, 0);
+        assert_eq!(report.total_loose_value, 0);
+    }
+
+    #[test]
+    fn test_pack_rolls_and_loose_coins() {
+        let purse: Purse = [(Coin::Penny, 123), (Coin::Quarter, 85)]
+            .into_iter()
+            .collect();
+        let report = pack(&purse);
+
+        let pennies = &report.lines[0];
+        assert_eq!((pennies.rolls, pennies.loose), (2, 23));
+        assert_eq!(pennies.rolled_value, 100);
+
+        let quarters = &report.lines[3];
+        assert_eq!((quarters.rolls, quarters.loose), (2, 5));
+        assert_eq!(quarters.loose_value, 125);
+
+        assert_eq!(report.total_rolls, 4);
+        assert_eq!(report.total_rolled_value, 2_100);
+        assert_eq!(report.total_loose_value, 148);
+    }
+
+    #[test]
+    fn test_pack_preserves_total_value() {
+        let purse: Purse = [(Coin::Nickel, 99), (Coin::Dime, 50)].into_iter().collect();
+        let report = pack(&purse);
+        assert_eq!(
+            report.total_rolled_value + report.total_loose_value,
+            purse.total_value()
+        );
+    }
+}
@@ -0,0 +1,131 @@
+// ============================================================================
+// ROUNDING MODULE: Cash Rounding
+// ============================================================================
+// Many countries have stopped minting their smallest coin (Canada, Australia,
+// Sweden, ...). Cash totals are then rounded to a multiple of the smallest
+// coin still in use, often 5 cents ("Swedish rounding"):
+//
+//   $1.01, $1.02 -> $1.00     $1.03, $1.04 -> $1.05
+//   $1.06, $1.07 -> $1.05     $1.08, $1.09 -> $1.10
+
+use serde::{Deserialize, Serialize};
+
+use crate::Money;
+
+/// How to round an amount that falls between two multiples of the increment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingStrategy {
+    /// Round to the nearest multiple; exact halves round up
+    #[default]
+    Nearest,
+    /// Round to the nearest multiple; exact halves round to the even multiple
+    NearestEven,
+    /// Always round down (in the customer's favour when paying)
+    Down,
+    /// Always round up
+    Up,
+}
+
+/// Rounds `amount` to a multiple of `increment` for cash payment
+///
+/// Returns None if `increment` is zero or rounding up would overflow.
+pub fn round_for_cash(
+    amount: Money,
+    increment: Money,
+    strategy: RoundingStrategy,
+) -> Option<Money> {
+    let cents = amount.cents();
+    let step = increment.cents();
+    if step == 0 {
+        return None;
+    }
+
+    let remainder = cents % step;
+    let down = cents - remainder;
+    if remainder == 0 {
+        return Some(amount);
+    }
+
+    let round_up = match strategy {
+        RoundingStrategy::Down => false,
+        RoundingStrategy::Up => true,
+        // Compare remainder with step - remainder (2 * remainder with step,
+        // without overflow) to avoid losing the half on odd steps
+        RoundingStrategy::Nearest => remainder >= step - remainder,
+        RoundingStrategy::NearestEven => match remainder.cmp(&(step - remainder)) {
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Equal => (down / step) % 2 == 1,
+        },
+    };
+
+    if round_up {
+        down.checked_add(step).map(Money::from_cents)
+    } else {
+        Some(Money::from_cents(down))
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round(cents: u64, step: u64, strategy: RoundingStrategy) -> Option<u64> {
+        round_for_cash(Money::from_cents(cents), Money::from_cents(step), strategy)
+            .map(Money::cents)
+    }
+
+    #[test]
+    fn test_swedish_rounding_to_nickel() {
+        let expected = [
+            (101, 100),
+            (102, 100),
+            (103, 105),
+            (104, 105),
+            (105, 105),
+            (106, 105),
+            (107, 105),
+            (108, 110),
+            (109, 110),
+        ];
+        for (cents, rounded) in expected {
+            assert_eq!(round(cents, 5, RoundingStrategy::Nearest), Some(rounded));
+        }
+    }
+
+    #[test]
+    fn test_exact_halves() {
+        // 15 is exactly halfway between 10 and 20
+        assert_eq!(round(15, 10, RoundingStrategy::Nearest), Some(20));
+        assert_eq!(round(15, 10, RoundingStrategy::NearestEven), Some(20));
+        assert_eq!(round(25, 10, RoundingStrategy::NearestEven), Some(20));
+        assert_eq!(round(26, 10, RoundingStrategy::NearestEven), Some(30));
+    }
+
+    #[test]
+    fn test_directed_rounding() {
+        assert_eq!(round(101, 5, RoundingStrategy::Up), Some(105));
+        assert_eq!(round(109, 5, RoundingStrategy::Down), Some(105));
+        assert_eq!(round(110, 5, RoundingStrategy::Up), Some(110));
+    }
+
+    #[test]
+    fn test_other_increments() {
+        // Sweden rounds to whole kronor (100 öre)
+        assert_eq!(round(1_249, 100, RoundingStrategy::Nearest), Some(1_200));
+        assert_eq!(round(1_250, 100, RoundingStrategy::Nearest), Some(1_300));
+        // An increment of 1 never changes the amount
+        assert_eq!(round(41, 1, RoundingStrategy::Up), Some(41));
+    }
+
+    #[test]
+    fn test_invalid_increment_and_overflow() {
+        assert_eq!(round(41, 0, RoundingStrategy::Nearest), None);
+        assert_eq!(round(u64::MAX, 10, RoundingStrategy::Up), None);
+    }
+}
@@ -0,0 +1,344 @@
+// ============================================================================
+// SAVINGS MODULE: A Piggy Bank with a Goal
+// ============================================================================
+// Coins dropped into a piggy bank add up toward a target amount:
+//
+//   let mut bank = PiggyBank::new(Money::from_cents(5_000));   // save $50
+//   bank.deposit(today, &coins);
+//   bank.progress().fraction                                   // e.g. 0.25
+//   bank.projected_completion(today, &Cadence::weekly(Money::from_cents(300)))
+//
+// The bank is saved in the purse file format with the target and deposit
+// history alongside the coins, so Purse::load() reads its coins as well.
+
+use chrono::{Days, NaiveDate};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ts_rs::TS;
+
+use crate::purse::{read_purse_file, write_purse_file};
+use crate::{Money, Purse, PurseFileError, PURSE_FILE_VERSION};
+
+/// One handful of coins put into a piggy bank
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavingsDeposit {
+    pub date: NaiveDate,
+    pub coins: Purse,
+    /// Value of the bank after the deposit
+    pub balance: Money,
+}
+
+/// How far a piggy bank is from its target
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "SavingsProgress.d.ts")]
+pub struct SavingsProgress {
+    pub saved: Money,
+    pub target: Money,
+    /// Still to save; 0 once the target is reached
+    pub remaining: Money,
+    /// Share of the target saved, at most 1
+    pub fraction: f64,
+    pub reached: bool,
+}
+
+/// A regular deposit: `amount` every `every_days` days
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cadence {
+    pub amount: Money,
+    pub every_days: u32,
+}
+
+impl Cadence {
+    /// `amount` once a day
+    pub fn daily(amount: Money) -> Self {
+        Cadence {
+            amount,
+            every_days: 1,
+        }
+    }
+
+    /// `amount` once a week
+    pub fn weekly(amount: Money) -> Self {
+        Cadence {
+            amount,
+            every_days: 7,
+        }
+    }
+}
+
+/// Coins saved toward a target, with the history of deposits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiggyBank {
+    target: Money,
+    coins: Purse,
+    history: Vec<SavingsDeposit>,
+}
+
+/// On-disk layout: a purse file with the target and history added
+#[derive(Serialize, Deserialize)]
+struct SavingsFile {
+    version: u32,
+    target: Money,
+    coins: Purse,
+    history: Vec<SavingsDeposit>,
+}
+
+impl PiggyBank {
+    /// An empty bank saving toward `target`
+    pub fn new(target: Money) -> Self {
+        PiggyBank {
+            target,
+            coins: Purse::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// The amount being saved for
+    pub fn target(&self) -> Money {
+        self.target
+    }
+
+    /// The coins saved so far
+    pub fn coins(&self) -> &Purse {
+        &self.coins
+    }
+
+    /// Value of the coins saved so far
+    pub fn saved(&self) -> Money {
+        self.coins.total_value()
+    }
+
+    /// Every deposit, in the order they were made
+    pub fn history(&self) -> &[SavingsDeposit] {
+        &self.history
+    }
+
+    /// Puts `coins` in the bank on `date`
+    pub fn deposit(&mut self, date: NaiveDate, coins: &Purse) {
+        for (coin, count) in coins.iter() {
+            self.coins.add(coin, count);
+        }
+        self.history.push(SavingsDeposit {
+            date,
+            coins: coins.clone(),
+            balance: self.saved(),
+        });
+    }
+
+    /// How much has been saved against the target
+    pub fn progress(&self) -> SavingsProgress {
+        let saved = self.saved();
+        let remaining = Money::from_cents(self.target.cents().saturating_sub(saved.cents()));
+        let fraction = if self.target == 0 {
+            1.0
+        } else {
+            (saved.cents() as f64 / self.target.cents() as f64).min(1.0)
+        };
+        SavingsProgress {
+            saved,
+            target: self.target,
+            remaining,
+            fraction,
+            reached: remaining == 0,
+        }
+    }
+
+    /// Number of further deposits of `cadence.amount` needed to reach the
+    /// target, or None if the amount is zero and the target isn't reached
+    pub fn deposits_needed(&self, cadence: &Cadence) -> Option<u64> {
+        let remaining = self.progress().remaining.cents();
+        if remaining == 0 {
+            Some(0)
+        } else if cadence.amount == 0 {
+            None
+        } else {
+            Some(remaining.div_ceil(cadence.amount.cents()))
+        }
+    }
+
+    /// The day the target will be reached when depositing on `cadence`,
+    /// starting one period after `from`
+    ///
+    /// `from` itself if the target is already reached; None if it never
+    /// will be (a zero amount) or the date is out of range.
+    pub fn projected_completion(&self, from: NaiveDate, cadence: &Cadence) -> Option<NaiveDate> {
+        let deposits = self.deposits_needed(cadence)?;
+        let days = deposits.checked_mul(u64::from(cadence.every_days))?;
+        from.checked_add_days(Days::new(days))
+    }
+
+    /// Writes the bank to `path`, as TOML if it ends in `.toml` and as JSON
+    /// otherwise
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), PurseFileError> {
+        write_purse_file(
+            path.as_ref(),
+            &SavingsFile {
+                version: PURSE_FILE_VERSION,
+                target: self.target,
+                coins: self.coins.clone(),
+                history: self.history.clone(),
+            },
+        )
+    }
+
+    /// Reads a bank written by save()
+    pub fn load(path: impl AsRef<Path>) -> Result<PiggyBank, PurseFileError> {
+        read_purse_file(path.as_ref()).map(|file: SavingsFile| PiggyBank {
+            target: file.target,
+            coins: file.coins,
+            history: file.history,
+        })
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Coin;
+    use std::path::PathBuf;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    fn quarters(n: u32) -> Purse {
+        [(Coin::Quarter, n)].into_iter().collect()
+    }
+
+    fn sample_bank() -> PiggyBank {
+        let mut bank = PiggyBank::new(Money::from_cents(1_000));
+        bank.deposit(day(1), &quarters(4));
+        bank.deposit(
+            day(8),
+            &[(Coin::Dime, 5), (Coin::Penny, 3)].into_iter().collect(),
+        );
+        bank
+    }
+
+    // A path in the temp directory that no other test uses
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("coins-savings-{}-{name}", std::process::id()))
+    }
+
+    // ========================================================================
+    // Progress
+    // ========================================================================
+
+    #[test]
+    fn test_deposits_accumulate() {
+        let bank = sample_bank();
+        assert_eq!(bank.saved(), 153);
+        assert_eq!(bank.coins().count_of(Coin::Quarter), 4);
+        assert_eq!(bank.history().len(), 2);
+        assert_eq!(bank.history()[0].balance, 100);
+        assert_eq!(bank.history()[1].balance, 153);
+        assert_eq!(bank.history()[1].date, day(8));
+    }
+
+    #[test]
+    fn test_progress() {
+        let progress = sample_bank().progress();
+        assert_eq!(progress.saved, 153);
+        assert_eq!(progress.remaining, 847);
+        assert!((progress.fraction - 0.153).abs() < 1e-12);
+        assert!(!progress.reached);
+    }
+
+    #[test]
+    fn test_target_reached_and_passed() {
+        let mut bank = PiggyBank::new(Money::from_cents(100));
+        bank.deposit(day(1), &quarters(6));
+        let progress = bank.progress();
+        assert!(progress.reached);
+        assert_eq!(progress.remaining, 0);
+        assert_eq!(progress.fraction, 1.0);
+        assert!(PiggyBank::new(Money::from_cents(0)).progress().reached);
+    }
+
+    // ========================================================================
+    // Projections
+    // ========================================================================
+
+    #[test]
+    fn test_projected_completion() {
+        let bank = sample_bank();
+        // 847 more at $3 a week: 3 deposits
+        let weekly = Cadence::weekly(Money::from_cents(300));
+        assert_eq!(bank.deposits_needed(&weekly), Some(3));
+        assert_eq!(bank.projected_completion(day(8), &weekly), Some(day(29)));
+
+        let daily = Cadence::daily(Money::from_cents(847));
+        assert_eq!(bank.projected_completion(day(8), &daily), Some(day(9)));
+    }
+
+    #[test]
+    fn test_projection_edge_cases() {
+        let bank = sample_bank();
+        let nothing = Cadence::weekly(Money::from_cents(0));
+        assert_eq!(bank.deposits_needed(&nothing), None);
+        assert_eq!(bank.projected_completion(day(1), &nothing), None);
+
+        let mut done = PiggyBank::new(Money::from_cents(25));
+        done.deposit(day(1), &quarters(1));
+        assert_eq!(done.deposits_needed(&nothing), Some(0));
+        assert_eq!(done.projected_completion(day(3), &nothing), Some(day(3)));
+
+        let glacial = Cadence {
+            amount: Money::from_cents(1),
+            every_days: u32::MAX,
+        };
+        assert_eq!(bank.projected_completion(day(1), &glacial), None);
+    }
+
+    // ========================================================================
+    // Files
+    // ========================================================================
+
+    #[test]
+    fn test_save_and_load() {
+        for name in ["piggy.json", "piggy.toml"] {
+            let path = temp_path(name);
+            sample_bank().save(&path).unwrap();
+            let loaded = PiggyBank::load(&path);
+            // The coins are readable as a plain purse file too
+            let coins = Purse::load(&path);
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(loaded.unwrap(), sample_bank(), "{name}");
+            assert_eq!(coins.unwrap(), *sample_bank().coins(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_plain_purse_file_is_not_a_piggy_bank() {
+        let path = temp_path("purse.json");
+        quarters(2).save(&path).unwrap();
+        let error = PiggyBank::load(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(error, PurseFileError::Parse { .. }), "{error}");
+    }
+
+    #[test]
+    fn test_save_target_above_toml_integers() {
+        // TOML integers are i64, so this target can only be saved as JSON
+        let bank = PiggyBank::new(Money::MAX);
+        let path = temp_path("huge.toml");
+        let error = bank.save(&path).unwrap_err();
+        assert!(matches!(error, PurseFileError::Serialize { .. }), "{error}");
+        assert!(error
+            .to_string()
+            .starts_with(&format!("cannot write purse file {}: ", path.display())));
+        assert!(!path.exists());
+
+        let path = temp_path("huge.json");
+        bank.save(&path).unwrap();
+        let loaded = PiggyBank::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), bank);
+    }
+}
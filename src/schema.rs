@@ -0,0 +1,129 @@
+// ============================================================================
+// SCHEMA MODULE: JSON Schemas for API Types
+// ============================================================================
+// Publishes JSON Schemas for every type the web API accepts or returns, so
+// clients can generate typed bindings and validate payloads.
+// Served at GET /schema.
+
+use schemars::schema_for;
+use serde_json::Value;
+
+use crate::rolls::RollReport;
+use crate::simulation::SimulationReport;
+use crate::stats::CombinationStats;
+use crate::web::{
+    AllCombinationsResponse, CatalogResponse, ChangeRequest, ChangeResponse, CombinationDetail,
+    CurrencyBatchResponse, CurrencyCombination, CurrencyCombinationsResponse, DailyResponse,
+    DenominationsResponse, ErrorResponse, FlipResponse, HealthResponse, RandomBatchResponse,
+    RandomResponse, RandomStatsResponse, SimulateRequest, ValidateResponse, ValueRequest,
+    ValueResponse,
+};
+use crate::{Coin, Combination, Money, Purse};
+
+/// JSON Schemas for the API types, keyed by type name
+///
+/// Each entry is a standalone schema document (including its own
+/// `definitions`), so entries can be extracted and used independently.
+pub fn schema() -> Value {
+    serde_json::json!({
+        "Coin": schema_for!(Coin),
+        "Combination": schema_for!(Combination),
+        "Money": schema_for!(Money),
+        "Purse": schema_for!(Purse),
+        "RandomResponse": schema_for!(RandomResponse),
+        "RandomBatchResponse": schema_for!(RandomBatchResponse),
+        "DailyResponse": schema_for!(DailyResponse),
+        "FlipResponse": schema_for!(FlipResponse),
+        "AllCombinationsResponse": schema_for!(AllCombinationsResponse),
+        "CombinationDetail": schema_for!(CombinationDetail),
+        "HealthResponse": schema_for!(HealthResponse),
+        "CatalogResponse": schema_for!(CatalogResponse),
+        "RollReport": schema_for!(RollReport),
+        "CombinationStats": schema_for!(CombinationStats),
+        "RandomStatsResponse": schema_for!(RandomStatsResponse),
+        "SimulateRequest": schema_for!(SimulateRequest),
+        "SimulationReport": schema_for!(SimulationReport),
+        "ChangeRequest": schema_for!(ChangeRequest),
+        "ChangeResponse": schema_for!(ChangeResponse),
+        "ValueRequest": schema_for!(ValueRequest),
+        "ValueResponse": schema_for!(ValueResponse),
+        "ValidateResponse": schema_for!(ValidateResponse),
+        "DenominationsResponse": schema_for!(DenominationsResponse),
+        "CurrencyCombination": schema_for!(CurrencyCombination),
+        "CurrencyCombinationsResponse": schema_for!(CurrencyCombinationsResponse),
+        "CurrencyBatchResponse": schema_for!(CurrencyBatchResponse),
+        "ErrorResponse": schema_for!(ErrorResponse),
+    })
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_contains_all_types() {
+        let schema = schema();
+        for name in [
+            "Coin",
+            "Combination",
+            "Money",
+            "Purse",
+            "RandomResponse",
+            "RandomBatchResponse",
+            "DailyResponse",
+            "FlipResponse",
+            "AllCombinationsResponse",
+            "CombinationDetail",
+            "HealthResponse",
+            "CatalogResponse",
+            "RollReport",
+            "CombinationStats",
+            "RandomStatsResponse",
+            "SimulateRequest",
+            "SimulationReport",
+            "ChangeRequest",
+            "ChangeResponse",
+            "ValueRequest",
+            "ValueResponse",
+            "ValidateResponse",
+            "DenominationsResponse",
+            "CurrencyCombination",
+            "CurrencyCombinationsResponse",
+            "CurrencyBatchResponse",
+            "ErrorResponse",
+        ] {
+            assert!(schema[name].is_object(), "missing schema for {}", name);
+        }
+    }
+
+    #[test]
+    fn test_coin_schema_lists_variants() {
+        let schema = schema();
+        let variants = schema["Coin"]["enum"].as_array().unwrap();
+        assert_eq!(variants.len(), Coin::COUNT);
+        assert!(variants.contains(&Value::from("Quarter")));
+    }
+
+    #[test]
+    fn test_combination_schema_is_an_array_of_coins() {
+        let schema = schema();
+        assert_eq!(schema["Combination"]["type"], "array");
+        assert!(schema["Combination"]["items"].is_object());
+    }
+
+    #[test]
+    fn test_random_response_schema_fields() {
+        let schema = schema();
+        let properties = &schema["RandomResponse"]["properties"];
+        assert!(properties["coins"].is_object());
+        assert!(properties["value"].is_object());
+
+        let required = schema["RandomResponse"]["required"].as_array().unwrap();
+        assert!(required.contains(&Value::from("coins")));
+        assert!(!required.contains(&Value::from("formatted_value")));
+    }
+}
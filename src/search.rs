@@ -0,0 +1,230 @@
+// ============================================================================
+// SEARCH MODULE: Combinations With a Given Value
+// ============================================================================
+// Answers "which handfuls add up to exactly N cents?" without building the
+// whole power set first. The search picks a count for each coin, highest
+// value first, and abandons a branch as soon as it has overshot the target
+// or can no longer reach it with the coins still undecided.
+
+use crate::{combinations, total_value, Coin, Combination};
+
+// ============================================================================
+// Exact value
+// ============================================================================
+
+/// Every combination whose total is exactly `target` cents, in index order
+pub fn combinations_with_value(target: u64) -> Vec<Combination> {
+    combinations_with_value_counts(target, 1)
+}
+
+/// Every handful of up to `max_per_coin` of each coin totalling exactly
+/// `target` cents, in the same order as generate_combinations_with_counts()
+/// with that limit for every coin
+pub fn combinations_with_value_counts(target: u64, max_per_coin: usize) -> Vec<Combination> {
+    // Highest value first, so the penny count is decided last and ticks
+    // fastest, matching the enumeration order
+    let coins: Vec<Coin> = Coin::iter().rev().collect();
+
+    // reachable[i]: the most the coins from position i onwards can add,
+    // saturating, since it only has to be an upper bound
+    let mut reachable = vec![0u64; coins.len() + 1];
+    for i in (0..coins.len()).rev() {
+        let most = coin_cents(coins[i]).saturating_mul(max_per_coin as u64);
+        reachable[i] = reachable[i + 1].saturating_add(most);
+    }
+
+    let mut results = Vec::new();
+    let mut counts = vec![0; coins.len()];
+    search(
+        &coins,
+        &reachable,
+        max_per_coin,
+        target,
+        0,
+        &mut counts,
+        &mut results,
+    );
+    results
+}
+
+fn search(
+    coins: &[Coin],
+    reachable: &[u64],
+    max_per_coin: usize,
+    remaining: u64,
+    position: usize,
+    counts: &mut [usize],
+    results: &mut Vec<Combination>,
+) {
+    if position == coins.len() {
+        if remaining == 0 {
+            // Lowest value first, like every other combination
+            let handful = coins
+                .iter()
+                .zip(counts.iter())
+                .rev()
+                .flat_map(|(&coin, &count)| std::iter::repeat_n(coin, count))
+                .collect();
+            results.push(handful);
+        }
+        return;
+    }
+
+    if reachable[position] < remaining {
+        return;
+    }
+
+    let value = coin_cents(coins[position]);
+    for count in 0..=max_per_coin {
+        let spent = match value.checked_mul(count as u64) {
+            Some(spent) if spent <= remaining => spent,
+            _ => break,
+        };
+        counts[position] = count;
+        search(
+            coins,
+            reachable,
+            max_per_coin,
+            remaining - spent,
+            position + 1,
+            counts,
+            results,
+        );
+    }
+    counts[position] = 0;
+}
+
+// ============================================================================
+// Nearest value
+// ============================================================================
+
+/// The combination whose total is nearest to `target` cents
+///
+/// Equally near candidates are settled by, in turn:
+/// 1. staying at or under the target (never overpay),
+/// 2. using fewer coins,
+/// 3. the lower index.
+///
+/// An exact match always wins, and there is always an answer since the
+/// empty combination is worth zero.
+pub fn closest_combination(target: u64) -> Combination {
+    combinations()
+        .min_by_key(|coins| {
+            let value = total_value(coins).cents();
+            (value.abs_diff(target), value > target, coins.len())
+        })
+        .unwrap_or_default()
+}
+
+fn coin_cents(coin: Coin) -> u64 {
+    coin.value_in_cents()
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_all_combinations, generate_combinations_with_counts, total_value};
+
+    // ========================================================================
+    // Exact Value Tests
+    // ========================================================================
+
+    #[test]
+    fn test_unique_subset() {
+        assert_eq!(
+            combinations_with_value(36),
+            vec![vec![Coin::Penny, Coin::Dime, Coin::Quarter]]
+        );
+    }
+
+    #[test]
+    fn test_zero_is_the_empty_combination() {
+        assert_eq!(combinations_with_value(0), vec![Vec::<Coin>::new()]);
+    }
+
+    #[test]
+    fn test_unreachable_value() {
+        assert!(combinations_with_value(2).is_empty());
+        assert!(combinations_with_value(42).is_empty());
+        assert!(combinations_with_value(u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_matches_filtering_the_power_set() {
+        for target in 0..=41 {
+            let expected: Vec<Combination> = generate_all_combinations()
+                .into_iter()
+                .filter(|coins| total_value(coins) == target)
+                .collect();
+            assert_eq!(combinations_with_value(target), expected, "target {target}");
+        }
+    }
+
+    #[test]
+    fn test_multiset_matches_filtering() {
+        let all = generate_combinations_with_counts(|_| 3);
+        for target in [0, 10, 15, 25, 30, 50, 123] {
+            let expected: Vec<Combination> = all
+                .iter()
+                .filter(|coins| total_value(coins) == target)
+                .cloned()
+                .collect();
+            assert_eq!(
+                combinations_with_value_counts(target, 3),
+                expected,
+                "target {target}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_multiset_ways_to_make_a_dime() {
+        let ways = combinations_with_value_counts(10, 10);
+        assert_eq!(ways.len(), 4);
+        assert!(ways.iter().any(|way| way == &[Coin::Nickel, Coin::Nickel]));
+        assert!(ways.iter().any(|way| way == &[Coin::Penny; 10]));
+    }
+
+    #[test]
+    fn test_multiset_huge_limit_does_not_overflow() {
+        let ways = combinations_with_value_counts(30, usize::MAX);
+        assert_eq!(ways, combinations_with_value_counts(30, 30));
+        assert_eq!(ways.len(), 18);
+    }
+
+    // ========================================================================
+    // Nearest Value Tests
+    // ========================================================================
+
+    #[test]
+    fn test_closest_exact_match() {
+        assert_eq!(closest_combination(15), vec![Coin::Nickel, Coin::Dime]);
+        assert_eq!(closest_combination(0), Vec::<Coin>::new());
+    }
+
+    #[test]
+    fn test_closest_rounds_to_nearest() {
+        // 4 is 1 away from a nickel
+        assert_eq!(closest_combination(4), vec![Coin::Nickel]);
+        // 24 is 1 away from 25
+        assert_eq!(closest_combination(24), vec![Coin::Quarter]);
+    }
+
+    #[test]
+    fn test_closest_tie_prefers_under_target() {
+        // Penny (1) and Nickel (5) are both 2 away from 3
+        assert_eq!(closest_combination(3), vec![Coin::Penny]);
+        // Not a tie: 21 is 5 above Penny + Nickel + Dime (16) but only 4
+        // below Quarter (25), so the closer one wins even though it's over
+        assert_eq!(closest_combination(21), vec![Coin::Quarter]);
+    }
+
+    #[test]
+    fn test_closest_above_everything() {
+        assert_eq!(closest_combination(1000), generate_all_combinations()[15]);
+    }
+}
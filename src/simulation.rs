@@ -0,0 +1,153 @@
+// ============================================================================
+// SIMULATION MODULE: Monte Carlo Draws
+// ============================================================================
+// Runs many random draws and summarises what came out, so the theory can be
+// checked against real numbers:
+//
+//   let report = simulate(10_000, &RandomOptions::default()).unwrap();
+//   report.empty_rate                      // close to 1/16 = 0.0625
+//   report.coin_frequency[&Coin::Dime]     // close to 0.5
+//   report.value.mean                      // close to 41 / 2 = 20.5
+//
+// Draws are summarised as they are made, so memory doesn't grow with the
+// number of trials beyond one value per draw for the median.
+
+use rand::Rng;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+use crate::stats::{stats, CombinationStats};
+use crate::{Coin, RandomOptions};
+
+/// Aggregated results of a Monte Carlo run
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "SimulationReport.d.ts")]
+pub struct SimulationReport {
+    /// Number of draws made
+    pub trials: usize,
+    /// Distribution of the drawn values, in cents
+    pub value: CombinationStats,
+    /// Fraction of draws that included each coin
+    pub coin_frequency: BTreeMap<Coin, f64>,
+    /// Fraction of draws that were the empty combination
+    pub empty_rate: f64,
+}
+
+/// Draws `n_trials` random combinations meeting `options` and summarises
+/// them, or None if there are no trials or no combination meets the options
+pub fn simulate(n_trials: usize, options: &RandomOptions) -> Option<SimulationReport> {
+    simulate_with(&mut rand::thread_rng(), n_trials, options)
+}
+
+/// Like simulate(), drawing from `rng`
+pub fn simulate_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    n_trials: usize,
+    options: &RandomOptions,
+) -> Option<SimulationReport> {
+    if !options.is_satisfiable() {
+        return None;
+    }
+
+    let mut hits = [0usize; Coin::COUNT];
+    let mut empty = 0usize;
+    let draws = (0..n_trials)
+        .map(|_| options.generate_with(rng).expect("options are satisfiable"))
+        .inspect(|combination| {
+            if combination.is_empty() {
+                empty += 1;
+            }
+            for (hit, coin) in hits.iter_mut().zip(Coin::iter()) {
+                if combination.contains(&coin) {
+                    *hit += 1;
+                }
+            }
+        });
+    let value = stats(draws)?;
+
+    let rate = |count: usize| count as f64 / n_trials as f64;
+    Some(SimulationReport {
+        trials: n_trials,
+        value,
+        coin_frequency: Coin::iter()
+            .zip(hits)
+            .map(|(coin, n)| (coin, rate(n)))
+            .collect(),
+        empty_rate: rate(empty),
+    })
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_uniform_simulation_matches_theory() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let report = simulate_with(&mut rng, 40_000, &RandomOptions::default()).unwrap();
+
+        assert_eq!(report.trials, 40_000);
+        assert_eq!(report.value.count, 40_000);
+        assert!((report.empty_rate - 1.0 / 16.0).abs() < 0.01);
+        assert!((report.value.mean - 20.5).abs() < 0.3);
+        assert_eq!(report.value.min, 0);
+        assert_eq!(report.value.max, 41);
+        for coin in Coin::iter() {
+            assert!((report.coin_frequency[&coin] - 0.5).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_simulation_respects_options() {
+        let options = RandomOptions {
+            allow_empty: false,
+            exact_coins: Some(1),
+            ..Default::default()
+        };
+        let report = simulate(2_000, &options).unwrap();
+        assert_eq!(report.empty_rate, 0.0);
+        // One coin per draw, so the frequencies add up to 1
+        let total: f64 = report.coin_frequency.values().sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(report
+            .value
+            .histogram
+            .keys()
+            .all(|v| [1, 5, 10, 25].contains(v)));
+    }
+
+    #[test]
+    fn test_simulation_is_reproducible() {
+        let options = RandomOptions::default();
+        let first = simulate_with(&mut StdRng::seed_from_u64(8), 500, &options);
+        let second = simulate_with(&mut StdRng::seed_from_u64(8), 500, &options);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_simulation_without_draws() {
+        assert_eq!(simulate(0, &RandomOptions::default()), None);
+        let impossible = RandomOptions {
+            allow_empty: false,
+            exact_coins: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(simulate(10, &impossible), None);
+    }
+
+    #[test]
+    fn test_report_serializes_coin_names() {
+        let report = simulate(10, &RandomOptions::default()).unwrap();
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json["coin_frequency"]["Quarter"].is_f64());
+        assert!(json["value"]["histogram"].is_object());
+    }
+}
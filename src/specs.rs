@@ -0,0 +1,142 @@
+// ============================================================================
+// SPECS MODULE: Physical Coin Metadata
+// ============================================================================
+// Physical specifications for each coin as published by the US Mint.
+// Handy for vending machines and coin counters, which identify coins by
+// weight and size rather than by face value.
+
+use serde::Serialize;
+
+use crate::Coin;
+
+/// Physical specification of a single coin
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CoinSpecs {
+    /// Mass in milligrams (kept as an integer so totals don't drift)
+    pub mass_mg: u32,
+    /// Diameter in millimetres
+    pub diameter_mm: f64,
+    /// Thickness in millimetres
+    pub thickness_mm: f64,
+    /// Metal composition
+    pub composition: &'static str,
+}
+
+impl CoinSpecs {
+    /// Mass in grams
+    pub fn mass_grams(&self) -> f64 {
+        self.mass_mg as f64 / 1000.0
+    }
+}
+
+impl Coin {
+    /// Returns the physical specifications of this coin
+    pub fn specs(&self) -> CoinSpecs {
+        match self {
+            Coin::Penny => CoinSpecs {
+                mass_mg: 2_500,
+                diameter_mm: 19.05,
+                thickness_mm: 1.52,
+                composition: "Copper-plated zinc (97.5% Zn, 2.5% Cu)",
+            },
+            Coin::Nickel => CoinSpecs {
+                mass_mg: 5_000,
+                diameter_mm: 21.21,
+                thickness_mm: 1.95,
+                composition: "Cupro-nickel (75% Cu, 25% Ni)",
+            },
+            Coin::Dime => CoinSpecs {
+                mass_mg: 2_268,
+                diameter_mm: 17.91,
+                thickness_mm: 1.35,
+                composition: "Cupro-nickel clad copper (91.67% Cu, 8.33% Ni)",
+            },
+            Coin::Quarter => CoinSpecs {
+                mass_mg: 5_670,
+                diameter_mm: 24.26,
+                thickness_mm: 1.75,
+                composition: "Cupro-nickel clad copper (91.67% Cu, 8.33% Ni)",
+            },
+        }
+    }
+}
+
+impl Coin {
+    /// Share of US coin production made up by this coin (0.0 - 1.0)
+    ///
+    /// Approximate averages of recent US Mint circulating mintages. Pennies
+    /// dominate, which is why they turn up in most handfuls of change.
+    /// The shares of all coins add up to 1.0.
+    pub fn circulation_share(&self) -> f64 {
+        match self {
+            Coin::Penny => 0.56,
+            Coin::Nickel => 0.09,
+            Coin::Dime => 0.20,
+            Coin::Quarter => 0.15,
+        }
+    }
+}
+
+/// Total weight of a combination in grams
+///
+/// Sums integer milligrams first and converts once at the end, so
+/// e.g. all four coins weigh exactly 15.438 g rather than 15.437999...
+pub fn total_weight(coins: &[Coin]) -> f64 {
+    let total_mg: u32 = coins.iter().map(|coin| coin.specs().mass_mg).sum();
+    total_mg as f64 / 1000.0
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coin_masses() {
+        assert_eq!(Coin::Penny.specs().mass_grams(), 2.5);
+        assert_eq!(Coin::Nickel.specs().mass_grams(), 5.0);
+        assert_eq!(Coin::Dime.specs().mass_grams(), 2.268);
+        assert_eq!(Coin::Quarter.specs().mass_grams(), 5.67);
+    }
+
+    #[test]
+    fn test_coin_diameters_are_distinct() {
+        // Coin mechanisms rely on diameters to tell coins apart
+        let mut diameters: Vec<f64> = Coin::iter().map(|c| c.specs().diameter_mm).collect();
+        diameters.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        diameters.dedup();
+        assert_eq!(diameters.len(), Coin::COUNT);
+    }
+
+    #[test]
+    fn test_circulation_shares_sum_to_one() {
+        let total: f64 = Coin::iter().map(|c| c.circulation_share()).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pennies_are_most_common() {
+        let most_common = Coin::iter()
+            .max_by(|a, b| a.circulation_share().total_cmp(&b.circulation_share()))
+            .unwrap();
+        assert_eq!(most_common, Coin::Penny);
+    }
+
+    #[test]
+    fn test_total_weight_empty() {
+        assert_eq!(total_weight(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_total_weight_all_coins() {
+        assert_eq!(total_weight(&Coin::all()), 15.438);
+    }
+
+    #[test]
+    fn test_total_weight_duplicates() {
+        assert_eq!(total_weight(&[Coin::Nickel, Coin::Nickel]), 10.0);
+    }
+}
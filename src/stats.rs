@@ -0,0 +1,234 @@
+// ============================================================================
+// STATS MODULE: Summary Statistics Over Combination Values
+// ============================================================================
+// Summarises the total values of any collection of combinations:
+//
+//   stats(generate_all_combinations())          // all 16
+//   stats(combinations_of_size(2))              // just the pairs
+//
+// All values are in cents. Served at GET /stats. group_by_value() answers
+// the related question of which combinations share an amount.
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::{all_combinations_cached, total_value, Coin, Combination};
+
+/// Summary of the total values of a set of combinations, in cents
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "CombinationStats.d.ts")]
+pub struct CombinationStats {
+    /// Number of combinations summarised
+    pub count: usize,
+    #[ts(type = "number")]
+    pub min: u64,
+    #[ts(type = "number")]
+    pub max: u64,
+    pub mean: f64,
+    /// Middle value; the mean of the two middle values for an even count
+    pub median: f64,
+    /// Most common value; the lowest one if several are equally common
+    #[ts(type = "number")]
+    pub mode: u64,
+    /// Population variance
+    pub variance: f64,
+    /// How many combinations have each value
+    #[ts(type = "{ [key in number]?: number }")]
+    pub histogram: BTreeMap<u64, usize>,
+}
+
+impl CombinationStats {
+    /// Population standard deviation
+    pub fn std_dev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+}
+
+/// Statistics over the values of `combinations`, or None if there are none
+pub fn stats<I, C>(combinations: I) -> Option<CombinationStats>
+where
+    I: IntoIterator<Item = C>,
+    C: AsRef<[Coin]>,
+{
+    let mut values: Vec<u64> = combinations
+        .into_iter()
+        .map(|coins| total_value(coins.as_ref()).cents())
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+
+    let count = values.len();
+    let mean = values.iter().map(|&v| v as f64).sum::<f64>() / count as f64;
+    let variance = values
+        .iter()
+        .map(|&v| (v as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count as f64;
+    let median = if count % 2 == 1 {
+        values[count / 2] as f64
+    } else {
+        (values[count / 2 - 1] as f64 + values[count / 2] as f64) / 2.0
+    };
+
+    let mut histogram = BTreeMap::new();
+    for &value in &values {
+        *histogram.entry(value).or_insert(0) += 1;
+    }
+    // max_by_key keeps the last maximum, so walk from the top to land on
+    // the lowest value among equally common ones
+    let mode = histogram
+        .iter()
+        .rev()
+        .max_by_key(|(_, &n)| n)
+        .map(|(&value, _)| value)
+        .expect("histogram is not empty");
+
+    Some(CombinationStats {
+        count,
+        min: values[0],
+        max: values[count - 1],
+        mean,
+        median,
+        mode,
+        variance,
+        histogram,
+    })
+}
+
+// ============================================================================
+// Grouping by value
+// ============================================================================
+
+/// Every combination, grouped by total value in cents
+///
+/// With one of each US coin all 16 values are distinct, so each group has a
+/// single entry; use group_by_value_of() with
+/// generate_combinations_with_counts() to see amounts reachable several ways.
+pub fn group_by_value() -> BTreeMap<u64, Vec<Combination>> {
+    group_by_value_of(all_combinations_cached().iter().cloned())
+}
+
+/// Groups the given combinations by total value in cents; each group keeps
+/// the order the combinations came in
+pub fn group_by_value_of<I>(combinations: I) -> BTreeMap<u64, Vec<Combination>>
+where
+    I: IntoIterator<Item = Combination>,
+{
+    let mut groups: BTreeMap<u64, Vec<Combination>> = BTreeMap::new();
+    for combination in combinations {
+        groups
+            .entry(total_value(&combination).cents())
+            .or_default()
+            .push(combination);
+    }
+    groups
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        combinations_of_size, generate_all_combinations, generate_combinations_with_counts,
+    };
+
+    // ========================================================================
+    // Statistics Tests
+    // ========================================================================
+
+    #[test]
+    fn test_stats_over_all_combinations() {
+        let stats = stats(generate_all_combinations()).unwrap();
+        assert_eq!(stats.count, 16);
+        assert_eq!(stats.min, 0);
+        assert_eq!(stats.max, 41);
+        // Every coin is in half the combinations: 41 / 2
+        assert_eq!(stats.mean, 20.5);
+        assert_eq!(stats.median, 20.5);
+        // All 16 values are distinct, so the lowest one is the mode
+        assert_eq!(stats.mode, 0);
+        assert_eq!(stats.histogram.len(), 16);
+    }
+
+    #[test]
+    fn test_variance_and_std_dev() {
+        let stats = stats([vec![Coin::Penny], vec![Coin::Dime, Coin::Penny]]).unwrap();
+        // Values 1 and 11: mean 6, each 5 away
+        assert_eq!(stats.mean, 6.0);
+        assert_eq!(stats.variance, 25.0);
+        assert_eq!(stats.std_dev(), 5.0);
+    }
+
+    #[test]
+    fn test_odd_count_median_and_mode() {
+        let stats = stats([vec![Coin::Nickel], vec![Coin::Nickel], vec![Coin::Quarter]]).unwrap();
+        assert_eq!(stats.median, 5.0);
+        assert_eq!(stats.mode, 5);
+        assert_eq!(stats.histogram, BTreeMap::from([(5, 2), (25, 1)]));
+    }
+
+    #[test]
+    fn test_mode_tie_picks_lowest_value() {
+        let stats = stats([vec![Coin::Dime], vec![Coin::Penny]]).unwrap();
+        assert_eq!(stats.mode, 1);
+    }
+
+    #[test]
+    fn test_stats_over_iterator() {
+        let stats = stats(combinations_of_size(1)).unwrap();
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.min, 1);
+        assert_eq!(stats.max, 25);
+    }
+
+    #[test]
+    fn test_empty_input_has_no_stats() {
+        assert_eq!(stats(Vec::<Vec<Coin>>::new()), None);
+    }
+
+    // ========================================================================
+    // Grouping Tests
+    // ========================================================================
+
+    #[test]
+    fn test_group_by_value_eleven_cents() {
+        let groups = group_by_value();
+        assert_eq!(groups.len(), 16);
+        assert_eq!(
+            groups[&11],
+            vec![Combination::from(vec![Coin::Penny, Coin::Dime])]
+        );
+        assert!(!groups.contains_key(&12));
+    }
+
+    #[test]
+    fn test_group_by_value_is_sorted_by_amount() {
+        let amounts: Vec<u64> = group_by_value().into_keys().collect();
+        assert!(amounts.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(amounts.first(), Some(&0));
+        assert_eq!(amounts.last(), Some(&41));
+    }
+
+    #[test]
+    fn test_group_multisets_with_shared_values() {
+        let groups = group_by_value_of(generate_combinations_with_counts(|_| 2));
+        // With at most two of each coin, 10 cents is a dime or two nickels
+        assert_eq!(
+            groups[&10],
+            vec![
+                Combination::from(vec![Coin::Nickel, Coin::Nickel]),
+                Combination::from(vec![Coin::Dime]),
+            ]
+        );
+        let total: usize = groups.values().map(Vec::len).sum();
+        assert_eq!(total, 81);
+    }
+}
@@ -0,0 +1,148 @@
+// ============================================================================
+// TESTING MODULE: Generators for Property-Based Tests
+// ============================================================================
+// Only compiled with the `testing` feature:
+//
+//   [dev-dependencies]
+//   coins = { version = "0.1", features = ["testing"] }
+//
+// Provides:
+// - arbitrary::Arbitrary for Coin (for cargo-fuzz and friends)
+// - proptest strategies for coins, combinations and purses
+
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::prelude::*;
+
+use crate::{combination_from_bits, Coin, Purse};
+
+// ============================================================================
+// arbitrary
+// ============================================================================
+
+impl<'a> Arbitrary<'a> for Coin {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let coins = Coin::all();
+        Ok(*u.choose(&coins)?)
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        // choose() consumes at most 4 bytes to pick an index
+        (0, Some(4))
+    }
+}
+
+/// Builds an arbitrary combination: a subset of coins, each at most once,
+/// in the same ascending order that generate_all_combinations() produces
+pub fn arbitrary_combination(u: &mut Unstructured<'_>) -> arbitrary::Result<Vec<Coin>> {
+    let bits = u.int_in_range(0..=(1usize << Coin::COUNT) - 1)?;
+    Ok(combination_from_bits(bits).into_vec())
+}
+
+// ============================================================================
+// proptest
+// ============================================================================
+
+/// Strategy producing any single coin
+pub fn coin() -> impl Strategy<Value = Coin> {
+    prop::sample::select(Coin::all().to_vec())
+}
+
+/// Strategy producing any combination (subset) of coins
+///
+/// Shrinks towards the empty combination.
+pub fn combination() -> impl Strategy<Value = Vec<Coin>> {
+    (0..(1usize << Coin::COUNT)).prop_map(|bits| combination_from_bits(bits).into_vec())
+}
+
+/// Strategy producing a list of up to `max_len` coins, duplicates allowed
+pub fn coins(max_len: usize) -> impl Strategy<Value = Vec<Coin>> {
+    prop::collection::vec(coin(), 0..=max_len)
+}
+
+/// Strategy producing a purse with up to `max_per_coin` of each coin
+///
+/// Shrinks towards the empty purse.
+pub fn purse(max_per_coin: u32) -> impl Strategy<Value = Purse> {
+    prop::collection::vec(0..=max_per_coin, Coin::COUNT)
+        .prop_map(|counts| Coin::iter().zip(counts).collect())
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_all_combinations, total_value, Combination};
+
+    #[test]
+    fn test_arbitrary_coin_from_bytes() {
+        let mut u = Unstructured::new(&[0, 1, 2, 3, 255]);
+        for _ in 0..5 {
+            let coin = Coin::arbitrary(&mut u).unwrap();
+            assert!(Coin::all().contains(&coin));
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_combination_is_a_known_combination() {
+        let all = generate_all_combinations();
+        let data: Vec<u8> = (0..=255).collect();
+        let mut u = Unstructured::new(&data);
+        for _ in 0..32 {
+            let combination = arbitrary_combination(&mut u).unwrap();
+            assert!(all.contains(&Combination::from(combination)));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_combination_has_no_duplicates(combination in combination()) {
+            let mut deduped = combination.clone();
+            deduped.dedup();
+            prop_assert_eq!(deduped, combination);
+        }
+
+        #[test]
+        fn prop_combination_value_at_most_41(combination in combination()) {
+            prop_assert!(total_value(&combination) <= 41);
+        }
+
+        #[test]
+        fn prop_coins_respects_max_len(coins in coins(10)) {
+            prop_assert!(coins.len() <= 10);
+        }
+
+        #[test]
+        fn prop_purse_respects_max_per_coin(purse in purse(5)) {
+            prop_assert!(purse.iter().all(|(_, count)| count <= 5));
+        }
+
+        #[test]
+        fn prop_consolidate_keeps_value(purse in purse(1_000)) {
+            let mut consolidated = purse.clone();
+            consolidated.consolidate();
+            prop_assert_eq!(consolidated.total_value(), purse.total_value());
+            prop_assert!(consolidated.coin_count() <= purse.coin_count());
+
+            // Already as few coins as possible
+            let mut again = consolidated.clone();
+            again.consolidate();
+            prop_assert_eq!(again, consolidated);
+        }
+
+        #[test]
+        fn prop_exchange_keeps_value(purse in purse(1_000), from in coin(), to in coin()) {
+            let mut exchanged = purse.clone();
+            let received = exchanged.exchange(from, to);
+            prop_assert_eq!(exchanged.total_value(), purse.total_value());
+            if from != to {
+                prop_assert_eq!(
+                    exchanged.count_of(to),
+                    purse.count_of(to) + received
+                );
+            }
+        }
+    }
+}
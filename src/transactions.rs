@@ -0,0 +1,476 @@
+// ============================================================================
+// TRANSACTIONS MODULE: An Audit Trail for Coin Movements
+// ============================================================================
+// Every change to a CashDrawer is appended to its TransactionLog with the
+// time it happened and the balance it left behind:
+//
+//   drawer.deposit(&float)?;              // Add
+//   drawer.sale(65, &tendered)?;          // Sale
+//   drawer.log().between(opened..closed)  // what happened during a shift
+//
+// Entries are never edited or removed. replay() re-applies them to the
+// starting coins, so a drawer's contents can be checked against its history.
+// Even CashDrawer::undo() appends: it records an Operation::Undo that
+// reverses the earlier entry, and redo() records the operation again.
+//
+// to_csv() exports the log for a spreadsheet, one row per entry.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeBounds;
+
+use crate::csv_out::write_csv;
+use crate::{Coin, Money, NotEnoughCoinsError, Purse};
+
+/// A single movement of coins
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Operation {
+    /// Coins put in
+    Add { coins: Purse },
+    /// Coins taken out
+    Remove { coins: Purse },
+    /// `tendered` taken in for an item costing `price` cents, and `change`
+    /// handed back
+    Sale {
+        price: u64,
+        tendered: Purse,
+        change: Purse,
+    },
+    /// An earlier operation reversed
+    Undo { operation: Box<Operation> },
+}
+
+impl Operation {
+    /// Applies the operation to `purse`, or fails without changing it if
+    /// coins have to come out that it doesn't hold
+    pub fn apply(&self, purse: &mut Purse) -> Result<(), NotEnoughCoinsError> {
+        let mut after = purse.clone();
+        self.apply_in(&mut after, true)?;
+        *purse = after;
+        Ok(())
+    }
+
+    /// The operation that reverses this one
+    pub fn undo(self) -> Operation {
+        Operation::Undo {
+            operation: Box::new(self),
+        }
+    }
+
+    // A short name for the operation, the price if it is a sale, and the
+    // coins it puts in and takes out
+    fn summary(&self) -> (String, Option<u64>, Purse, Purse) {
+        match self {
+            Operation::Add { coins } => ("add".to_string(), None, coins.clone(), Purse::new()),
+            Operation::Remove { coins } => {
+                ("remove".to_string(), None, Purse::new(), coins.clone())
+            }
+            Operation::Sale {
+                price,
+                tendered,
+                change,
+            } => (
+                "sale".to_string(),
+                Some(*price),
+                tendered.clone(),
+                change.clone(),
+            ),
+            Operation::Undo { operation } => {
+                let (name, price, coins_in, coins_out) = operation.summary();
+                (format!("undo {}", name), price, coins_out, coins_in)
+            }
+        }
+    }
+
+    // Applies the operation, or reverses it if `forward` is false
+    fn apply_in(&self, purse: &mut Purse, forward: bool) -> Result<(), NotEnoughCoinsError> {
+        match (self, forward) {
+            (Operation::Add { coins }, true) | (Operation::Remove { coins }, false) => {
+                add_all(purse, coins);
+            }
+            (Operation::Remove { coins }, true) | (Operation::Add { coins }, false) => {
+                remove_all(purse, coins)?;
+            }
+            (
+                Operation::Sale {
+                    tendered, change, ..
+                },
+                true,
+            ) => {
+                add_all(purse, tendered);
+                remove_all(purse, change)?;
+            }
+            (
+                Operation::Sale {
+                    tendered, change, ..
+                },
+                false,
+            ) => {
+                add_all(purse, change);
+                remove_all(purse, tendered)?;
+            }
+            (Operation::Undo { operation }, forward) => operation.apply_in(purse, !forward)?,
+        }
+        Ok(())
+    }
+}
+
+fn add_all(purse: &mut Purse, coins: &Purse) {
+    for (coin, count) in coins.iter() {
+        purse.add(coin, count);
+    }
+}
+
+fn remove_all(purse: &mut Purse, coins: &Purse) -> Result<(), NotEnoughCoinsError> {
+    for (coin, count) in coins.iter() {
+        purse.remove(coin, count)?;
+    }
+    Ok(())
+}
+
+/// One entry in a transaction log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction {
+    /// When the operation happened
+    pub timestamp: DateTime<Utc>,
+    pub operation: Operation,
+    /// Value of the coins held after the operation
+    pub balance: Money,
+}
+
+// One row of a transaction log CSV: the net change in each coin, and in
+// value, that the operation made
+#[derive(Serialize)]
+struct TransactionCsvRow {
+    timestamp: DateTime<Utc>,
+    operation: String,
+    price: Option<u64>,
+    penny: i64,
+    nickel: i64,
+    dime: i64,
+    quarter: i64,
+    value_change: i64,
+    balance: Money,
+}
+
+/// Append-only record of operations, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionLog {
+    entries: Vec<Transaction>,
+}
+
+impl TransactionLog {
+    /// Creates an empty log
+    pub fn new() -> Self {
+        TransactionLog::default()
+    }
+
+    /// Appends `operation`, timestamped now, that left `balance` behind
+    pub fn record(&mut self, operation: Operation, balance: Money) -> &Transaction {
+        self.record_at(Utc::now(), operation, balance)
+    }
+
+    /// Appends `operation` with the given timestamp, e.g. when importing a
+    /// history from elsewhere
+    pub fn record_at(
+        &mut self,
+        timestamp: DateTime<Utc>,
+        operation: Operation,
+        balance: Money,
+    ) -> &Transaction {
+        self.entries.push(Transaction {
+            timestamp,
+            operation,
+            balance,
+        });
+        self.entries.last().expect("just pushed")
+    }
+
+    /// Every entry, oldest first
+    pub fn entries(&self) -> &[Transaction] {
+        &self.entries
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The most recent entry, if any
+    pub fn last(&self) -> Option<&Transaction> {
+        self.entries.last()
+    }
+
+    /// Entries whose timestamp falls in `range`, oldest first
+    ///
+    /// Takes any range, so `start..end`, `start..` and `..=end` all work.
+    pub fn between<R: RangeBounds<DateTime<Utc>>>(
+        &self,
+        range: R,
+    ) -> impl Iterator<Item = &Transaction> {
+        self.entries
+            .iter()
+            .filter(move |entry| range.contains(&entry.timestamp))
+    }
+
+    /// The log as CSV, one row per entry, oldest first
+    ///
+    /// Each row has the timestamp (RFC 3339), the operation ("add",
+    /// "remove", "sale", or "undo " and one of those), the price of a sale,
+    /// the net change in the number of each coin, the net change in value
+    /// and the balance after, all in cents.
+    pub fn to_csv(&self) -> String {
+        let rows = self.entries.iter().map(|entry| {
+            let (operation, price, coins_in, coins_out) = entry.operation.summary();
+            let net = |coin: Coin| {
+                i64::from(coins_in.count_of(coin)) - i64::from(coins_out.count_of(coin))
+            };
+            TransactionCsvRow {
+                timestamp: entry.timestamp,
+                operation,
+                price,
+                penny: net(Coin::Penny),
+                nickel: net(Coin::Nickel),
+                dime: net(Coin::Dime),
+                quarter: net(Coin::Quarter),
+                value_change: Coin::iter()
+                    .map(|coin| net(coin) * coin.value_in_cents() as i64)
+                    .sum(),
+                balance: entry.balance,
+            }
+        });
+        write_csv(
+            &[
+                "timestamp",
+                "operation",
+                "price",
+                "penny",
+                "nickel",
+                "dime",
+                "quarter",
+                "value_change",
+                "balance",
+            ],
+            rows,
+        )
+    }
+
+    /// Applies every operation in order to `start`, returning the coins
+    /// that should be held now
+    ///
+    /// Fails if the history takes out coins that were never there, which
+    /// means `start` is not what the log began from.
+    pub fn replay(&self, start: &Purse) -> Result<Purse, NotEnoughCoinsError> {
+        let mut purse = start.clone();
+        for entry in &self.entries {
+            entry.operation.apply(&mut purse)?;
+        }
+        Ok(purse)
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Coin;
+    use chrono::TimeZone;
+
+    fn purse(counts: &[(Coin, u32)]) -> Purse {
+        counts.iter().copied().collect()
+    }
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, hour, 0, 0).unwrap()
+    }
+
+    fn sample_log() -> TransactionLog {
+        let mut log = TransactionLog::new();
+        log.record_at(
+            at(9),
+            Operation::Add {
+                coins: purse(&[(Coin::Dime, 5)]),
+            },
+            Money::from_cents(50),
+        );
+        log.record_at(
+            at(12),
+            Operation::Sale {
+                price: 20,
+                tendered: purse(&[(Coin::Quarter, 1)]),
+                change: purse(&[(Coin::Nickel, 1)]),
+            },
+            Money::from_cents(70),
+        );
+        log.record_at(
+            at(17),
+            Operation::Remove {
+                coins: purse(&[(Coin::Dime, 2)]),
+            },
+            Money::from_cents(50),
+        );
+        log
+    }
+
+    // ========================================================================
+    // Operations
+    // ========================================================================
+
+    #[test]
+    fn test_apply_operations() {
+        let mut coins = purse(&[(Coin::Nickel, 1)]);
+        for entry in sample_log().entries() {
+            entry.operation.apply(&mut coins).unwrap();
+            assert_eq!(coins.total_value(), entry.balance + Money::from_cents(5));
+        }
+        assert_eq!(coins, purse(&[(Coin::Dime, 3), (Coin::Quarter, 1)]));
+    }
+
+    #[test]
+    fn test_failed_apply_changes_nothing() {
+        let mut coins = purse(&[(Coin::Penny, 1)]);
+        let sale = Operation::Sale {
+            price: 20,
+            tendered: purse(&[(Coin::Quarter, 1)]),
+            change: purse(&[(Coin::Nickel, 1)]),
+        };
+        let error = sale.apply(&mut coins).unwrap_err();
+        assert_eq!(error.coin, Coin::Nickel);
+        assert_eq!(coins, purse(&[(Coin::Penny, 1)]));
+    }
+
+    #[test]
+    fn test_undo_reverses_every_operation() {
+        let start = purse(&[(Coin::Nickel, 1), (Coin::Dime, 2)]);
+        for entry in sample_log().entries() {
+            let mut coins = start.clone();
+            entry.operation.apply(&mut coins).unwrap();
+            entry.operation.clone().undo().apply(&mut coins).unwrap();
+            assert_eq!(coins, start, "{:?}", entry.operation);
+        }
+    }
+
+    #[test]
+    fn test_undoing_an_undo_redoes() {
+        let add = Operation::Add {
+            coins: purse(&[(Coin::Quarter, 2)]),
+        };
+        let mut coins = Purse::new();
+        add.clone().undo().undo().apply(&mut coins).unwrap();
+        assert_eq!(coins, purse(&[(Coin::Quarter, 2)]));
+        // Undoing an add that never happened fails
+        assert!(add.undo().apply(&mut Purse::new()).is_err());
+    }
+
+    #[test]
+    fn test_operation_json() {
+        let json = serde_json::to_value(Operation::Add {
+            coins: purse(&[(Coin::Penny, 2)]),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"kind": "add", "coins": {"Penny": 2}})
+        );
+
+        let undo = Operation::Remove {
+            coins: purse(&[(Coin::Dime, 1)]),
+        }
+        .undo();
+        assert_eq!(
+            serde_json::to_value(undo).unwrap(),
+            serde_json::json!({
+                "kind": "undo",
+                "operation": {"kind": "remove", "coins": {"Dime": 1}}
+            })
+        );
+    }
+
+    // ========================================================================
+    // The log
+    // ========================================================================
+
+    #[test]
+    fn test_record_appends_in_order() {
+        let log = sample_log();
+        assert_eq!(log.len(), 3);
+        assert!(!log.is_empty());
+        assert_eq!(log.last().unwrap().timestamp, at(17));
+        assert!(TransactionLog::new().is_empty());
+
+        let mut log = TransactionLog::new();
+        let before = Utc::now();
+        let entry = log.record(
+            Operation::Remove {
+                coins: Purse::new(),
+            },
+            Money::from_cents(0),
+        );
+        assert!(entry.timestamp >= before && entry.timestamp <= Utc::now());
+    }
+
+    #[test]
+    fn test_between_time_ranges() {
+        let log = sample_log();
+        let balances = |entries: Vec<&Transaction>| -> Vec<u64> {
+            entries.iter().map(|entry| entry.balance.cents()).collect()
+        };
+        assert_eq!(balances(log.between(at(9)..at(17)).collect()), vec![50, 70]);
+        assert_eq!(balances(log.between(at(10)..).collect()), vec![70, 50]);
+        assert_eq!(balances(log.between(..=at(9)).collect()), vec![50]);
+        assert_eq!(log.between(at(13)..at(14)).count(), 0);
+        assert_eq!(log.between(..).count(), 3);
+    }
+
+    #[test]
+    fn test_replay() {
+        let log = sample_log();
+        let end = log.replay(&purse(&[(Coin::Nickel, 1)])).unwrap();
+        assert_eq!(end, purse(&[(Coin::Dime, 3), (Coin::Quarter, 1)]));
+        // The sale's nickel change was never there
+        assert!(log.replay(&Purse::new()).is_err());
+        assert_eq!(TransactionLog::new().replay(&end), Ok(end));
+    }
+
+    #[test]
+    fn test_log_csv() {
+        let mut log = sample_log();
+        log.record_at(
+            at(18),
+            Operation::Remove {
+                coins: purse(&[(Coin::Dime, 2)]),
+            }
+            .undo(),
+            Money::from_cents(70),
+        );
+        assert_eq!(
+            log.to_csv(),
+            "timestamp,operation,price,penny,nickel,dime,quarter,value_change,balance\n\
+             2024-03-01T09:00:00Z,add,,0,0,5,0,50,50\n\
+             2024-03-01T12:00:00Z,sale,20,0,-1,0,1,20,70\n\
+             2024-03-01T17:00:00Z,remove,,0,0,-2,0,-20,50\n\
+             2024-03-01T18:00:00Z,undo remove,,0,0,2,0,20,70\n"
+        );
+        assert_eq!(
+            TransactionLog::new().to_csv(),
+            "timestamp,operation,price,penny,nickel,dime,quarter,value_change,balance\n"
+        );
+    }
+
+    #[test]
+    fn test_log_json_round_trip() {
+        let log = sample_log();
+        let json = serde_json::to_string(&log).unwrap();
+        let back: TransactionLog = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, log);
+    }
+}
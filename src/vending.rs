@@ -0,0 +1,260 @@
+// ============================================================================
+// VENDING MODULE: A Coin-Operated Vending Machine
+// ============================================================================
+// Coins are inserted one at a time and held apart from the machine's own
+// coins until a purchase goes through:
+//
+//   Idle --insert--> Credit(n) --insert--> Credit(n + coin)
+//   Credit(n) --purchase(price <= n)--> Idle, change dispensed
+//   Credit(n) --cancel--> Idle, inserted coins returned
+//
+// A purchase the machine can't give change for leaves the credit in place,
+// so the customer can add coins, pick something else or cancel. Change is
+// made from the machine's bank like a CashDrawer sale, inserted coins
+// included.
+
+use std::fmt;
+
+use crate::{CashDrawer, Coin, Money, Purse, SaleError};
+
+/// What the machine is waiting for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VendingState {
+    /// No coins inserted
+    Idle,
+    /// Coins worth this much inserted and not yet spent
+    Credit(Money),
+}
+
+/// Error returned when a purchase can't go through; the inserted coins stay
+/// in the machine as credit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VendingError {
+    /// Not enough has been inserted for the price
+    InsufficientCredit { price: u64, credit: u64 },
+    /// The machine can't give the change ("exact change only")
+    NoChange(SaleError),
+}
+
+impl fmt::Display for VendingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VendingError::InsufficientCredit { price, credit } => write!(
+                f,
+                "insert {} more: the price is {} and the credit is {}",
+                price - credit,
+                price,
+                credit
+            ),
+            VendingError::NoChange(error) => write!(f, "exact change only: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for VendingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VendingError::NoChange(error) => Some(error),
+            VendingError::InsufficientCredit { .. } => None,
+        }
+    }
+}
+
+/// A vending machine with its own coin bank for giving change
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VendingMachine {
+    bank: CashDrawer,
+    inserted: Purse,
+}
+
+impl VendingMachine {
+    /// A machine whose bank starts with the coins in `float`
+    pub fn new(float: Purse) -> Self {
+        VendingMachine {
+            bank: CashDrawer::new(float),
+            inserted: Purse::new(),
+        }
+    }
+
+    /// Whether the machine is idle or holding credit
+    pub fn state(&self) -> VendingState {
+        if self.inserted.is_empty() {
+            VendingState::Idle
+        } else {
+            VendingState::Credit(self.credit())
+        }
+    }
+
+    /// Value of the coins inserted and not yet spent
+    pub fn credit(&self) -> Money {
+        self.inserted.total_value()
+    }
+
+    /// The coins inserted and not yet spent
+    pub fn inserted(&self) -> &Purse {
+        &self.inserted
+    }
+
+    /// The machine's own coins, which change is paid from
+    pub fn bank(&self) -> &CashDrawer {
+        &self.bank
+    }
+
+    /// Inserts one coin, returning the credit afterwards
+    pub fn insert(&mut self, coin: Coin) -> Money {
+        self.inserted.add(coin, 1);
+        self.credit()
+    }
+
+    /// Buys an item costing `price` cents with the credit, returning the
+    /// change dispensed
+    ///
+    /// On success the inserted coins go into the bank and the machine is
+    /// idle again. On failure nothing changes.
+    pub fn purchase(&mut self, price: u64) -> Result<Purse, VendingError> {
+        let credit = self.credit().cents();
+        if credit < price {
+            return Err(VendingError::InsufficientCredit { price, credit });
+        }
+        let change = self
+            .bank
+            .sale(price, &self.inserted)
+            .map_err(VendingError::NoChange)?;
+        self.inserted = Purse::new();
+        Ok(change)
+    }
+
+    /// Returns the inserted coins, leaving the machine idle
+    pub fn cancel(&mut self) -> Purse {
+        std::mem::take(&mut self.inserted)
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChangeError;
+
+    fn purse(counts: &[(Coin, u32)]) -> Purse {
+        counts.iter().copied().collect()
+    }
+
+    fn machine() -> VendingMachine {
+        VendingMachine::new(purse(&[
+            (Coin::Penny, 5),
+            (Coin::Nickel, 5),
+            (Coin::Dime, 5),
+        ]))
+    }
+
+    // ========================================================================
+    // Transitions
+    // ========================================================================
+
+    #[test]
+    fn test_inserting_builds_credit() {
+        let mut machine = machine();
+        assert_eq!(machine.state(), VendingState::Idle);
+
+        assert_eq!(machine.insert(Coin::Quarter), 25);
+        assert_eq!(machine.insert(Coin::Dime), 35);
+        assert_eq!(machine.state(), VendingState::Credit(Money::from_cents(35)));
+        assert_eq!(
+            machine.inserted(),
+            &purse(&[(Coin::Dime, 1), (Coin::Quarter, 1)])
+        );
+    }
+
+    #[test]
+    fn test_purchase_dispenses_change_and_goes_idle() {
+        let mut machine = machine();
+        let bank_before = machine.bank().balance();
+        for _ in 0..3 {
+            machine.insert(Coin::Quarter);
+        }
+
+        let change = machine.purchase(60).unwrap();
+        assert_eq!(change, purse(&[(Coin::Dime, 1), (Coin::Nickel, 1)]));
+        assert_eq!(machine.state(), VendingState::Idle);
+        assert_eq!(
+            machine.bank().balance(),
+            bank_before + Money::from_cents(60)
+        );
+        assert_eq!(machine.bank().coins().count_of(Coin::Quarter), 3);
+    }
+
+    #[test]
+    fn test_exact_purchase() {
+        let mut machine = VendingMachine::default();
+        machine.insert(Coin::Quarter);
+        machine.insert(Coin::Quarter);
+        assert_eq!(machine.purchase(50), Ok(Purse::new()));
+        assert_eq!(machine.bank().balance(), 50);
+    }
+
+    #[test]
+    fn test_cancel_returns_inserted_coins() {
+        let mut machine = machine();
+        machine.insert(Coin::Dime);
+        machine.insert(Coin::Penny);
+
+        assert_eq!(
+            machine.cancel(),
+            purse(&[(Coin::Penny, 1), (Coin::Dime, 1)])
+        );
+        assert_eq!(machine.state(), VendingState::Idle);
+        assert_eq!(machine, self::machine());
+        assert_eq!(machine.cancel(), Purse::new());
+    }
+
+    // ========================================================================
+    // Refused purchases
+    // ========================================================================
+
+    #[test]
+    fn test_insufficient_credit_keeps_credit() {
+        let mut machine = machine();
+        machine.insert(Coin::Quarter);
+        let error = machine.purchase(65).unwrap_err();
+        assert_eq!(
+            error,
+            VendingError::InsufficientCredit {
+                price: 65,
+                credit: 25
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "insert 40 more: the price is 65 and the credit is 25"
+        );
+        assert_eq!(machine.state(), VendingState::Credit(Money::from_cents(25)));
+
+        // Topping up makes it go through
+        machine.insert(Coin::Quarter);
+        machine.insert(Coin::Dime);
+        machine.insert(Coin::Nickel);
+        assert_eq!(machine.purchase(65), Ok(Purse::new()));
+    }
+
+    #[test]
+    fn test_no_change_keeps_credit() {
+        // An empty bank can't give 15¢ back from a quarter
+        let mut machine = VendingMachine::default();
+        machine.insert(Coin::Quarter);
+        let error = machine.purchase(10).unwrap_err();
+        assert_eq!(
+            error,
+            VendingError::NoChange(SaleError::NoChange(ChangeError::NoExactChange {
+                amount: 15,
+                closest_below: 0
+            }))
+        );
+        assert!(error.to_string().starts_with("exact change only: "));
+        assert_eq!(machine.cancel(), purse(&[(Coin::Quarter, 1)]));
+        assert_eq!(machine.bank().balance(), 0);
+    }
+}
@@ -0,0 +1,832 @@
+// ============================================================================
+// WALLET MODULE: An Event-Sourced Purse
+// ============================================================================
+// A Wallet never edits its coins directly. Every change is a WalletEvent
+// appended to its history, and the coins are whatever replaying that
+// history gives:
+//
+//   let mut wallet = Wallet::new();
+//   wallet.deposit(&coins);
+//   wallet.pay(65)?;                       // Paid { amount: 65, coins }
+//   let same = Wallet::from_events(wallet.events().to_vec())?;
+//
+// The events serialize with serde, so they can live in any append-only
+// store. A WalletSnapshot records the coins after a number of events, and
+// from_snapshot() picks up from there with only the events that came later.
+//
+// Long simulations can also checkpoint and roll back:
+//
+//   let checkpoint = wallet.snapshot();    // kept in memory
+//   wallet.save_snapshot("day-1.json")?;   // or in a purse file
+//   wallet.restore(checkpoint)?;
+//
+// A WalletManager holds any number of wallets by name, e.g. one per user,
+// and moves coins between them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::purse::{read_purse_file, write_purse_file};
+use crate::{
+    ChangeError, Coin, Money, NotEnoughCoinsError, Purse, PurseFileError, PURSE_FILE_VERSION,
+};
+
+/// Something that happened to a wallet's coins
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WalletEvent {
+    /// Coins put in
+    Deposited { coins: Purse },
+    /// Coins taken out
+    Withdrawn { coins: Purse },
+    /// `coins` handed over to pay exactly `amount` cents
+    Paid { amount: u64, coins: Purse },
+    /// The coins traded for the fewest of the same value
+    Consolidated,
+}
+
+impl WalletEvent {
+    /// Applies the event to `purse`, or fails without changing it if coins
+    /// have to come out that it doesn't hold
+    ///
+    /// Panics if a deposit would leave more than u32::MAX of one coin.
+    pub fn apply(&self, purse: &mut Purse) -> Result<(), NotEnoughCoinsError> {
+        match self {
+            WalletEvent::Deposited { coins } => *purse += coins,
+            WalletEvent::Withdrawn { coins } | WalletEvent::Paid { coins, .. } => {
+                *purse = purse.checked_sub(coins)?;
+            }
+            WalletEvent::Consolidated => purse.consolidate(),
+        }
+        Ok(())
+    }
+}
+
+/// A wallet's coins after its first `sequence` events
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletSnapshot {
+    pub sequence: u64,
+    pub coins: Purse,
+}
+
+/// Identifies a restore point taken with Wallet::snapshot()
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SnapshotId(usize);
+
+impl fmt::Display for SnapshotId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Error returned when restoring a snapshot the wallet doesn't have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownSnapshotError(pub SnapshotId);
+
+impl fmt::Display for UnknownSnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no snapshot with id {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownSnapshotError {}
+
+/// On-disk layout: a purse file with the snapshot's position added
+#[derive(Serialize, Deserialize)]
+struct SnapshotFile {
+    version: u32,
+    sequence: u64,
+    coins: Purse,
+}
+
+/// Why an event in a history can't be replayed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayFailure {
+    /// Coins had to come out that the wallet didn't hold
+    NotEnoughCoins(NotEnoughCoinsError),
+    /// A deposit would leave the wallet with more than u32::MAX of the coin
+    TooManyCoins(Coin),
+}
+
+impl fmt::Display for ReplayFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayFailure::NotEnoughCoins(error) => write!(f, "{error}"),
+            ReplayFailure::TooManyCoins(coin) => {
+                write!(f, "the wallet would hold more than {} {coin:?}", u32::MAX)
+            }
+        }
+    }
+}
+
+/// Error returned when a history can't be replayed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletReplayError {
+    /// Position of the failing event in the whole history, from 0
+    pub sequence: u64,
+    pub error: ReplayFailure,
+}
+
+impl fmt::Display for WalletReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "event {} cannot be replayed: {}",
+            self.sequence, self.error
+        )
+    }
+}
+
+impl std::error::Error for WalletReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.error {
+            ReplayFailure::NotEnoughCoins(error) => Some(error),
+            ReplayFailure::TooManyCoins(_) => None,
+        }
+    }
+}
+
+// The first coin that depositing `deposit` would push past u32::MAX
+fn overflowing_coin(purse: &Purse, deposit: &Purse) -> Option<Coin> {
+    deposit
+        .iter()
+        .find(|&(coin, count)| purse.count_of(coin).checked_add(count).is_none())
+        .map(|(coin, _)| coin)
+}
+
+/// A purse whose coins are derived from a history of events
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Wallet {
+    // Where the history held here starts
+    base: WalletSnapshot,
+    // Events since `base`, oldest first
+    events: Vec<WalletEvent>,
+    // `base.coins` with `events` applied
+    coins: Purse,
+    // Restore points, indexed by SnapshotId
+    snapshots: Vec<WalletSnapshot>,
+}
+
+impl Wallet {
+    /// An empty wallet with no history
+    pub fn new() -> Self {
+        Wallet::default()
+    }
+
+    /// Rebuilds a wallet by replaying its whole history
+    pub fn from_events(events: Vec<WalletEvent>) -> Result<Wallet, WalletReplayError> {
+        Wallet::from_snapshot(WalletSnapshot::default(), events)
+    }
+
+    /// Rebuilds a wallet from a snapshot and the events recorded after it
+    pub fn from_snapshot(
+        snapshot: WalletSnapshot,
+        events: Vec<WalletEvent>,
+    ) -> Result<Wallet, WalletReplayError> {
+        let mut coins = snapshot.coins.clone();
+        for (i, event) in events.iter().enumerate() {
+            let fail = |error| WalletReplayError {
+                sequence: snapshot.sequence + i as u64,
+                error,
+            };
+            // Checked here, since a stored history may hold any counts
+            if let WalletEvent::Deposited { coins: deposit } = event {
+                if let Some(coin) = overflowing_coin(&coins, deposit) {
+                    return Err(fail(ReplayFailure::TooManyCoins(coin)));
+                }
+            }
+            event
+                .apply(&mut coins)
+                .map_err(|error| fail(ReplayFailure::NotEnoughCoins(error)))?;
+        }
+        Ok(Wallet {
+            base: snapshot,
+            events,
+            coins,
+            snapshots: Vec::new(),
+        })
+    }
+
+    /// The coins in the wallet
+    pub fn coins(&self) -> &Purse {
+        &self.coins
+    }
+
+    /// Total value of the coins in the wallet
+    pub fn balance(&self) -> Money {
+        self.coins.total_value()
+    }
+
+    /// The events since the snapshot the wallet was built from (or all of
+    /// them), oldest first
+    pub fn events(&self) -> &[WalletEvent] {
+        &self.events
+    }
+
+    /// Number of events in the wallet's whole history
+    pub fn sequence(&self) -> u64 {
+        self.base.sequence + self.events.len() as u64
+    }
+
+    /// The wallet's current coins and position in its history
+    pub fn to_snapshot(&self) -> WalletSnapshot {
+        WalletSnapshot {
+            sequence: self.sequence(),
+            coins: self.coins.clone(),
+        }
+    }
+
+    /// Keeps the current coins as a restore point, returning its id
+    pub fn snapshot(&mut self) -> SnapshotId {
+        self.snapshots.push(self.to_snapshot());
+        SnapshotId(self.snapshots.len() - 1)
+    }
+
+    /// Rolls the wallet back (or forward) to a restore point from
+    /// snapshot(), without replaying any events
+    ///
+    /// The history then starts at the snapshot: events() is empty and the
+    /// events recorded since are discarded. Every restore point is kept, so
+    /// the same one can be restored again.
+    pub fn restore(&mut self, id: SnapshotId) -> Result<(), UnknownSnapshotError> {
+        let snapshot = self
+            .snapshots
+            .get(id.0)
+            .cloned()
+            .ok_or(UnknownSnapshotError(id))?;
+        self.reset_to(snapshot);
+        Ok(())
+    }
+
+    /// Writes the current coins and position to `path`, as TOML if it ends
+    /// in `.toml` and as JSON otherwise
+    ///
+    /// The file is a purse file, so Purse::load() reads its coins too.
+    pub fn save_snapshot(&self, path: impl AsRef<Path>) -> Result<(), PurseFileError> {
+        write_purse_file(
+            path.as_ref(),
+            &SnapshotFile {
+                version: PURSE_FILE_VERSION,
+                sequence: self.sequence(),
+                coins: self.coins.clone(),
+            },
+        )
+    }
+
+    /// Rolls the wallet back to a snapshot written by save_snapshot(), as
+    /// restore() does
+    ///
+    /// Fails without changing the wallet if the file can't be read.
+    pub fn restore_from(&mut self, path: impl AsRef<Path>) -> Result<(), PurseFileError> {
+        let file: SnapshotFile = read_purse_file(path.as_ref())?;
+        self.reset_to(WalletSnapshot {
+            sequence: file.sequence,
+            coins: file.coins,
+        });
+        Ok(())
+    }
+
+    // Makes `snapshot` the start of the history, keeping the restore points
+    fn reset_to(&mut self, snapshot: WalletSnapshot) {
+        self.coins = snapshot.coins.clone();
+        self.base = snapshot;
+        self.events.clear();
+    }
+
+    /// Puts coins into the wallet
+    ///
+    /// Panics if the wallet would hold more than u32::MAX of one coin.
+    pub fn deposit(&mut self, coins: &Purse) {
+        self.record(WalletEvent::Deposited {
+            coins: coins.clone(),
+        })
+        .expect("depositing can't fail");
+    }
+
+    /// Takes coins out of the wallet, or fails without recording anything
+    /// if it doesn't hold them all
+    pub fn withdraw(&mut self, coins: &Purse) -> Result<(), NotEnoughCoinsError> {
+        self.record(WalletEvent::Withdrawn {
+            coins: coins.clone(),
+        })
+    }
+
+    /// Hands over the fewest coins worth exactly `amount` cents, returning
+    /// them, or fails without recording anything; see Purse::pay_exactly()
+    pub fn pay(&mut self, amount: u64) -> Result<Purse, ChangeError> {
+        let coins = self.coins.clone().pay_exactly(amount)?;
+        self.record(WalletEvent::Paid {
+            amount,
+            coins: coins.clone(),
+        })
+        .expect("the payment came from the wallet");
+        Ok(coins)
+    }
+
+    /// Trades the coins for the fewest of the same value
+    pub fn consolidate(&mut self) {
+        self.record(WalletEvent::Consolidated)
+            .expect("consolidating can't fail");
+    }
+
+    /// Applies `event` and appends it to the history, or does neither
+    pub fn record(&mut self, event: WalletEvent) -> Result<(), NotEnoughCoinsError> {
+        event.apply(&mut self.coins)?;
+        self.events.push(event);
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Named wallets
+// ============================================================================
+
+/// Error returned by WalletManager
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalletManagerError {
+    /// Wallet names can't be empty or only whitespace
+    InvalidName(String),
+    /// A wallet with the name already exists
+    AlreadyExists(String),
+    /// No wallet has the name
+    NotFound(String),
+    /// A transfer needed coins the sending wallet doesn't hold
+    NotEnoughCoins {
+        wallet: String,
+        error: NotEnoughCoinsError,
+    },
+}
+
+impl fmt::Display for WalletManagerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalletManagerError::InvalidName(name) => write!(f, "invalid wallet name {:?}", name),
+            WalletManagerError::AlreadyExists(name) => {
+                write!(f, "a wallet named {:?} already exists", name)
+            }
+            WalletManagerError::NotFound(name) => write!(f, "no wallet named {:?}", name),
+            WalletManagerError::NotEnoughCoins { wallet, error } => {
+                write!(f, "wallet {:?}: {}", wallet, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WalletManagerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WalletManagerError::NotEnoughCoins { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Wallets kept by name, listed in name order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WalletManager {
+    wallets: BTreeMap<String, Wallet>,
+}
+
+impl WalletManager {
+    /// A manager with no wallets
+    pub fn new() -> Self {
+        WalletManager::default()
+    }
+
+    /// Adds an empty wallet called `name`, returning it
+    pub fn create(&mut self, name: &str) -> Result<&mut Wallet, WalletManagerError> {
+        if name.trim().is_empty() {
+            return Err(WalletManagerError::InvalidName(name.to_string()));
+        }
+        if self.wallets.contains_key(name) {
+            return Err(WalletManagerError::AlreadyExists(name.to_string()));
+        }
+        Ok(self.wallets.entry(name.to_string()).or_default())
+    }
+
+    /// The wallet called `name`, if there is one
+    pub fn get(&self, name: &str) -> Option<&Wallet> {
+        self.wallets.get(name)
+    }
+
+    /// The wallet called `name` for changing, if there is one
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Wallet> {
+        self.wallets.get_mut(name)
+    }
+
+    /// Gives the wallet called `from` the name `to`, keeping its history
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), WalletManagerError> {
+        if to.trim().is_empty() {
+            return Err(WalletManagerError::InvalidName(to.to_string()));
+        }
+        if !self.wallets.contains_key(from) {
+            return Err(WalletManagerError::NotFound(from.to_string()));
+        }
+        if from == to {
+            return Ok(());
+        }
+        if self.wallets.contains_key(to) {
+            return Err(WalletManagerError::AlreadyExists(to.to_string()));
+        }
+        let wallet = self.wallets.remove(from).expect("checked above");
+        self.wallets.insert(to.to_string(), wallet);
+        Ok(())
+    }
+
+    /// Removes the wallet called `name`, returning it with its coins
+    pub fn delete(&mut self, name: &str) -> Result<Wallet, WalletManagerError> {
+        self.wallets
+            .remove(name)
+            .ok_or_else(|| WalletManagerError::NotFound(name.to_string()))
+    }
+
+    /// The names of every wallet, in order
+    pub fn list(&self) -> impl Iterator<Item = &str> + '_ {
+        self.wallets.keys().map(String::as_str)
+    }
+
+    /// Number of wallets
+    pub fn len(&self) -> usize {
+        self.wallets.len()
+    }
+
+    /// Whether there are no wallets
+    pub fn is_empty(&self) -> bool {
+        self.wallets.is_empty()
+    }
+
+    /// Moves `coins` from the wallet called `from` to the one called `to`
+    ///
+    /// Recorded as Withdrawn in the sender's history and Deposited in the
+    /// receiver's. Fails without changing either wallet if one doesn't
+    /// exist or the sender doesn't hold the coins.
+    pub fn transfer(
+        &mut self,
+        from: &str,
+        to: &str,
+        coins: &Purse,
+    ) -> Result<(), WalletManagerError> {
+        if !self.wallets.contains_key(to) {
+            return Err(WalletManagerError::NotFound(to.to_string()));
+        }
+        self.wallets
+            .get_mut(from)
+            .ok_or_else(|| WalletManagerError::NotFound(from.to_string()))?
+            .withdraw(coins)
+            .map_err(|error| WalletManagerError::NotEnoughCoins {
+                wallet: from.to_string(),
+                error,
+            })?;
+        self.wallets
+            .get_mut(to)
+            .expect("checked above")
+            .deposit(coins);
+        Ok(())
+    }
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn purse(counts: &[(Coin, u32)]) -> Purse {
+        counts.iter().copied().collect()
+    }
+
+    // A path in the temp directory that no other test uses
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("coins-wallet-{}-{name}", std::process::id()))
+    }
+
+    fn sample_wallet() -> Wallet {
+        let mut wallet = Wallet::new();
+        wallet.deposit(&purse(&[(Coin::Penny, 8), (Coin::Quarter, 4)]));
+        wallet.pay(52).unwrap();
+        wallet.withdraw(&purse(&[(Coin::Penny, 1)])).unwrap();
+        wallet.consolidate();
+        wallet
+    }
+
+    // ========================================================================
+    // Events
+    // ========================================================================
+
+    #[test]
+    fn test_operations_record_events() {
+        let wallet = sample_wallet();
+        assert_eq!(wallet.sequence(), 4);
+        assert_eq!(
+            wallet.events()[1],
+            WalletEvent::Paid {
+                amount: 52,
+                coins: purse(&[(Coin::Penny, 2), (Coin::Quarter, 2)])
+            }
+        );
+        assert_eq!(wallet.events()[3], WalletEvent::Consolidated);
+        // 5 pennies became a nickel
+        assert_eq!(
+            wallet.coins(),
+            &purse(&[(Coin::Nickel, 1), (Coin::Quarter, 2)])
+        );
+        assert_eq!(wallet.balance(), 55);
+    }
+
+    #[test]
+    fn test_failures_record_nothing() {
+        let mut wallet = sample_wallet();
+        assert!(wallet.withdraw(&purse(&[(Coin::Dime, 1)])).is_err());
+        assert!(wallet.pay(3).is_err());
+        assert!(wallet.pay(1_000).is_err());
+        assert_eq!(wallet, sample_wallet());
+    }
+
+    #[test]
+    fn test_event_json() {
+        let json = serde_json::to_value(&sample_wallet().events()[1]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "kind": "paid",
+                "amount": 52,
+                "coins": {"Penny": 2, "Quarter": 2}
+            })
+        );
+        let back: WalletEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(back, sample_wallet().events()[1]);
+        assert_eq!(
+            serde_json::to_value(WalletEvent::Consolidated).unwrap(),
+            serde_json::json!({"kind": "consolidated"})
+        );
+    }
+
+    // ========================================================================
+    // Replay and snapshots
+    // ========================================================================
+
+    #[test]
+    fn test_replay_rebuilds_the_wallet() {
+        let wallet = sample_wallet();
+        let replayed = Wallet::from_events(wallet.events().to_vec()).unwrap();
+        assert_eq!(replayed, wallet);
+        assert_eq!(Wallet::from_events(vec![]).unwrap(), Wallet::new());
+    }
+
+    #[test]
+    fn test_replay_of_impossible_history_fails() {
+        let events = vec![
+            WalletEvent::Deposited {
+                coins: purse(&[(Coin::Dime, 1)]),
+            },
+            WalletEvent::Withdrawn {
+                coins: purse(&[(Coin::Dime, 2)]),
+            },
+        ];
+        let error = Wallet::from_events(events).unwrap_err();
+        assert_eq!(error.sequence, 1);
+        assert!(
+            matches!(error.error, ReplayFailure::NotEnoughCoins(e) if e.coin == Coin::Dime),
+            "{error}"
+        );
+        assert_eq!(
+            error.to_string(),
+            "event 1 cannot be replayed: cannot remove 2 Dime from a purse holding 1"
+        );
+    }
+
+    #[test]
+    fn test_replay_of_overflowing_deposit_fails() {
+        let events = vec![
+            WalletEvent::Deposited {
+                coins: purse(&[(Coin::Penny, 1), (Coin::Dime, u32::MAX)]),
+            },
+            WalletEvent::Deposited {
+                coins: purse(&[(Coin::Penny, 1), (Coin::Dime, 1)]),
+            },
+        ];
+        let error = Wallet::from_events(events).unwrap_err();
+        assert_eq!(error.sequence, 1);
+        assert_eq!(error.error, ReplayFailure::TooManyCoins(Coin::Dime));
+        assert_eq!(
+            error.to_string(),
+            "event 1 cannot be replayed: the wallet would hold more than 4294967295 Dime"
+        );
+    }
+
+    #[test]
+    fn test_resume_from_snapshot() {
+        let mut wallet = sample_wallet();
+        let snapshot = wallet.to_snapshot();
+        assert_eq!(snapshot.sequence, 4);
+
+        wallet.deposit(&purse(&[(Coin::Dime, 3)]));
+        wallet.withdraw(&purse(&[(Coin::Quarter, 1)])).unwrap();
+        let later = wallet.events()[4..].to_vec();
+
+        let resumed = Wallet::from_snapshot(snapshot, later).unwrap();
+        assert_eq!(resumed.coins(), wallet.coins());
+        assert_eq!(resumed.sequence(), 6);
+        assert_eq!(resumed.events().len(), 2);
+        assert_eq!(resumed.to_snapshot(), wallet.to_snapshot());
+    }
+
+    #[test]
+    fn test_replay_error_counts_from_snapshot() {
+        let snapshot = WalletSnapshot {
+            sequence: 10,
+            coins: purse(&[(Coin::Penny, 1)]),
+        };
+        let error = Wallet::from_snapshot(
+            snapshot,
+            vec![
+                WalletEvent::Consolidated,
+                WalletEvent::Withdrawn {
+                    coins: purse(&[(Coin::Nickel, 1)]),
+                },
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(error.sequence, 11);
+    }
+
+    // ========================================================================
+    // Restore points
+    // ========================================================================
+
+    #[test]
+    fn test_restore_rolls_back() {
+        let mut wallet = sample_wallet();
+        let checkpoint = wallet.snapshot();
+        wallet.deposit(&purse(&[(Coin::Dime, 3)]));
+        wallet.pay(55).unwrap();
+        let later = wallet.snapshot();
+
+        wallet.restore(checkpoint).unwrap();
+        assert_eq!(wallet.coins(), sample_wallet().coins());
+        assert_eq!(wallet.sequence(), 4);
+        assert!(wallet.events().is_empty());
+
+        // New events carry on from the restore point
+        wallet.withdraw(&purse(&[(Coin::Nickel, 1)])).unwrap();
+        assert_eq!(wallet.sequence(), 5);
+        assert_eq!(wallet.events().len(), 1);
+
+        // Restore points survive a restore, in either direction
+        wallet.restore(later).unwrap();
+        assert_eq!(wallet.coins(), &purse(&[(Coin::Dime, 3)]));
+        assert_eq!(wallet.sequence(), 6);
+    }
+
+    #[test]
+    fn test_restore_unknown_snapshot() {
+        let mut wallet = sample_wallet();
+        let id = wallet.clone().snapshot();
+        let error = wallet.restore(id).unwrap_err();
+        assert_eq!(error, UnknownSnapshotError(id));
+        assert_eq!(error.to_string(), "no snapshot with id 0");
+        assert_eq!(wallet, sample_wallet());
+    }
+
+    #[test]
+    fn test_snapshot_files() {
+        for name in ["snapshot.json", "snapshot.toml"] {
+            let path = temp_path(name);
+            let mut wallet = sample_wallet();
+            wallet.save_snapshot(&path).unwrap();
+            wallet.deposit(&purse(&[(Coin::Quarter, 10)]));
+
+            let restored = wallet.restore_from(&path);
+            let coins = Purse::load(&path);
+            std::fs::remove_file(&path).unwrap();
+
+            restored.unwrap();
+            assert_eq!(wallet.coins(), sample_wallet().coins(), "{name}");
+            assert_eq!(wallet.sequence(), 4, "{name}");
+            assert_eq!(&coins.unwrap(), wallet.coins(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_restore_from_missing_file() {
+        let mut wallet = sample_wallet();
+        let error = wallet.restore_from(temp_path("missing.json")).unwrap_err();
+        assert!(matches!(error, PurseFileError::Io { .. }), "{error}");
+        assert_eq!(wallet, sample_wallet());
+    }
+
+    // ========================================================================
+    // Named wallets
+    // ========================================================================
+
+    fn family() -> WalletManager {
+        let mut wallets = WalletManager::new();
+        wallets
+            .create("parent")
+            .unwrap()
+            .deposit(&purse(&[(Coin::Quarter, 20)]));
+        wallets.create("kid").unwrap();
+        wallets
+    }
+
+    #[test]
+    fn test_create_and_list() {
+        let mut wallets = family();
+        assert_eq!(wallets.list().collect::<Vec<_>>(), vec!["kid", "parent"]);
+        assert_eq!(wallets.len(), 2);
+        assert!(WalletManager::new().is_empty());
+        assert_eq!(wallets.get("parent").unwrap().balance(), 500);
+        assert!(wallets.get("nobody").is_none());
+
+        assert_eq!(
+            wallets.create("kid").unwrap_err(),
+            WalletManagerError::AlreadyExists("kid".to_string())
+        );
+        assert_eq!(
+            wallets.create(" ").unwrap_err().to_string(),
+            "invalid wallet name \" \""
+        );
+        wallets
+            .get_mut("kid")
+            .unwrap()
+            .deposit(&purse(&[(Coin::Dime, 1)]));
+        assert_eq!(wallets.get("kid").unwrap().balance(), 10);
+    }
+
+    #[test]
+    fn test_rename_and_delete() {
+        let mut wallets = family();
+        wallets.rename("kid", "child").unwrap();
+        assert_eq!(wallets.list().collect::<Vec<_>>(), vec!["child", "parent"]);
+        assert!(wallets.rename("child", "child").is_ok());
+        assert_eq!(
+            wallets.rename("child", "parent"),
+            Err(WalletManagerError::AlreadyExists("parent".to_string()))
+        );
+        assert_eq!(
+            wallets.rename("kid", "other"),
+            Err(WalletManagerError::NotFound("kid".to_string()))
+        );
+
+        let parent = wallets.delete("parent").unwrap();
+        assert_eq!(parent.balance(), 500);
+        assert_eq!(wallets.list().collect::<Vec<_>>(), vec!["child"]);
+        assert_eq!(
+            wallets.delete("parent").unwrap_err().to_string(),
+            "no wallet named \"parent\""
+        );
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut wallets = family();
+        let allowance = purse(&[(Coin::Quarter, 4)]);
+        wallets.transfer("parent", "kid", &allowance).unwrap();
+
+        let (parent, kid) = (wallets.get("parent").unwrap(), wallets.get("kid").unwrap());
+        assert_eq!(parent.balance(), 400);
+        assert_eq!(kid.coins(), &allowance);
+        assert_eq!(
+            parent.events().last(),
+            Some(&WalletEvent::Withdrawn {
+                coins: allowance.clone()
+            })
+        );
+        assert_eq!(
+            kid.events(),
+            &[WalletEvent::Deposited {
+                coins: allowance.clone()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_failed_transfer_changes_nothing() {
+        let mut wallets = family();
+        let error = wallets
+            .transfer("kid", "parent", &purse(&[(Coin::Dime, 1)]))
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            WalletManagerError::NotEnoughCoins { wallet, .. } if wallet == "kid"
+        ));
+        assert_eq!(
+            error.to_string(),
+            "wallet \"kid\": cannot remove 1 Dime from a purse holding 0"
+        );
+        assert_eq!(
+            wallets.transfer("parent", "nobody", &purse(&[(Coin::Quarter, 1)])),
+            Err(WalletManagerError::NotFound("nobody".to_string()))
+        );
+        assert_eq!(
+            wallets.transfer("nobody", "kid", &Purse::new()),
+            Err(WalletManagerError::NotFound("nobody".to_string()))
+        );
+        assert_eq!(wallets, family());
+    }
+}
@@ -4,298 +4,3407 @@
 // This module provides a REST API for the coin combinations functionality
 
 use axum::{
-    http::StatusCode,
-    response::{IntoResponse, Json},
-    routing::get,
+    extract::{
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+        Path, Query, RawQuery, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+    routing::{get, post},
     Router,
 };
-use serde::Serialize;
-use std::sync::Arc;
+use chrono::{Timelike, Utc};
+use futures_util::stream::{self, Stream, StreamExt};
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tower_http::cors::CorsLayer;
 use tracing::info;
-
-use crate::{generate_all_combinations, generate_random_combination, total_value, Coin};
+use ts_rs::TS;
+
+use crate::catalog::{Catalog, Series};
+use crate::flip::{flip_stats, flips_with, FlipStats, Side};
+use crate::i18n::localized_names;
+use crate::powerset::subset_at;
+use crate::rolls::{self, RollReport};
+use crate::simulation::{simulate_with, SimulationReport};
+use crate::{
+    all_combinations_cached, combination_at, combination_index, daily_combination,
+    generate_all_combinations_sorted, random_stream_with, schema, stats, total_value, total_weight,
+    Coin, CoinWeights, Combination, CombinationMask, CombinationQuery, CurrencySystem,
+    Denomination, Lang, Locale, Money, NaiveDate, Purse, RandomOptions, RngAlgorithm, SortBy,
+};
 
 // ============================================================================
 // Response Structures
 // ============================================================================
 
 /// Response for /random endpoint
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "RandomResponse.d.ts")]
 pub struct RandomResponse {
     pub coins: Vec<Coin>,
-    pub value: u32,
+    /// Compact, stable ID of the combination (see CombinationMask)
+    pub mask: CombinationMask,
+    #[ts(type = "number")]
+    pub value: u64,
+    /// Value rendered for the requested `?locale=`, omitted if none was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub formatted_value: Option<String>,
+    /// Coin names in the requested `?lang=`, omitted if none was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub localized_coins: Option<Vec<String>>,
+}
+
+impl RandomResponse {
+    /// Describes `combination`, with the optional formatted value and names
+    fn new(combination: Vec<Coin>, locale: Option<Locale>, lang: Option<Lang>) -> Self {
+        let value = total_value(&combination);
+        RandomResponse {
+            localized_coins: lang.map(|lang| localized_names(&combination, lang)),
+            mask: CombinationMask::from(combination.as_slice()),
+            coins: combination,
+            value: value.cents(),
+            formatted_value: locale.map(|locale| value.format_locale(locale)),
+        }
+    }
+}
+
+/// Response for /random/daily
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "DailyResponse.d.ts")]
+pub struct DailyResponse {
+    /// The day this combination belongs to, as YYYY-MM-DD
+    #[schemars(with = "String")]
+    #[ts(type = "string")]
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub combination: RandomResponse,
+}
+
+/// Response for /random?count=N
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "RandomBatchResponse.d.ts")]
+pub struct RandomBatchResponse {
+    pub count: usize,
+    pub combinations: Vec<RandomResponse>,
+    /// Value of every draw together, in cents
+    #[ts(type = "number")]
+    pub total_value: u64,
+    /// `total_value` rendered for the requested `?locale=`, omitted if none
+    /// was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub formatted_total_value: Option<String>,
+}
+
+/// Response for /stats/random: the value of a random draw, in cents
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "RandomStatsResponse.d.ts")]
+pub struct RandomStatsResponse {
+    pub expected_value: f64,
+    /// Variance in square cents
+    pub variance: f64,
+    pub std_dev: f64,
+}
+
+/// Response for /flip
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "FlipResponse.d.ts")]
+pub struct FlipResponse {
+    pub flips: Vec<Side>,
+    pub stats: FlipStats,
 }
 
 /// Response for /all endpoint
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "AllCombinationsResponse.d.ts")]
 pub struct AllCombinationsResponse {
+    /// Number of combinations in this response
     pub total_combinations: usize,
     pub combinations: Vec<CombinationDetail>,
+    /// Number of combinations passing the filters, across every page
+    pub total: usize,
+    /// Position of the first combination of this page in the full list
+    pub offset: usize,
+    /// Page size, omitted if none was asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub limit: Option<usize>,
+    /// Link to the following page, omitted on the last one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub next: Option<String>,
+    /// Link to the preceding page, omitted on the first one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub prev: Option<String>,
 }
 
 /// Details of a single combination
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "CombinationDetail.d.ts")]
 pub struct CombinationDetail {
     pub index: usize,
-    pub coins: Vec<Coin>,
-    pub value: u32,
+    pub coins: Combination,
+    /// Compact, stable ID of the combination (see CombinationMask)
+    pub mask: CombinationMask,
+    #[ts(type = "number")]
+    pub value: u64,
+    pub weight_grams: f64,
+    /// Value rendered for the requested `?locale=`, omitted if none was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub formatted_value: Option<String>,
+    /// Coin names in the requested `?lang=`, omitted if none was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub localized_coins: Option<Vec<String>>,
+}
+
+impl CombinationDetail {
+    /// Details of `coins`, with the optional localized fields filled in
+    /// when a locale or language was asked for
+    fn new(coins: &Combination, locale: Option<Locale>, lang: Option<Lang>) -> Self {
+        CombinationDetail {
+            index: combination_index(coins),
+            coins: coins.clone(),
+            mask: CombinationMask::from(coins.as_slice()),
+            value: total_value(coins).cents(),
+            weight_grams: total_weight(coins),
+            formatted_value: locale.map(|locale| total_value(coins).format_locale(locale)),
+            localized_coins: lang.map(|lang| localized_names(coins, lang)),
+        }
+    }
 }
 
 /// Response for /health endpoint
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "HealthResponse.d.ts")]
 pub struct HealthResponse {
     pub status: String,
     pub service: String,
     pub version: String,
 }
 
-// ============================================================================
-// Application State
-// ============================================================================
+/// Response for /catalog endpoint
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "CatalogResponse.d.ts")]
+pub struct CatalogResponse {
+    pub total_series: usize,
+    pub series: Vec<Series>,
+}
 
-#[derive(Clone)]
-pub struct AppState {
-    // Could add database connections, cache, etc. here in the future
+/// One denomination's share of the change from POST /change
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "ChangeLine.d.ts")]
+pub struct ChangeLine {
+    pub name: &'static str,
+    /// Face value in the currency's minor unit
+    pub value: u32,
+    pub count: u32,
+}
+
+/// Response for POST /change
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "ChangeResponse.d.ts")]
+pub struct ChangeResponse {
+    pub currency: CurrencySystem,
+    pub strategy: ChangeStrategy,
+    /// Name of every coin handed over, smallest first
+    pub coins: Vec<&'static str>,
+    /// Every denomination of the currency with how many of it were used,
+    /// smallest first
+    pub counts: Vec<ChangeLine>,
+    pub coin_count: usize,
+    /// Value of the coins in minor units; always the requested amount
+    #[ts(type = "number")]
+    pub total: u64,
+    /// The total with its currency symbol, e.g. "$0.87"
+    pub formatted_total: String,
+}
+
+/// One coin's share of the total from POST /value
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "ValueLine.d.ts")]
+pub struct ValueLine {
+    pub coin: Coin,
+    pub count: u32,
+    /// Value of these coins in cents
+    #[ts(type = "number")]
+    pub value: u64,
+}
+
+/// Response for POST /value
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "ValueResponse.d.ts")]
+pub struct ValueResponse {
+    /// Number of coins submitted
+    #[ts(type = "number")]
+    pub coin_count: u64,
+    /// Value of every coin together, in cents
+    #[ts(type = "number")]
+    pub total: u64,
+    /// The total in dollars, e.g. "$0.51"
+    pub formatted_total: String,
+    /// One line per coin type, in ascending value order
+    pub breakdown: Vec<ValueLine>,
+}
+
+/// A name POST /validate couldn't match to a coin
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "InvalidCoinName.d.ts")]
+pub struct InvalidCoinName {
+    /// Index of the name in the submitted list
+    pub position: usize,
+    pub input: String,
+    pub message: String,
+    /// The coin the name was probably meant to be, if one is close
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub suggestion: Option<Coin>,
+}
+
+/// Response for POST /validate
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "ValidateResponse.d.ts")]
+pub struct ValidateResponse {
+    /// Whether every name is a coin
+    pub valid: bool,
+    /// The names that are coins, in the order submitted
+    pub coins: Vec<Coin>,
+    /// The names that aren't, in the order submitted
+    pub errors: Vec<InvalidCoinName>,
+}
+
+/// A combination of another currency's coins, from /all or /random with
+/// `?currency=`
+#[derive(Clone, Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "CurrencyCombination.d.ts")]
+pub struct CurrencyCombination {
+    /// Bit j is set when the j-th smallest coin is included, as for
+    /// CombinationDetail
+    #[ts(type = "number")]
+    pub index: u64,
+    /// Coin names, smallest first
+    pub coins: Vec<&'static str>,
+    /// Value in the currency's minor unit
+    #[ts(type = "number")]
+    pub value: u64,
+    pub weight_grams: f64,
+    /// The value with its currency symbol, e.g. "€1.50"
+    pub formatted_value: String,
+}
+
+impl CurrencyCombination {
+    /// Combination number `index` of `currency`'s coins
+    fn at(currency: CurrencySystem, index: u64) -> Self {
+        let coins = subset_at(currency.denominations(), index);
+        let value = coins.iter().map(|d| u64::from(d.value)).sum();
+        CurrencyCombination {
+            index,
+            coins: coins.iter().map(|d| d.name).collect(),
+            value,
+            weight_grams: coins.iter().map(|d| f64::from(d.mass_mg)).sum::<f64>() / 1000.0,
+            formatted_value: currency.format_amount(value),
+        }
+    }
+
+    /// Every combination of `currency`'s coins, in index order
+    fn all(currency: CurrencySystem) -> impl Iterator<Item = Self> {
+        (0..1u64 << currency.denominations().len()).map(move |index| Self::at(currency, index))
+    }
+}
+
+/// Response for /all with a currency other than US dollars; the fields
+/// mean the same as in AllCombinationsResponse
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "CurrencyCombinationsResponse.d.ts")]
+pub struct CurrencyCombinationsResponse {
+    pub currency: CurrencySystem,
+    pub total_combinations: usize,
+    pub combinations: Vec<CurrencyCombination>,
+    pub total: usize,
+    pub offset: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub next: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub prev: Option<String>,
+}
+
+/// Response for /random?count=N with a currency other than US dollars
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "CurrencyBatchResponse.d.ts")]
+pub struct CurrencyBatchResponse {
+    pub currency: CurrencySystem,
+    pub count: usize,
+    pub combinations: Vec<CurrencyCombination>,
+    /// Value of every draw together, in the currency's minor unit
+    #[ts(type = "number")]
+    pub total_value: u64,
+    pub formatted_total_value: String,
+}
+
+/// Response for /denominations
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "DenominationsResponse.d.ts")]
+pub struct DenominationsResponse {
+    pub currency: CurrencySystem,
+    /// English name of the currency, e.g. "Euro"
+    pub name: &'static str,
+    pub symbol: &'static str,
+    /// Minor units per major unit: 100 cents to the dollar, 1 for the yen
+    pub minor_units: u32,
+    /// The circulating coins, smallest first
+    pub denominations: Vec<Denomination>,
+}
+
+/// Body returned for rejected requests
+#[derive(Serialize, JsonSchema, TS)]
+#[ts(export, export_to = "ErrorResponse.d.ts")]
+pub struct ErrorResponse {
+    pub error: String,
 }
 
 // ============================================================================
-// HTTP Handlers
+// Request Parameters
 // ============================================================================
 
-/// GET /random - Returns a random coin combination
-async fn get_random_combination() -> impl IntoResponse {
-    let combination = generate_random_combination();
-    let value = total_value(&combination);
+/// Query parameters controlling how combinations are presented
+#[derive(Deserialize, Default)]
+pub struct FormatParams {
+    /// Locale tag such as "en-US" or "fr-FR", for formatted values
+    pub locale: Option<String>,
+    /// Language code such as "es" or "de", for localized coin names
+    pub lang: Option<String>,
+}
 
-    let response = RandomResponse {
-        coins: combination,
-        value,
-    };
+impl FormatParams {
+    /// Parses the `locale` parameter, if present
+    fn locale(&self) -> Result<Option<Locale>, ApiError> {
+        self.locale
+            .as_deref()
+            .map(|tag| tag.parse().map_err(ApiError::bad_request))
+            .transpose()
+    }
 
-    (StatusCode::OK, Json(response))
+    /// Parses the `lang` parameter, if present
+    fn lang(&self) -> Result<Option<Lang>, ApiError> {
+        self.lang
+            .as_deref()
+            .map(|code| code.parse().map_err(ApiError::bad_request))
+            .transpose()
+    }
 }
 
-/// GET /all - Returns all possible coin combinations
-async fn get_all_combinations() -> impl IntoResponse {
-    let all_combinations = generate_all_combinations();
+/// Draw restriction parameters shared by /random and /stats/random
+#[derive(Deserialize, Default)]
+pub struct OptionsParams {
+    /// Draw only among combinations with exactly this many coins
+    pub exact_coins: Option<String>,
+    /// "true" to never draw the empty combination
+    pub non_empty: Option<String>,
+    /// Draw only among combinations with at least this many coins
+    pub min_coins: Option<String>,
+    /// Draw only among combinations with at most this many coins
+    pub max_coins: Option<String>,
+    /// Draw only among combinations worth at least this many cents
+    pub min_value: Option<String>,
+    /// Draw only among combinations worth at most this many cents
+    pub max_value: Option<String>,
+}
 
-    let combinations: Vec<CombinationDetail> = all_combinations
-        .iter()
-        .enumerate()
-        .map(|(index, coins)| CombinationDetail {
-            index,
-            coins: coins.clone(),
-            value: total_value(coins),
-        })
-        .collect();
+impl OptionsParams {
+    /// Parses the `exact_coins` parameter, if present, allowing at most
+    /// `coin_types`
+    fn exact_coins(&self, coin_types: usize) -> Result<Option<usize>, ApiError> {
+        self.exact_coins
+            .as_deref()
+            .map(|k| match k.parse() {
+                Ok(k) if k <= coin_types => Ok(k),
+                _ => Err(ApiError::bad_request(format!(
+                    "invalid exact_coins: {k} (expected 0 to {coin_types})"
+                ))),
+            })
+            .transpose()
+    }
 
-    let response = AllCombinationsResponse {
-        total_combinations: combinations.len(),
-        combinations,
-    };
+    /// Parses the `non_empty` parameter, if present
+    fn non_empty(&self) -> Result<Option<bool>, ApiError> {
+        self.non_empty
+            .as_deref()
+            .map(|flag| {
+                flag.parse().map_err(|_| {
+                    ApiError::bad_request(format!(
+                        "invalid non_empty: {flag} (expected true or false)"
+                    ))
+                })
+            })
+            .transpose()
+    }
 
-    (StatusCode::OK, Json(response))
+    /// Parses one of the `min_*` or `max_*` parameters, if present
+    fn bound<T: FromStr>(name: &str, value: &Option<String>) -> Result<Option<T>, ApiError> {
+        value
+            .as_deref()
+            .map(|bound| {
+                bound
+                    .parse()
+                    .map_err(|_| ApiError::bad_request(format!("invalid {name}: {bound}")))
+            })
+            .transpose()
+    }
+
+    /// The draw restrictions these parameters ask for
+    ///
+    /// Contradictory coin-count restrictions are a 400; a value range that
+    /// no allowed combination falls in is a 422.
+    fn options(&self) -> Result<RandomOptions, ApiError> {
+        self.options_in(CurrencySystem::Usd)
+    }
+
+    /// Like options(), for draws among the coins of `currency`
+    fn options_in(&self, currency: CurrencySystem) -> Result<RandomOptions, ApiError> {
+        let is_satisfiable = |options: &RandomOptions| {
+            CurrencyCombination::all(currency)
+                .any(|c| options.allows_size_and_value(c.coins.len(), c.value))
+        };
+        let options = RandomOptions {
+            allow_empty: !self.non_empty()?.unwrap_or(false),
+            exact_coins: self.exact_coins(currency.denominations().len())?,
+            ..Default::default()
+        };
+        if !is_satisfiable(&options) {
+            return Err(ApiError::bad_request(
+                "no combination has 0 coins and is non-empty",
+            ));
+        }
+        let min_coins = Self::bound("min_coins", &self.min_coins)?;
+        let max_coins = Self::bound("max_coins", &self.max_coins)?;
+        let options = RandomOptions {
+            min_coins,
+            max_coins,
+            ..options
+        };
+        if !is_satisfiable(&options) {
+            return Err(ApiError::bad_request(format!(
+                "no combination allowed by exact_coins and non_empty has {} coins",
+                describe_range(min_coins, max_coins)
+            )));
+        }
+        let min_value = Self::bound("min_value", &self.min_value)?;
+        let max_value = Self::bound("max_value", &self.max_value)?;
+        let options = RandomOptions {
+            min_value,
+            max_value,
+            ..options
+        };
+        if !is_satisfiable(&options) {
+            let unit = match currency {
+                CurrencySystem::Usd => "cents",
+                _ => "minor units",
+            };
+            return Err(ApiError::unprocessable(format!(
+                "no combination allowed by the other parameters is worth {} {unit}",
+                describe_range(min_value, max_value)
+            )));
+        }
+        Ok(options)
+    }
 }
 
-/// GET /health - Health check endpoint
-async fn health_check() -> impl IntoResponse {
-    let response = HealthResponse {
-        status: "healthy".to_string(),
-        service: "coins-api".to_string(),
-        version: env!("CARGO_PKG_VERSION").to_string(),
-    };
+/// "between 2 and 3", "at least 2" or "at most 3", for error messages
+fn describe_range<T: fmt::Display>(min: Option<T>, max: Option<T>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("between {min} and {max}"),
+        (Some(min), None) => format!("at least {min}"),
+        (None, Some(max)) => format!("at most {max}"),
+        (None, None) => "any number of".to_string(),
+    }
+}
 
-    (StatusCode::OK, Json(response))
+/// Query parameters for /random
+#[derive(Deserialize, Default)]
+pub struct RandomParams {
+    #[serde(flatten)]
+    pub format: FormatParams,
+    #[serde(flatten)]
+    pub options: OptionsParams,
+    /// Seed for a reproducible draw; the same seed gives the same combination
+    pub seed: Option<String>,
+    /// Number of combinations to draw in one request
+    pub count: Option<String>,
 }
 
-/// GET / - Root endpoint with API information
-async fn root() -> impl IntoResponse {
-    let info = serde_json::json!({
-        "service": "Coin Combinations API",
-        "version": env!("CARGO_PKG_VERSION"),
-        "endpoints": {
-            "/": "API information",
-            "/health": "Health check",
-            "/random": "Get a random coin combination",
-            "/all": "Get all possible coin combinations (16 total)"
+impl RandomParams {
+    /// Parses the `seed` parameter, if present
+    fn seed(&self) -> Result<Option<u64>, ApiError> {
+        self.seed
+            .as_deref()
+            .map(|seed| {
+                seed.parse()
+                    .map_err(|_| ApiError::bad_request(format!("invalid seed: {seed}")))
+            })
+            .transpose()
+    }
+
+    /// Parses the `count` parameter, if present, allowing at most `limit`
+    fn count(&self, limit: usize) -> Result<Option<usize>, ApiError> {
+        self.count
+            .as_deref()
+            .map(|count| match count.parse() {
+                Ok(n) if n <= limit => Ok(n),
+                Ok(_) => Err(ApiError::bad_request(format!(
+                    "count must be at most {limit}"
+                ))),
+                Err(_) => Err(ApiError::bad_request(format!("invalid count: {count}"))),
+            })
+            .transpose()
+    }
+}
+
+/// Query parameters for /random/stream
+#[derive(Deserialize, Default)]
+pub struct StreamParams {
+    #[serde(flatten)]
+    pub random: RandomParams,
+    /// Milliseconds between combinations
+    pub interval_ms: Option<String>,
+}
+
+/// Pause between /random/stream events when `?interval_ms=` is absent
+pub const DEFAULT_STREAM_INTERVAL_MS: u64 = 1000;
+
+/// Shortest `?interval_ms=` accepted by /random/stream
+pub const MIN_STREAM_INTERVAL_MS: u64 = 10;
+
+impl StreamParams {
+    /// Parses the `interval_ms` parameter, allowing no less than
+    /// MIN_STREAM_INTERVAL_MS
+    fn interval(&self) -> Result<Duration, ApiError> {
+        let millis = match self.interval_ms.as_deref() {
+            None => DEFAULT_STREAM_INTERVAL_MS,
+            Some(ms) => match ms.parse() {
+                Ok(ms) if ms >= MIN_STREAM_INTERVAL_MS => ms,
+                Ok(_) => {
+                    return Err(ApiError::bad_request(format!(
+                        "interval_ms must be at least {MIN_STREAM_INTERVAL_MS}"
+                    )))
+                }
+                Err(_) => return Err(ApiError::bad_request(format!("invalid interval_ms: {ms}"))),
+            },
+        };
+        Ok(Duration::from_millis(millis))
+    }
+}
+
+/// Query parameters for /stats/random
+#[derive(Deserialize, Default)]
+pub struct RandomStatsParams {
+    #[serde(flatten)]
+    pub options: OptionsParams,
+    /// "uniform" (the default) or "circulation" for CoinWeights::circulation()
+    pub weights: Option<String>,
+}
+
+impl RandomStatsParams {
+    /// Parses the `weights` parameter: None for uniform draws
+    fn weights(&self) -> Result<Option<CoinWeights>, ApiError> {
+        match self.weights.as_deref() {
+            None | Some("uniform") => Ok(None),
+            Some("circulation") => Ok(Some(CoinWeights::circulation())),
+            Some(other) => Err(ApiError::bad_request(format!(
+                "invalid weights: {other} (expected uniform or circulation)"
+            ))),
         }
-    });
+    }
+}
 
-    (StatusCode::OK, Json(info))
+/// Query parameters for /random/daily
+#[derive(Deserialize, Default)]
+pub struct DailyParams {
+    #[serde(flatten)]
+    pub format: FormatParams,
+    /// Day to pick for, as YYYY-MM-DD; today (UTC) if absent
+    pub date: Option<String>,
 }
 
-// ============================================================================
-// Router Configuration
-// ============================================================================
+impl DailyParams {
+    /// Parses the `date` parameter, if present
+    fn date(&self) -> Result<Option<NaiveDate>, ApiError> {
+        self.date
+            .as_deref()
+            .map(|date| {
+                date.parse().map_err(|_| {
+                    ApiError::bad_request(format!("invalid date: {date} (expected YYYY-MM-DD)"))
+                })
+            })
+            .transpose()
+    }
+}
 
-/// Creates and configures the Axum router with all endpoints
-pub fn create_router() -> Router {
-    let state = Arc::new(AppState {});
+/// Query parameters for /flip
+#[derive(Deserialize, Default)]
+pub struct FlipParams {
+    /// Number of flips, 1 if absent
+    pub count: Option<String>,
+    /// Probability of heads, 0.5 if absent
+    pub bias: Option<String>,
+    /// Seed for reproducible flips
+    pub seed: Option<String>,
+}
 
-    Router::new()
-        .route("/", get(root))
-        .route("/health", get(health_check))
-        .route("/random", get(get_random_combination))
-        .route("/all", get(get_all_combinations))
-        .layer(CorsLayer::permissive())
-        .with_state(state)
+impl FlipParams {
+    /// Parses the `count` parameter, allowing at most `limit`
+    fn count(&self, limit: usize) -> Result<usize, ApiError> {
+        match self.count.as_deref().map(|count| (count, count.parse())) {
+            None => Ok(1),
+            Some((_, Ok(n))) if n <= limit => Ok(n),
+            Some((_, Ok(_))) => Err(ApiError::bad_request(format!(
+                "count must be at most {limit}"
+            ))),
+            Some((count, Err(_))) => Err(ApiError::bad_request(format!("invalid count: {count}"))),
+        }
+    }
+
+    /// Parses the `bias` parameter
+    fn bias(&self) -> Result<f64, ApiError> {
+        match self.bias.as_deref() {
+            None => Ok(0.5),
+            Some(bias) => bias
+                .parse()
+                .map_err(|_| ApiError::bad_request(format!("invalid bias: {bias}"))),
+        }
+    }
+
+    /// Parses the `seed` parameter, if present
+    fn seed(&self) -> Result<Option<u64>, ApiError> {
+        self.seed
+            .as_deref()
+            .map(|seed| {
+                seed.parse()
+                    .map_err(|_| ApiError::bad_request(format!("invalid seed: {seed}")))
+            })
+            .transpose()
+    }
 }
 
-// ============================================================================
-// Server Launch
-// ============================================================================
+/// Query parameters for /all
+#[derive(Deserialize, Default)]
+pub struct AllParams {
+    #[serde(flatten)]
+    pub format: FormatParams,
+    #[serde(flatten)]
+    pub currency: CurrencyParams,
+    /// Present combinations by "value" or "coin_count" instead of index order
+    pub sort: Option<SortBy>,
+    /// Only list combinations with exactly this many coins
+    pub len: Option<String>,
+    /// Only list combinations worth at least this many cents
+    pub min_value: Option<String>,
+    /// Only list combinations worth at most this many cents
+    pub max_value: Option<String>,
+    /// Only list combinations including these coins, comma-separated
+    pub contains: Option<String>,
+    /// Largest number of combinations to return
+    pub limit: Option<String>,
+    /// Number of combinations to skip
+    pub offset: Option<String>,
+}
 
-/// Starts the HTTP server on the specified address
-pub async fn run_server(addr: &str) -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .compact()
-        .init();
+impl AllParams {
+    /// Parses the `len` parameter, if present
+    fn len(&self) -> Result<Option<usize>, ApiError> {
+        self.len
+            .as_deref()
+            .map(|len| {
+                len.parse()
+                    .map_err(|_| ApiError::bad_request(format!("invalid len: {len}")))
+            })
+            .transpose()
+    }
 
-    info!("Starting Coin Combinations API server");
-    info!("Listening on http://{}", addr);
-    info!("Endpoints:");
-    info!("  GET /        - API information");
-    info!("  GET /health  - Health check");
-    info!("  GET /random  - Random coin combination");
-    info!("  GET /all     - All combinations");
+    /// Parses the `min_value` and `max_value` parameters, if present
+    fn value_range(&self) -> Result<(Option<u64>, Option<u64>), ApiError> {
+        let parse = |name: &str, value: &Option<String>| {
+            value
+                .as_deref()
+                .map(|cents| {
+                    cents
+                        .parse()
+                        .map_err(|_| ApiError::bad_request(format!("invalid {name}: {cents}")))
+                })
+                .transpose()
+        };
+        let min = parse("min_value", &self.min_value)?;
+        let max = parse("max_value", &self.max_value)?;
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return Err(ApiError::bad_request(format!(
+                    "min_value ({min}) is greater than max_value ({max})"
+                )));
+            }
+        }
+        Ok((min, max))
+    }
 
-    let app = create_router();
+    /// Parses the `contains` parameter: no coins if absent
+    fn contains(&self) -> Result<Vec<Coin>, ApiError> {
+        match self.contains.as_deref() {
+            None => Ok(Vec::new()),
+            Some(coins) => coins
+                .split(',')
+                .map(|coin| coin.parse().map_err(ApiError::bad_request))
+                .collect(),
+        }
+    }
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    /// Parses the `offset` and `limit` parameters: offset 0 and no limit
+    /// if absent
+    fn page(&self) -> Result<(usize, Option<usize>), ApiError> {
+        let offset = match self.offset.as_deref() {
+            None => 0,
+            Some(offset) => offset
+                .parse()
+                .map_err(|_| ApiError::bad_request(format!("invalid offset: {offset}")))?,
+        };
+        let limit = self
+            .limit
+            .as_deref()
+            .map(|limit| match limit.parse() {
+                Ok(0) => Err(ApiError::bad_request("limit must be at least 1")),
+                Ok(n) => Ok(n),
+                Err(_) => Err(ApiError::bad_request(format!("invalid limit: {limit}"))),
+            })
+            .transpose()?;
+        Ok((offset, limit))
+    }
+
+    /// The filters these parameters ask for
+    fn query(&self) -> Result<CombinationQuery, ApiError> {
+        let mut query = CombinationQuery::new();
+        if let Some(len) = self.len()? {
+            query = query.min_coins(len).max_coins(len);
+        }
+        let (min_value, max_value) = self.value_range()?;
+        if let Some(min) = min_value {
+            query = query.min_value(min);
+        }
+        if let Some(max) = max_value {
+            query = query.max_value(max);
+        }
+        for coin in self.contains()? {
+            query = query.contains(coin);
+        }
+        Ok(query)
+    }
+}
+
+/// Request body for POST /simulate
+#[derive(Deserialize, JsonSchema, TS)]
+#[ts(export, export_to = "SimulateRequest.d.ts")]
+pub struct SimulateRequest {
+    /// Number of random draws to make
+    pub trials: usize,
+    /// Seed for a reproducible run; the same seed gives the same report
+    #[serde(default)]
+    #[ts(optional, type = "number")]
+    pub seed: Option<u64>,
+    /// Draw only among combinations with exactly this many coins
+    #[serde(default)]
+    #[ts(optional)]
+    pub exact_coins: Option<usize>,
+    /// Never draw the empty combination
+    #[serde(default)]
+    pub non_empty: bool,
+}
+
+/// Largest `trials` accepted by POST /simulate
+pub const MAX_SIMULATION_TRIALS: usize = 1_000_000;
+
+impl SimulateRequest {
+    /// The draw restrictions this request asks for
+    fn options(&self) -> Result<RandomOptions, ApiError> {
+        if let Some(k) = self.exact_coins.filter(|&k| k > Coin::COUNT) {
+            return Err(ApiError::bad_request(format!(
+                "invalid exact_coins: {k} (expected 0 to {})",
+                Coin::COUNT
+            )));
+        }
+        let options = RandomOptions {
+            allow_empty: !self.non_empty,
+            exact_coins: self.exact_coins,
+            ..Default::default()
+        };
+        if !options.is_satisfiable() {
+            return Err(ApiError::bad_request(
+                "no combination has 0 coins and is non-empty",
+            ));
+        }
+        Ok(options)
+    }
 
+    /// Checks that `trials` is between 1 and MAX_SIMULATION_TRIALS
+    fn trials(&self) -> Result<usize, ApiError> {
+        match self.trials {
+            0 => Err(ApiError::bad_request("trials must be at least 1")),
+            n if n > MAX_SIMULATION_TRIALS => Err(ApiError::bad_request(format!(
+                "trials must be at most {MAX_SIMULATION_TRIALS}"
+            ))),
+            n => Ok(n),
+        }
+    }
+}
+
+/// How POST /change chooses coins
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "ChangeStrategy.d.ts")]
+pub enum ChangeStrategy {
+    /// The fewest coins (DenominationSet::make_change)
+    #[default]
+    Optimal,
+    /// Largest coin first (DenominationSet::make_change_greedy)
+    Greedy,
+}
+
+/// Request body for POST /change
+#[derive(Deserialize, JsonSchema, TS)]
+#[ts(export, export_to = "ChangeRequest.d.ts")]
+pub struct ChangeRequest {
+    /// Amount to pay in the currency's minor unit
+    #[ts(type = "number")]
+    pub amount: u64,
+    /// Currency code in any case, like `?currency=`
+    #[serde(default, deserialize_with = "currency_code")]
+    #[ts(optional)]
+    pub currency: Option<CurrencySystem>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub strategy: Option<ChangeStrategy>,
+    /// Refuse the request if paying takes more coins than this
+    #[serde(default)]
+    #[ts(optional)]
+    pub max_coins: Option<usize>,
+}
+
+// Parses the body's currency with CurrencySystem::from_str, as
+// CurrencyParams::currency() does for the query and header
+fn currency_code<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<CurrencySystem>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|code| code.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Largest `amount` accepted by POST /change
+pub const MAX_CHANGE_AMOUNT: u64 = 1_000_000;
+
+impl ChangeRequest {
+    /// Pays the amount, smallest coin first
+    fn pay(&self, currency: CurrencySystem) -> Result<Vec<u32>, ApiError> {
+        if self.amount > MAX_CHANGE_AMOUNT {
+            return Err(ApiError::bad_request(format!(
+                "amount must be at most {MAX_CHANGE_AMOUNT}"
+            )));
+        }
+        let denominations = currency.denomination_set();
+        let change = match self.strategy.unwrap_or_default() {
+            ChangeStrategy::Optimal => denominations.make_change(self.amount),
+            ChangeStrategy::Greedy => denominations.make_change_greedy(self.amount),
+        }
+        .map_err(ApiError::unprocessable)?;
+
+        match self.max_coins {
+            Some(max) if change.len() > max => Err(ApiError::unprocessable(format!(
+                "{} takes {} coins, more than max_coins ({max})",
+                currency.format_amount(self.amount),
+                change.len()
+            ))),
+            _ => Ok(change),
+        }
+    }
+}
+
+/// Request body for POST /value
+#[derive(Deserialize, JsonSchema, TS)]
+#[ts(export, export_to = "ValueRequest.d.ts")]
+pub struct ValueRequest {
+    /// Coin names in any spelling Coin::from_str accepts, e.g. "quarter"
+    pub coins: Vec<String>,
+}
+
+impl ValueRequest {
+    /// Parses every coin name, rejecting the first one that isn't a coin
+    fn purse(&self) -> Result<Purse, ApiError> {
+        self.coins
+            .iter()
+            .enumerate()
+            .map(|(position, name)| {
+                name.parse::<Coin>()
+                    .map_err(|error| ApiError::bad_request(format!("coins[{position}]: {error}")))
+            })
+            .collect()
+    }
+}
+
+/// Currency selection shared by /all, /random, /change and /denominations
+#[derive(Deserialize, Default)]
+pub struct CurrencyParams {
+    /// Currency code such as "usd" or "EUR"
+    pub currency: Option<String>,
+}
+
+/// Header consulted for the currency when there is no `?currency=`
+pub const CURRENCY_HEADER: &str = "x-currency";
+
+impl CurrencyParams {
+    /// The requested currency: `?currency=`, else the X-Currency header,
+    /// else US dollars
+    fn currency(&self, headers: &HeaderMap) -> Result<CurrencySystem, ApiError> {
+        let code = match (self.currency.as_deref(), headers.get(CURRENCY_HEADER)) {
+            (Some(code), _) => code,
+            (None, Some(header)) => header
+                .to_str()
+                .map_err(|_| ApiError::bad_request("invalid X-Currency header"))?,
+            (None, None) => return Ok(CurrencySystem::default()),
+        };
+        code.parse().map_err(ApiError::bad_request)
+    }
+}
+
+/// Rejects a parameter that only applies to US coins when another currency
+/// was asked for
+fn usd_only(currency: CurrencySystem, name: &str, given: bool) -> Result<(), ApiError> {
+    if given && currency != CurrencySystem::Usd {
+        return Err(ApiError::bad_request(format!(
+            "{name} is only supported for usd, not {currency}"
+        )));
+    }
     Ok(())
 }
 
+/// Query parameters for /catalog
+#[derive(Deserialize, Default)]
+pub struct CatalogParams {
+    /// Only list series of this coin (any spelling accepted by Coin::from_str)
+    pub coin: Option<String>,
+}
+
 // ============================================================================
-// TESTS MODULE
+// Error Handling
 // ============================================================================
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use axum::body::Body;
-    use axum::http::{Request, StatusCode};
-    use http_body_util::BodyExt;
-    use tower::ServiceExt;
+/// Error returned by handlers, rendered as an ErrorResponse with a status code
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
 
-    /// Helper function to convert response body to JSON string
-    async fn body_to_json(body: Body) -> serde_json::Value {
-        let bytes = body.collect().await.unwrap().to_bytes();
-        serde_json::from_slice(&bytes).unwrap()
+impl ApiError {
+    /// 400 Bad Request, for malformed or unsupported parameters
+    fn bad_request(message: impl ToString) -> Self {
+        ApiError {
+            status: StatusCode::BAD_REQUEST,
+            message: message.to_string(),
+        }
     }
 
-    // ========================================================================
-    // Response Structure Tests
-    // ========================================================================
+    /// 404 Not Found, for paths naming something that doesn't exist
+    fn not_found(message: impl ToString) -> Self {
+        ApiError {
+            status: StatusCode::NOT_FOUND,
+            message: message.to_string(),
+        }
+    }
 
-    #[test]
-    fn test_random_response_serialization() {
-        let response = RandomResponse {
-            coins: vec![Coin::Penny, Coin::Nickel],
-            value: 6,
-        };
+    /// 422 Unprocessable Entity, for well-formed requests that can't be met
+    fn unprocessable(message: impl ToString) -> Self {
+        ApiError {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            message: message.to_string(),
+        }
+    }
+}
 
-        let json = serde_json::to_string(&response).unwrap();
-        assert!(json.contains("\"coins\""));
-        assert!(json.contains("\"value\""));
-        assert!(json.contains("Penny"));
-        assert!(json.contains("Nickel"));
+// Malformed JSON bodies and query strings become a 400 with our usual
+// error body, instead of axum's plain-text rejection
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        ApiError::bad_request(rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiError::bad_request(rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> Self {
+        ApiError::bad_request(rejection.body_text())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorResponse {
+            error: self.message,
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+// ============================================================================
+// Application State
+// ============================================================================
+
+/// State shared by every handler
+///
+/// Clones share the same RNG, so a router built from a seeded state gives
+/// the same sequence of draws however its handlers are cloned.
+#[derive(Clone)]
+pub struct AppState {
+    /// RNG behind /random draws that don't pass their own `?seed=`
+    rng: Arc<Mutex<Box<dyn RngCore + Send>>>,
+    /// Seed the shared RNG started from, if it was seeded
+    seed: Option<u64>,
+    /// Generator behind `?seed=` draws
+    rng_algorithm: RngAlgorithm,
+    /// Largest `?count=` accepted by /random
+    max_random_count: usize,
+}
+
+/// Default cap on `/random?count=`
+pub const DEFAULT_MAX_RANDOM_COUNT: usize = 1000;
+
+impl AppState {
+    /// State with an RNG seeded from the operating system
+    pub fn new() -> Self {
+        AppState::with_rng(StdRng::from_entropy())
+    }
+
+    /// State whose RNG starts from `seed`, for reproducible servers and tests
+    pub fn with_seed(seed: u64) -> Self {
+        AppState {
+            seed: Some(seed),
+            ..AppState::with_rng(RngAlgorithm::default().seeded(seed))
+        }
+    }
+
+    /// State using the given RNG
+    pub fn with_rng(rng: impl RngCore + Send + 'static) -> Self {
+        AppState {
+            rng: Arc::new(Mutex::new(Box::new(rng))),
+            seed: None,
+            rng_algorithm: RngAlgorithm::default(),
+            max_random_count: DEFAULT_MAX_RANDOM_COUNT,
+        }
+    }
+
+    /// This state with `algorithm` behind `?seed=` draws
+    ///
+    /// A state made with with_seed() also reseeds its shared RNG with the
+    /// new algorithm, so the whole server is reproducible with it.
+    pub fn with_rng_algorithm(mut self, algorithm: RngAlgorithm) -> Self {
+        self.rng_algorithm = algorithm;
+        if let Some(seed) = self.seed {
+            self.rng = Arc::new(Mutex::new(algorithm.seeded(seed)));
+        }
+        self
+    }
+
+    /// This state with a different cap on `/random?count=`
+    pub fn with_max_random_count(mut self, limit: usize) -> Self {
+        self.max_random_count = limit;
+        self
+    }
+
+    /// Runs `f` with exclusive access to the shared RNG
+    fn with_shared_rng<T>(&self, f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        // A panic while holding the lock leaves the RNG usable, so a
+        // poisoned lock is not an error here
+        let mut rng = self
+            .rng
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut **rng)
+    }
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        AppState::new()
+    }
+}
+
+// ============================================================================
+// Streams
+// ============================================================================
+
+/// Endless random combinations meeting `options`, one every `period`
+///
+/// The async counterpart of random_stream(): the first combination is
+/// ready at once, and each later one `period` after the previous. A zero
+/// period yields them as fast as they are polled. Yields nothing if no
+/// combination meets the options.
+pub fn random_combination_stream<R>(
+    rng: R,
+    options: RandomOptions,
+    period: Duration,
+) -> impl Stream<Item = Combination> + Send
+where
+    R: rand::Rng + Send + 'static,
+{
+    let ticks = (!period.is_zero()).then(|| tokio::time::interval(period));
+    stream::unfold(
+        (random_stream_with(rng, options), ticks),
+        |(mut draws, mut ticks)| async move {
+            if let Some(ticks) = ticks.as_mut() {
+                ticks.tick().await;
+            }
+            let combination = draws.next()?;
+            Some((combination, (draws, ticks)))
+        },
+    )
+}
+
+// ============================================================================
+// HTTP Handlers
+// ============================================================================
+
+/// GET /random - Returns a random coin combination
+///
+/// Optional `?locale=` adds a `formatted_value` field and optional `?lang=`
+/// adds `localized_coins`; `?seed=` makes the draw reproducible,
+/// `?exact_coins=k` draws only among combinations of k coins (or
+/// `?min_coins=` and `?max_coins=` among a range of sizes), `?min_value=`
+/// and `?max_value=` only among those worth that many cents,
+/// and `?non_empty=true` never draws the empty one. `?count=N` returns a
+/// RandomBatchResponse of N draws instead of a single RandomResponse.
+async fn get_random_combination(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    currency: Result<Query<CurrencyParams>, QueryRejection>,
+    params: Result<Query<RandomParams>, QueryRejection>,
+) -> Result<Response, ApiError> {
+    let Query(params) = params?;
+    let Query(currency) = currency?;
+    let currency = currency.currency(&headers)?;
+    if currency != CurrencySystem::Usd {
+        return random_in_currency(&state, &params, currency);
+    }
+    let locale = params.format.locale()?;
+    let lang = params.format.lang()?;
+    let options = params.options.options()?;
+    let count = params.count(state.max_random_count)?;
+
+    // All draws come from one RNG acquisition
+    let draw = |rng: &mut dyn RngCore| {
+        (0..count.unwrap_or(1))
+            .map(|_| options.generate_with(rng).expect("options were validated"))
+            .collect::<Vec<_>>()
+    };
+    let combinations = match params.seed()? {
+        Some(seed) => draw(&mut *state.rng_algorithm.seeded(seed)),
+        None => state.with_shared_rng(draw),
+    };
+
+    let mut responses = combinations
+        .into_iter()
+        .map(|combination| RandomResponse::new(combination, locale, lang));
+
+    let response = match count {
+        Some(count) => {
+            let combinations: Vec<RandomResponse> = responses.collect();
+            let total_value: Money = combinations
+                .iter()
+                .map(|draw| Money::from_cents(draw.value))
+                .sum();
+            Json(RandomBatchResponse {
+                count,
+                combinations,
+                total_value: total_value.cents(),
+                formatted_total_value: locale.map(|locale| total_value.format_locale(locale)),
+            })
+            .into_response()
+        }
+        None => Json(responses.next().expect("one draw")).into_response(),
+    };
+    Ok((StatusCode::OK, response).into_response())
+}
+
+/// /random for a currency other than US dollars: draws uniformly among the
+/// combinations of its coins that the parameters allow
+fn random_in_currency(
+    state: &AppState,
+    params: &RandomParams,
+    currency: CurrencySystem,
+) -> Result<Response, ApiError> {
+    usd_only(currency, "locale", params.format.locale.is_some())?;
+    usd_only(currency, "lang", params.format.lang.is_some())?;
+    let options = params.options.options_in(currency)?;
+    let count = params.count(state.max_random_count)?;
+    let allowed: Vec<CurrencyCombination> = CurrencyCombination::all(currency)
+        .filter(|c| options.allows_size_and_value(c.coins.len(), c.value))
+        .collect();
+
+    let draw = |rng: &mut dyn RngCore| {
+        (0..count.unwrap_or(1))
+            .map(|_| allowed[rng.gen_range(0..allowed.len())].clone())
+            .collect::<Vec<_>>()
+    };
+    let mut combinations = match params.seed()? {
+        Some(seed) => draw(&mut *state.rng_algorithm.seeded(seed)),
+        None => state.with_shared_rng(draw),
+    };
+
+    let response = match count {
+        Some(count) => {
+            let total_value = combinations.iter().map(|c| c.value).sum();
+            Json(CurrencyBatchResponse {
+                currency,
+                count,
+                combinations,
+                total_value,
+                formatted_total_value: currency.format_amount(total_value),
+            })
+            .into_response()
+        }
+        None => Json(combinations.pop().expect("one draw")).into_response(),
+    };
+    Ok((StatusCode::OK, response).into_response())
+}
+
+/// GET /random/stream - Server-sent events, one random combination each
+///
+/// Takes the same parameters as /random, except that `?count=N` ends the
+/// stream after N combinations instead of batching them; without it the
+/// stream runs until the client disconnects. `?interval_ms=` sets the pause
+/// between events.
+async fn get_random_stream(
+    State(state): State<Arc<AppState>>,
+    params: Result<Query<StreamParams>, QueryRejection>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, ApiError> {
+    let Query(params) = params?;
+    let locale = params.random.format.locale()?;
+    let lang = params.random.format.lang()?;
+    let options = params.random.options.options()?;
+    let count = params.random.count(usize::MAX)?;
+    let interval = params.interval()?;
+
+    // The stream gets its own RNG so it never holds the shared one
+    let rng = match params.random.seed()? {
+        Some(seed) => state.rng_algorithm.seeded(seed),
+        None => state.with_shared_rng(|rng| -> Box<dyn RngCore + Send> {
+            Box::new(StdRng::from_rng(rng).expect("StdRng never fails to produce a seed"))
+        }),
+    };
+
+    let events = random_combination_stream(rng, options, interval)
+        .take(count.unwrap_or(usize::MAX))
+        .map(move |combination| {
+            Event::default().json_data(RandomResponse::new(combination.into(), locale, lang))
+        });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// GET /random/daily - The combination of the day
+///
+/// Every client gets the same pick for the same day, so responses carry a
+/// Cache-Control header: until midnight UTC for today's pick, a day for an
+/// explicit `?date=`. Accepts `?locale=` and `?lang=` like /random.
+async fn get_daily_combination(
+    params: Result<Query<DailyParams>, QueryRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Query(params) = params?;
+    let locale = params.format.locale()?;
+    let lang = params.format.lang()?;
+
+    let (date, max_age) = match params.date()? {
+        Some(date) => (date, SECONDS_PER_DAY),
+        None => {
+            let now = Utc::now();
+            let elapsed = i64::from(now.num_seconds_from_midnight());
+            (now.date_naive(), SECONDS_PER_DAY - elapsed)
+        }
+    };
+    let response = DailyResponse {
+        date,
+        combination: RandomResponse::new(daily_combination(date), locale, lang),
+    };
+    let cache_control = format!("public, max-age={max_age}");
+
+    Ok((
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, cache_control)],
+        Json(response),
+    ))
+}
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// GET /flip - Flips a coin
+///
+/// `?count=N` flips N times (at most the /random count cap), `?bias=p`
+/// lands heads with probability p, and `?seed=` makes the flips
+/// reproducible.
+async fn get_flip(
+    State(state): State<Arc<AppState>>,
+    params: Result<Query<FlipParams>, QueryRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Query(params) = params?;
+    let count = params.count(state.max_random_count)?;
+    let bias = params.bias()?;
+
+    let draw = |rng: &mut dyn RngCore| flips_with(rng, count, bias);
+    let flips = match params.seed()? {
+        Some(seed) => draw(&mut *state.rng_algorithm.seeded(seed)),
+        None => state.with_shared_rng(draw),
+    }
+    .map_err(ApiError::bad_request)?;
+
+    let response = FlipResponse {
+        stats: flip_stats(&flips),
+        flips,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// GET /all - Returns all possible coin combinations
+///
+/// Optional `?locale=` and `?lang=` add `formatted_value` and
+/// `localized_coins` fields to each combination; `?sort=value` or
+/// `?sort=coin_count` reorders them (each keeps its original `index`).
+/// `?len=k`, `?min_value=`, `?max_value=` and `?contains=Dime,Quarter`
+/// keep only the combinations that pass every filter. `?offset=` and
+/// `?limit=` return one page of what is left, with `next` and `prev`
+/// links that keep the other parameters.
+async fn get_all_combinations(
+    RawQuery(raw_query): RawQuery,
+    headers: HeaderMap,
+    params: Result<Query<AllParams>, QueryRejection>,
+) -> Result<Response, ApiError> {
+    let Query(params) = params?;
+    let currency = params.currency.currency(&headers)?;
+    if currency != CurrencySystem::Usd {
+        return all_in_currency(&params, raw_query.as_deref(), currency);
+    }
+    let locale = params.format.locale()?;
+    let lang = params.format.lang()?;
+    let query = params.query()?;
+    let (offset, limit) = params.page()?;
+    let all_combinations: Cow<[Combination]> = match params.sort {
+        Some(sort_by) => Cow::Owned(generate_all_combinations_sorted(sort_by)),
+        None => Cow::Borrowed(all_combinations_cached()),
+    };
+
+    let matching: Vec<&Combination> = all_combinations
+        .iter()
+        .filter(|coins| query.matches(coins))
+        .collect();
+    let total = matching.len();
+    let combinations: Vec<CombinationDetail> = matching
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|coins| CombinationDetail::new(coins, locale, lang))
+        .collect();
+
+    let (next, prev) = page_links(
+        raw_query.as_deref(),
+        offset,
+        limit,
+        combinations.len(),
+        total,
+    );
+    let response = AllCombinationsResponse {
+        total_combinations: combinations.len(),
+        combinations,
+        total,
+        offset,
+        limit,
+        next,
+        prev,
+    };
+
+    Ok((StatusCode::OK, Json(response)).into_response())
+}
+
+/// /all for a currency other than US dollars: the combinations of its
+/// coins, with the same filters (except `contains`), sorting and paging
+fn all_in_currency(
+    params: &AllParams,
+    raw_query: Option<&str>,
+    currency: CurrencySystem,
+) -> Result<Response, ApiError> {
+    usd_only(currency, "locale", params.format.locale.is_some())?;
+    usd_only(currency, "lang", params.format.lang.is_some())?;
+    usd_only(currency, "contains", params.contains.is_some())?;
+    let len = params.len()?;
+    let (min_value, max_value) = params.value_range()?;
+    let (offset, limit) = params.page()?;
+
+    let mut matching: Vec<CurrencyCombination> = CurrencyCombination::all(currency)
+        .filter(|c| {
+            len.is_none_or(|len| c.coins.len() == len)
+                && min_value.is_none_or(|min| c.value >= min)
+                && max_value.is_none_or(|max| c.value <= max)
+        })
+        .collect();
+    match params.sort {
+        Some(SortBy::Value) => matching.sort_by_key(|c| c.value),
+        Some(SortBy::CoinCount) => matching.sort_by_key(|c| c.coins.len()),
+        None => {}
+    }
+    let total = matching.len();
+    let combinations: Vec<CurrencyCombination> = matching
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+
+    let (next, prev) = page_links(raw_query, offset, limit, combinations.len(), total);
+    let response = CurrencyCombinationsResponse {
+        currency,
+        total_combinations: combinations.len(),
+        combinations,
+        total,
+        offset,
+        limit,
+        next,
+        prev,
+    };
+
+    Ok((StatusCode::OK, Json(response)).into_response())
+}
+
+/// The `next` and `prev` links of a page of `shown` combinations starting
+/// at `offset`, out of `total`
+///
+/// Past the end, `prev` goes to the last page rather than to another empty
+/// one.
+fn page_links(
+    raw_query: Option<&str>,
+    offset: usize,
+    limit: Option<usize>,
+    shown: usize,
+    total: usize,
+) -> (Option<String>, Option<String>) {
+    let link = |offset| all_page_link(raw_query, limit, offset);
+    let end = offset.saturating_add(shown);
+    let step = limit.unwrap_or(offset);
+    let prev = offset.saturating_sub(step).min(total.saturating_sub(step));
+    (
+        (end < total).then(|| link(end)),
+        (offset > 0).then(|| link(prev)),
+    )
+}
+
+/// Link to another page of /all: the parameters of `raw_query` with
+/// `limit` and `offset` replaced
+fn all_page_link(raw_query: Option<&str>, limit: Option<usize>, offset: usize) -> String {
+    let mut pairs: Vec<String> = raw_query
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| {
+            let name = pair.split('=').next().unwrap_or_default();
+            !name.is_empty() && name != "limit" && name != "offset"
+        })
+        .map(str::to_string)
+        .collect();
+    if let Some(limit) = limit {
+        pairs.push(format!("limit={limit}"));
+    }
+    pairs.push(format!("offset={offset}"));
+    format!("/all?{}", pairs.join("&"))
+}
+
+/// GET /combinations/{index} - One combination by its `index` in /all
+///
+/// Accepts the same `?locale=` and `?lang=` as /all. An index past the
+/// last combination is a 404.
+async fn get_combination(
+    index: Result<Path<usize>, PathRejection>,
+    params: Result<Query<FormatParams>, QueryRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Path(index) = index?;
+    let Query(params) = params?;
+    let locale = params.locale()?;
+    let lang = params.lang()?;
+    let coins = combination_at(index).ok_or_else(|| {
+        ApiError::not_found(format!(
+            "no combination with index {index} (expected 0 to {})",
+            all_combinations_cached().len() - 1
+        ))
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(CombinationDetail::new(&coins, locale, lang)),
+    ))
+}
+
+/// GET /health - Health check endpoint
+async fn health_check() -> impl IntoResponse {
+    let response = HealthResponse {
+        status: "healthy".to_string(),
+        service: "coins-api".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+
+    (StatusCode::OK, Json(response))
+}
+
+/// GET /catalog - Coin series from the numismatic catalog
+///
+/// Optional `?coin=` restricts the list to one coin.
+async fn get_catalog(Query(params): Query<CatalogParams>) -> Result<impl IntoResponse, ApiError> {
+    let catalog = Catalog::us();
+
+    let coins: Vec<Coin> = match params.coin.as_deref() {
+        Some(name) => vec![name.parse().map_err(ApiError::bad_request)?],
+        None => Coin::iter().collect(),
+    };
+
+    let series: Vec<Series> = coins
+        .into_iter()
+        .flat_map(|coin| catalog.series_of(coin).into_iter().cloned())
+        .collect();
+
+    let response = CatalogResponse {
+        total_series: series.len(),
+        series,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// GET /stats - Statistics over the values of all combinations
+async fn get_stats() -> impl IntoResponse {
+    let stats = stats(all_combinations_cached()).expect("there is always a combination");
+
+    (StatusCode::OK, Json(stats))
+}
+
+/// GET /stats/random - Expected value and variance of a random draw
+///
+/// Takes the same `?exact_coins=` and `?non_empty=` as /random, or
+/// `?weights=circulation` for draws weighted by CoinWeights::circulation().
+/// Weighted draws include each coin independently, so they can't be
+/// combined with the other two.
+async fn get_random_stats(
+    params: Result<Query<RandomStatsParams>, QueryRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Query(params) = params?;
+    let options = params.options.options()?;
+
+    let (expected_value, variance) = match params.weights()? {
+        Some(_) if options != RandomOptions::default() => {
+            return Err(ApiError::bad_request(
+                "weights can't be combined with other draw restrictions",
+            ));
+        }
+        Some(weights) => (weights.expected_value(), weights.variance()),
+        None => (
+            options.expected_value().expect("options were validated"),
+            options.variance().expect("options were validated"),
+        ),
+    };
+    let response = RandomStatsResponse {
+        expected_value,
+        variance,
+        std_dev: variance.sqrt(),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// POST /rolls - Packs a purse into standard bank rolls
+///
+/// The body is a purse: a map from coin to count, e.g. {"Penny": 120}.
+async fn post_rolls(
+    body: Result<Json<Purse>, JsonRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Json(purse) = body?;
+    let report: RollReport = rolls::pack(&purse);
+
+    Ok((StatusCode::OK, Json(report)))
+}
+
+/// POST /simulate - Summarises many random draws
+///
+/// The body is a SimulateRequest, e.g. {"trials": 10000, "non_empty": true}.
+/// Without a `seed`, the run is seeded from the shared RNG, which is only
+/// locked for that one step rather than for every draw.
+async fn post_simulate(
+    State(state): State<Arc<AppState>>,
+    body: Result<Json<SimulateRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Json(request) = body?;
+    let trials = request.trials()?;
+    let options = request.options()?;
+
+    let mut rng = match request.seed {
+        Some(seed) => state.rng_algorithm.seeded(seed),
+        None => state.with_shared_rng(|rng| {
+            Box::new(StdRng::from_rng(rng).expect("StdRng never fails to produce a seed"))
+        }),
+    };
+    // Up to a million draws: run them off the async workers so other
+    // requests aren't held up
+    let report: SimulationReport = tokio::task::spawn_blocking(move || {
+        simulate_with(&mut *rng, trials, &options).expect("request was validated")
+    })
+    .await
+    .expect("simulation task panicked");
+
+    Ok((StatusCode::OK, Json(report)))
+}
+
+/// POST /change - Pays an amount in coins
+///
+/// The body is a ChangeRequest, e.g. {"amount": 87, "strategy": "greedy"}.
+/// The currency is the body's `currency`, else `?currency=` or the
+/// X-Currency header, else US dollars; the strategy defaults to the fewest
+/// coins.
+async fn post_change(
+    headers: HeaderMap,
+    params: Result<Query<CurrencyParams>, QueryRejection>,
+    body: Result<Json<ChangeRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Query(params) = params?;
+    let Json(request) = body?;
+    let currency = match request.currency {
+        Some(currency) => currency,
+        None => params.currency(&headers)?,
+    };
+    let change = request.pay(currency)?;
+
+    let name = |value: u32| {
+        currency
+            .denomination(value)
+            .expect("change is paid in the currency's own coins")
+            .name
+    };
+    let counts = currency
+        .denominations()
+        .iter()
+        .map(|d| ChangeLine {
+            name: d.name,
+            value: d.value,
+            count: change.iter().filter(|&&v| v == d.value).count() as u32,
+        })
+        .collect();
+    let response = ChangeResponse {
+        currency,
+        strategy: request.strategy.unwrap_or_default(),
+        coins: change.iter().map(|&v| name(v)).collect(),
+        counts,
+        coin_count: change.len(),
+        total: request.amount,
+        formatted_total: currency.format_amount(request.amount),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// POST /value - Adds up a list of coins
+///
+/// The body is a ValueRequest, e.g. {"coins": ["Penny", "Quarter", "quarter"]}.
+async fn post_value(
+    body: Result<Json<ValueRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Json(request) = body?;
+    let purse = request.purse()?;
+
+    let total = purse.total_value();
+    let response = ValueResponse {
+        coin_count: purse.coin_count(),
+        total: total.cents(),
+        formatted_total: total.to_string(),
+        breakdown: Coin::iter()
+            .map(|coin| {
+                let count = purse.count_of(coin);
+                ValueLine {
+                    coin,
+                    count,
+                    value: coin.value_in_cents() * u64::from(count),
+                }
+            })
+            .collect(),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// POST /validate - Checks a list of coin names
+///
+/// Takes the same body as POST /value. Unlike /value, every name that
+/// isn't a coin is reported (with a suggestion when one is close), and the
+/// answer is a 200 either way.
+async fn post_validate(
+    body: Result<Json<ValueRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Json(request) = body?;
+
+    let mut coins = Vec::new();
+    let mut errors = Vec::new();
+    for (position, name) in request.coins.into_iter().enumerate() {
+        match name.parse::<Coin>() {
+            Ok(coin) => coins.push(coin),
+            Err(error) => errors.push(InvalidCoinName {
+                position,
+                message: error.to_string(),
+                suggestion: error.suggestion(),
+                input: name,
+            }),
+        }
+    }
+    let response = ValidateResponse {
+        valid: errors.is_empty(),
+        coins,
+        errors,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// GET /denominations - The coins of a currency
+///
+/// US dollars unless `?currency=` or the X-Currency header names another
+/// supported currency.
+async fn get_denominations(
+    headers: HeaderMap,
+    params: Result<Query<CurrencyParams>, QueryRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Query(params) = params?;
+    let currency = params.currency(&headers)?;
+
+    let response = DenominationsResponse {
+        currency,
+        name: currency.name(),
+        symbol: currency.symbol(),
+        minor_units: currency.minor_units(),
+        denominations: currency.denominations().to_vec(),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// GET /schema - JSON Schemas for all response types
+async fn get_schema() -> impl IntoResponse {
+    (StatusCode::OK, Json(schema()))
+}
+
+/// GET / - Root endpoint with API information
+async fn root() -> impl IntoResponse {
+    let info = serde_json::json!({
+        "service": "Coin Combinations API",
+        "version": env!("CARGO_PKG_VERSION"),
+        "endpoints": {
+            "/": "API information",
+            "/health": "Health check",
+            "/random": "Get a random coin combination",
+            "/random/daily": "The combination of the day (?date=YYYY-MM-DD for another day)",
+            "/random/stream": "Server-sent events with a random combination every ?interval_ms=",
+            "/all": "Get all possible coin combinations (16 total)",
+            "/combinations/{index}": "One combination by its index in /all",
+            "/flip": "Flip a coin (?count=N&bias=0.5)",
+            "/schema": "JSON Schemas for all response types",
+            "/catalog": "Numismatic series for each coin (?coin= to filter)",
+            "/denominations": "The coins of a currency (?currency=usd|eur|gbp|jpy)",
+            "/rolls": "POST a purse to pack it into bank rolls",
+            "/stats": "Value statistics over all combinations",
+            "/stats/random": "Expected value and variance of a random draw",
+            "/simulate": "POST trial settings to summarise many random draws",
+            "/change": "POST an amount to pay it in coins (optimal or greedy)",
+            "/value": "POST a list of coin names to add them up",
+            "/validate": "POST a list of coin names to check them, with suggestions"
+        }
+    });
+
+    (StatusCode::OK, Json(info))
+}
+
+// ============================================================================
+// Router Configuration
+// ============================================================================
+
+/// Creates and configures the Axum router with all endpoints
+pub fn create_router() -> Router {
+    create_router_with_state(AppState::new())
+}
+
+/// Like create_router(), but with the given state, e.g. a seeded RNG
+pub fn create_router_with_state(state: AppState) -> Router {
+    let state = Arc::new(state);
+
+    Router::new()
+        .route("/", get(root))
+        .route("/health", get(health_check))
+        .route("/random", get(get_random_combination))
+        .route("/random/daily", get(get_daily_combination))
+        .route("/random/stream", get(get_random_stream))
+        .route("/all", get(get_all_combinations))
+        .route("/combinations/{index}", get(get_combination))
+        .route("/flip", get(get_flip))
+        .route("/schema", get(get_schema))
+        .route("/catalog", get(get_catalog))
+        .route("/denominations", get(get_denominations))
+        .route("/rolls", post(post_rolls))
+        .route("/stats", get(get_stats))
+        .route("/stats/random", get(get_random_stats))
+        .route("/simulate", post(post_simulate))
+        .route("/change", post(post_change))
+        .route("/value", post(post_value))
+        .route("/validate", post(post_validate))
+        .layer(CorsLayer::permissive())
+        .with_state(state)
+}
+
+// ============================================================================
+// Server Launch
+// ============================================================================
+
+/// Starts the HTTP server on the specified address
+pub async fn run_server(addr: &str) -> Result<(), Box<dyn std::error::Error>> {
+    run_server_with_state(addr, AppState::new()).await
+}
+
+/// Like run_server(), but with the given state, e.g. a seeded or portable RNG
+pub async fn run_server_with_state(
+    addr: &str,
+    state: AppState,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize tracing
+    tracing_subscriber::fmt()
+        .with_target(false)
+        .compact()
+        .init();
+
+    info!("Starting Coin Combinations API server");
+    info!("Listening on http://{}", addr);
+    info!("Endpoints:");
+    info!("  GET /        - API information");
+    info!("  GET /health  - Health check");
+    info!("  GET /random  - Random coin combination");
+    info!("  GET /random/daily - Combination of the day");
+    info!("  GET /random/stream - Server-sent random combinations");
+    info!("  GET /all     - All combinations");
+    info!("  GET /combinations/{{index}} - One combination by index");
+    info!("  GET /flip    - Coin flips");
+    info!("  GET /schema  - JSON Schemas for response types");
+    info!("  GET /catalog - Numismatic catalog");
+    info!("  GET /denominations - Coins of a currency");
+    info!("  POST /rolls  - Pack coins into bank rolls");
+    info!("  GET /stats   - Value statistics");
+    info!("  GET /stats/random - Expected value of a random draw");
+    info!("  POST /simulate - Monte Carlo summary of random draws");
+    info!("  POST /change - Pay an amount in coins");
+    info!("  POST /value  - Value of a list of coins");
+    info!("  POST /validate - Check a list of coin names");
+    info!("Seeded draws use the {} RNG", state.rng_algorithm);
+
+    let app = create_router_with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+// ============================================================================
+// TESTS MODULE
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_random_combination_seeded, generate_random_combination_with};
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use http_body_util::BodyExt;
+    use rand::Rng;
+    use tower::ServiceExt;
+
+    /// Helper function to convert response body to JSON string
+    async fn body_to_json(body: Body) -> serde_json::Value {
+        let bytes = body.collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    // ========================================================================
+    // Response Structure Tests
+    // ========================================================================
+
+    #[test]
+    fn test_random_response_serialization() {
+        let response = RandomResponse {
+            coins: vec![Coin::Penny, Coin::Nickel],
+            mask: CombinationMask::from_bits(0b0011).unwrap(),
+            value: 6,
+            formatted_value: None,
+            localized_coins: None,
+        };
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"coins\""));
+        assert!(json.contains("\"value\""));
+        assert!(json.contains("Penny"));
+        assert!(json.contains("Nickel"));
+    }
+
+    #[test]
+    fn test_health_response_serialization() {
+        let response = HealthResponse {
+            status: "healthy".to_string(),
+            service: "coins-api".to_string(),
+            version: "0.1.0".to_string(),
+        };
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"status\":\"healthy\""));
+        assert!(json.contains("\"service\":\"coins-api\""));
+        assert!(json.contains("\"version\":\"0.1.0\""));
+    }
+
+    #[test]
+    fn test_combination_detail_serialization() {
+        let detail = CombinationDetail {
+            index: 5,
+            coins: vec![Coin::Penny, Coin::Dime].into(),
+            mask: CombinationMask::from_bits(0b0101).unwrap(),
+            value: 11,
+            weight_grams: 4.768,
+            formatted_value: None,
+            localized_coins: None,
+        };
+
+        let json = serde_json::to_string(&detail).unwrap();
+        assert!(json.contains("\"index\":5"));
+        assert!(json.contains("\"mask\":5"));
+        assert!(json.contains("\"value\":11"));
+        assert!(json.contains("\"weight_grams\":4.768"));
+        assert!(json.contains("Penny"));
+        assert!(json.contains("Dime"));
+    }
+
+    #[test]
+    fn test_all_combinations_response_structure() {
+        let response = AllCombinationsResponse {
+            total_combinations: 2,
+            combinations: vec![
+                CombinationDetail {
+                    index: 0,
+                    coins: Combination::new(),
+                    mask: CombinationMask::EMPTY,
+                    value: 0,
+                    weight_grams: 0.0,
+                    formatted_value: None,
+                    localized_coins: None,
+                },
+                CombinationDetail {
+                    index: 1,
+                    coins: vec![Coin::Penny].into(),
+                    mask: CombinationMask::from_bits(0b0001).unwrap(),
+                    value: 1,
+                    weight_grams: 2.5,
+                    formatted_value: None,
+                    localized_coins: None,
+                },
+            ],
+            total: 16,
+            offset: 0,
+            limit: Some(2),
+            next: Some("/all?limit=2&offset=2".to_string()),
+            prev: None,
+        };
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"total_combinations\":2"));
+        assert!(json.contains("\"combinations\""));
+        assert!(json.contains("\"total\":16"));
+        assert!(json.contains("\"next\":\"/all?limit=2&offset=2\""));
+        assert!(!json.contains("prev"));
+    }
+
+    // ========================================================================
+    // HTTP Endpoint Tests
+    // ========================================================================
+
+    #[tokio::test]
+    async fn test_root_endpoint() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        assert_eq!(body["service"], "Coin Combinations API");
+        assert!(body["endpoints"].is_object());
+        assert_eq!(body["endpoints"]["/"], "API information");
+        assert_eq!(body["endpoints"]["/health"], "Health check");
+        assert_eq!(
+            body["endpoints"]["/random"],
+            "Get a random coin combination"
+        );
+        assert_eq!(
+            body["endpoints"]["/all"],
+            "Get all possible coin combinations (16 total)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoint() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        assert_eq!(body["status"], "healthy");
+        assert_eq!(body["service"], "coins-api");
+        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_returns_valid_response() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/random")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        assert!(body["coins"].is_array());
+        assert!(body["value"].is_number());
+
+        let value = body["value"].as_u64().unwrap();
+        assert!(value <= 41, "Value should be at most 41 cents");
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_produces_variety() {
+        let app = create_router();
+
+        let mut results = Vec::new();
+        for _ in 0..10 {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri("/random")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            let body = body_to_json(response.into_body()).await;
+            results.push(format!("{:?}", body["coins"]));
+        }
+
+        // With 10 attempts, we should get at least 2 different results
+        results.sort();
+        results.dedup();
+        assert!(results.len() >= 2, "Random endpoint should produce variety");
+    }
+
+    #[tokio::test]
+    async fn test_all_endpoint_returns_all_combinations() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(Request::builder().uri("/all").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        assert_eq!(body["total_combinations"], 16);
+        assert!(body["combinations"].is_array());
+
+        let combinations = body["combinations"].as_array().unwrap();
+        assert_eq!(combinations.len(), 16);
+
+        // Check first combination (empty set)
+        assert_eq!(combinations[0]["index"], 0);
+        assert_eq!(combinations[0]["value"], 0);
+        assert_eq!(combinations[0]["coins"].as_array().unwrap().len(), 0);
+
+        // Check last combination (all coins)
+        assert_eq!(combinations[15]["index"], 15);
+        assert_eq!(combinations[15]["value"], 41);
+        assert_eq!(combinations[15]["coins"].as_array().unwrap().len(), 4);
+        assert_eq!(combinations[15]["weight_grams"], 15.438);
+        assert_eq!(combinations[15]["mask"], 15);
+    }
+
+    #[tokio::test]
+    async fn test_all_endpoint_combination_values_are_correct() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(Request::builder().uri("/all").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let body = body_to_json(response.into_body()).await;
+        let combinations = body["combinations"].as_array().unwrap();
+
+        // Verify specific combinations
+        // Combination 1: Penny only (value = 1)
+        assert_eq!(combinations[1]["value"], 1);
+
+        // Combination 2: Nickel only (value = 5)
+        assert_eq!(combinations[2]["value"], 5);
+
+        // Combination 4: Dime only (value = 10)
+        assert_eq!(combinations[4]["value"], 10);
+
+        // Combination 8: Quarter only (value = 25)
+        assert_eq!(combinations[8]["value"], 25);
+
+        // Combination 3: Penny + Nickel (value = 6)
+        assert_eq!(combinations[3]["value"], 6);
+
+        // Combination 5: Penny + Dime (value = 11)
+        assert_eq!(combinations[5]["value"], 11);
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_omits_formatted_value_by_default() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/random")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body = body_to_json(response.into_body()).await;
+        assert!(body.get("formatted_value").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_all_endpoint_with_locale() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/all?locale=fr-FR")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        let combinations = body["combinations"].as_array().unwrap();
+        assert_eq!(combinations[0]["formatted_value"], "0,00 €");
+        assert_eq!(combinations[15]["formatted_value"], "0,41 €");
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_with_locale() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/random?locale=en-US")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        let value = body["value"].as_u64().unwrap();
+        assert_eq!(body["formatted_value"], format!("$0.{:02}", value));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_locale_returns_400() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/random?locale=xx-YY")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = body_to_json(response.into_body()).await;
+        assert!(body["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("unknown locale 'xx-YY'"));
+    }
+
+    #[tokio::test]
+    async fn test_all_endpoint_with_lang() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/all?lang=es")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        let combinations = body["combinations"].as_array().unwrap();
+        assert_eq!(combinations[0]["localized_coins"], serde_json::json!([]));
+        assert_eq!(
+            combinations[8]["localized_coins"],
+            serde_json::json!(["Cuarto de dólar"])
+        );
+        // The canonical names are still present
+        assert_eq!(combinations[8]["coins"], serde_json::json!(["Quarter"]));
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_with_seed_is_reproducible() {
+        let mut bodies = Vec::new();
+        for _ in 0..2 {
+            let response = create_router()
+                .oneshot(
+                    Request::builder()
+                        .uri("/random?seed=42&lang=fr")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            bodies.push(body_to_json(response.into_body()).await);
+        }
+
+        assert_eq!(bodies[0], bodies[1]);
+        let expected = generate_random_combination_seeded(42);
+        assert_eq!(bodies[0]["coins"], serde_json::json!(expected));
+        assert!(bodies[0]["localized_coins"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_seed_ignores_shared_rng() {
+        // A seeded draw gives the same answer whatever came before it, and
+        // doesn't advance the shared RNG for the unseeded draws around it
+        let app = create_router_with_state(AppState::with_seed(7));
+        let mut coins = Vec::new();
+        for uri in ["/random", "/random?seed=42", "/random", "/random?seed=42"] {
+            let response = app
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            coins.push(body_to_json(response.into_body()).await["coins"].clone());
+        }
+
+        let expected = serde_json::json!(generate_random_combination_seeded(42));
+        assert_eq!(coins[1], expected);
+        assert_eq!(coins[3], expected);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(
+            coins[0],
+            serde_json::json!(generate_random_combination_with(&mut rng))
+        );
+        assert_eq!(
+            coins[2],
+            serde_json::json!(generate_random_combination_with(&mut rng))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_value_range() {
+        let (status, body) = get_json("/random?min_value=10&max_value=30&count=200").await;
+        assert_eq!(status, StatusCode::OK);
+        let values: Vec<u64> = body["combinations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["value"].as_u64().unwrap())
+            .collect();
+        assert!(values.iter().all(|value| (10..=30).contains(value)));
+        // Uniform over the 7 qualifying combinations, so each turns up
+        let distinct: std::collections::BTreeSet<u64> = values.into_iter().collect();
+        assert_eq!(distinct.len(), 7);
+
+        let (_, body) = get_json("/random?min_value=41").await;
+        assert_eq!(body["value"], 41);
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_unsatisfiable_value_range() {
+        for (uri, message) in [
+            ("/random?min_value=42", "worth at least 42 cents"),
+            (
+                "/random?max_value=0&non_empty=true",
+                "worth at most 0 cents",
+            ),
+            (
+                "/random?min_value=30&max_value=20",
+                "worth between 30 and 20 cents",
+            ),
+            ("/random?exact_coins=1&min_value=26", "at least 26"),
+            ("/stats/random?min_value=50", "at least 50"),
+        ] {
+            let (status, body) = get_json(uri).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{uri}");
+            assert!(body["error"].as_str().unwrap().contains(message), "{uri}");
+        }
+
+        let (status, body) = get_json("/random?min_value=ten").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid min_value: ten");
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_coin_count_range() {
+        let (status, body) = get_json("/random?min_coins=2&max_coins=3&count=300").await;
+        assert_eq!(status, StatusCode::OK);
+        let combinations = body["combinations"].as_array().unwrap();
+        let distinct: std::collections::BTreeSet<u64> = combinations
+            .iter()
+            .map(|c| c["mask"].as_u64().unwrap())
+            .collect();
+        // 6 pairs and 4 triples
+        assert_eq!(distinct.len(), 10);
+        assert!(combinations.iter().all(|c| {
+            let len = c["coins"].as_array().unwrap().len();
+            (2..=3).contains(&len)
+        }));
+
+        let (_, body) = get_json("/random?min_coins=4").await;
+        assert_eq!(body["value"], 41);
+        let (_, body) = get_json("/random?max_coins=0").await;
+        assert_eq!(body["coins"], serde_json::json!([]));
+        let (_, body) = get_json("/random?exact_coins=2&max_coins=9&seed=4").await;
+        assert_eq!(body["coins"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_rejects_bad_coin_counts() {
+        for (uri, error) in [
+            ("/random?min_coins=two", "invalid min_coins: two"),
+            ("/random?max_coins=-1", "invalid max_coins: -1"),
+            (
+                "/random?min_coins=3&max_coins=2",
+                "no combination allowed by exact_coins and non_empty has between 3 and 2 coins",
+            ),
+            (
+                "/random?min_coins=5",
+                "no combination allowed by exact_coins and non_empty has at least 5 coins",
+            ),
+            (
+                "/random?max_coins=0&non_empty=true",
+                "no combination allowed by exact_coins and non_empty has at most 0 coins",
+            ),
+            (
+                "/random?exact_coins=1&min_coins=2",
+                "no combination allowed by exact_coins and non_empty has at least 2 coins",
+            ),
+        ] {
+            let (status, body) = get_json(uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+            assert_eq!(body["error"], error, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_uses_shared_rng() {
+        // Two routers seeded alike give the same sequence of draws
+        let mut sequences = Vec::new();
+        for _ in 0..2 {
+            let app = create_router_with_state(AppState::with_seed(7));
+            let mut draws = Vec::new();
+            for _ in 0..5 {
+                let response = app
+                    .clone()
+                    .oneshot(
+                        Request::builder()
+                            .uri("/random")
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                draws.push(body_to_json(response.into_body()).await["coins"].clone());
+            }
+            sequences.push(draws);
+        }
+        assert_eq!(sequences[0], sequences[1]);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let expected: Vec<serde_json::Value> = (0..5)
+            .map(|_| serde_json::json!(generate_random_combination_with(&mut rng)))
+            .collect();
+        assert_eq!(sequences[0], expected);
+    }
+
+    #[test]
+    fn test_app_state_rng_algorithm_reseeds_seeded_state() {
+        let state = AppState::with_seed(5).with_rng_algorithm(RngAlgorithm::ChaCha8);
+        let drawn = state.with_shared_rng(|rng| rng.gen::<u64>());
+        assert_eq!(drawn, RngAlgorithm::ChaCha8.seeded(5).gen::<u64>());
+
+        // An unseeded state keeps its RNG and only changes `?seed=` draws
+        let state =
+            AppState::with_rng(StdRng::seed_from_u64(5)).with_rng_algorithm(RngAlgorithm::ChaCha8);
+        let drawn = state.with_shared_rng(|rng| rng.gen::<u64>());
+        assert_eq!(drawn, StdRng::seed_from_u64(5).gen::<u64>());
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_seed_uses_configured_rng() {
+        let state = AppState::new().with_rng_algorithm(RngAlgorithm::ChaCha8);
+        let response = create_router_with_state(state)
+            .oneshot(
+                Request::builder()
+                    .uri("/random?seed=9")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body = body_to_json(response.into_body()).await;
+        let options = RandomOptions {
+            rng: RngAlgorithm::ChaCha8,
+            ..Default::default()
+        };
+        assert_eq!(body["coins"], serde_json::json!(options.generate_seeded(9)));
+    }
+
+    #[test]
+    fn test_app_state_clones_share_rng() {
+        let state = AppState::with_seed(3);
+        let cloned = state.clone();
+        let first = state.with_shared_rng(|rng| rng.gen::<u64>());
+        let second = cloned.with_shared_rng(|rng| rng.gen::<u64>());
+
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_eq!((first, second), (rng.gen::<u64>(), rng.gen::<u64>()));
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_with_exact_coins() {
+        let app = create_router();
+        for k in 0..=Coin::COUNT {
+            for _ in 0..5 {
+                let response = app
+                    .clone()
+                    .oneshot(
+                        Request::builder()
+                            .uri(format!("/random?exact_coins={k}"))
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let body = body_to_json(response.into_body()).await;
+                assert_eq!(body["coins"].as_array().unwrap().len(), k);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_rejects_invalid_exact_coins() {
+        for k in ["5", "two"] {
+            let response = create_router()
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/random?exact_coins={k}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let body = body_to_json(response.into_body()).await;
+            assert_eq!(
+                body["error"],
+                format!("invalid exact_coins: {k} (expected 0 to 4)")
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_non_empty() {
+        let app = create_router();
+        for _ in 0..40 {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri("/random?non_empty=true")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = body_to_json(response.into_body()).await;
+            assert!(!body["coins"].as_array().unwrap().is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_rejects_bad_non_empty() {
+        for (uri, error) in [
+            (
+                "/random?non_empty=yes",
+                "invalid non_empty: yes (expected true or false)",
+            ),
+            (
+                "/random?non_empty=true&exact_coins=0",
+                "no combination has 0 coins and is non-empty",
+            ),
+        ] {
+            let response = create_router()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let body = body_to_json(response.into_body()).await;
+            assert_eq!(body["error"], error);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_with_count() {
+        let response = create_router()
+            .oneshot(
+                Request::builder()
+                    .uri("/random?count=20&non_empty=true")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        assert_eq!(body["count"], 20);
+        let combinations = body["combinations"].as_array().unwrap();
+        assert_eq!(combinations.len(), 20);
+        for combination in combinations {
+            assert!(!combination["coins"].as_array().unwrap().is_empty());
+            assert!(combination["value"].is_u64());
+        }
+        let total: u64 = combinations
+            .iter()
+            .map(|c| c["value"].as_u64().unwrap())
+            .sum();
+        assert_eq!(body["total_value"], total);
+        assert!(body.get("formatted_total_value").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_count_formats_total() {
+        let (_, body) = get_json("/random?count=4&exact_coins=4&locale=en-US").await;
+        assert_eq!(body["total_value"], 4 * 41);
+        assert_eq!(body["formatted_total_value"], "$1.64");
+        assert_eq!(body["combinations"][0]["formatted_value"], "$0.41");
+
+        let (_, body) = get_json("/random?count=0").await;
+        assert_eq!(body["combinations"], serde_json::json!([]));
+        assert_eq!(body["total_value"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_count_with_seed_starts_like_single_draw() {
+        let app = create_router();
+        let mut bodies = Vec::new();
+        for uri in ["/random?seed=9", "/random?seed=9&count=3"] {
+            let response = app
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            bodies.push(body_to_json(response.into_body()).await);
+        }
+        assert_eq!(bodies[1]["combinations"][0], bodies[0]);
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_count_limit() {
+        let app = create_router_with_state(AppState::new().with_max_random_count(5));
+        for (uri, status) in [
+            ("/random?count=5", StatusCode::OK),
+            ("/random?count=6", StatusCode::BAD_REQUEST),
+            ("/random?count=many", StatusCode::BAD_REQUEST),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), status, "{uri}");
+            if status == StatusCode::BAD_REQUEST {
+                let body = body_to_json(response.into_body()).await;
+                assert!(body["error"].as_str().unwrap().contains("count"));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_rejects_invalid_seed() {
+        let response = create_router()
+            .oneshot(
+                Request::builder()
+                    .uri("/random?seed=abc")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = body_to_json(response.into_body()).await;
+        assert_eq!(body["error"], "invalid seed: abc");
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_with_lang() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/random?lang=de")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body = body_to_json(response.into_body()).await;
+        let coins = body["coins"].as_array().unwrap();
+        let names = body["localized_coins"].as_array().unwrap();
+        assert_eq!(coins.len(), names.len());
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_lang_returns_400() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/all?lang=pt")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_stats_endpoint() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/stats")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        assert_eq!(body["count"], 16);
+        assert_eq!(body["min"], 0);
+        assert_eq!(body["max"], 41);
+        assert_eq!(body["mean"], 20.5);
+        assert_eq!(body["histogram"]["11"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_catalog_endpoint_lists_all_series() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/catalog")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        let series = body["series"].as_array().unwrap();
+        assert_eq!(body["total_series"], series.len());
+        assert_eq!(series[0]["coin"], "Penny");
+    }
+
+    #[tokio::test]
+    async fn test_catalog_endpoint_filters_by_coin() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/catalog?coin=nickel")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body = body_to_json(response.into_body()).await;
+        let series = body["series"].as_array().unwrap();
+        assert_eq!(series.len(), 3);
+        assert_eq!(series[1]["name"], "Buffalo");
+        assert_eq!(series[1]["first_year"], 1913);
+        assert_eq!(series[2]["last_year"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_catalog_endpoint_rejects_unknown_coin() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/catalog?coin=doubloon")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_rolls_endpoint() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/rolls")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"Penny": 123, "quarter": 85}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        assert_eq!(body["total_rolls"], 4);
+        assert_eq!(body["total_rolled_value"], 2100);
+        assert_eq!(body["total_loose_value"], 148);
+        assert_eq!(body["lines"][0]["coin"], "Penny");
+        assert_eq!(body["lines"][0]["loose"], 23);
+    }
+
+    #[tokio::test]
+    async fn test_rolls_endpoint_rejects_invalid_body() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/rolls")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"Doubloon": 3}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = body_to_json(response.into_body()).await;
+        assert!(body["error"].as_str().unwrap().contains("Doubloon"));
+    }
+
+    async fn get_json(uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = create_router()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        (status, body_to_json(response.into_body()).await)
+    }
+
+    #[tokio::test]
+    async fn test_random_stats_endpoint() {
+        let (status, body) = get_json("/stats/random").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["expected_value"], 20.5);
+        assert_eq!(body["variance"], 187.75);
+
+        let (status, body) = get_json("/stats/random?exact_coins=4").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["expected_value"], 41.0);
+        assert_eq!(body["std_dev"], 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_random_stats_endpoint_weighted() {
+        let (status, body) = get_json("/stats/random?weights=circulation").await;
+        assert_eq!(status, StatusCode::OK);
+        let weights = CoinWeights::circulation();
+        // JSON numbers don't round-trip every last bit of an f64
+        let close = |json: &serde_json::Value, expected: f64| {
+            (json.as_f64().unwrap() - expected).abs() < 1e-9
+        };
+        assert!(close(&body["expected_value"], weights.expected_value()));
+        assert!(close(&body["variance"], weights.variance()));
+    }
+
+    #[tokio::test]
+    async fn test_random_stats_endpoint_rejects_bad_params() {
+        for uri in [
+            "/stats/random?weights=lucky",
+            "/stats/random?weights=circulation&non_empty=true",
+            "/stats/random?exact_coins=0&non_empty=true",
+        ] {
+            let (status, _) = get_json(uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_daily_endpoint_for_a_date() {
+        let (status, body) = get_json("/random/daily?date=2024-07-04").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["date"], "2024-07-04");
+
+        let expected = daily_combination(NaiveDate::from_ymd_opt(2024, 7, 4).unwrap());
+        assert_eq!(body["coins"], serde_json::json!(expected));
+        assert_eq!(body["value"], total_value(&expected).cents());
+    }
+
+    #[tokio::test]
+    async fn test_daily_endpoint_defaults_to_today() {
+        let response = create_router()
+            .oneshot(
+                Request::builder()
+                    .uri("/random/daily")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let cache_control = response.headers()[header::CACHE_CONTROL].to_str().unwrap();
+        let max_age: i64 = cache_control
+            .strip_prefix("public, max-age=")
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((1..=SECONDS_PER_DAY).contains(&max_age));
+
+        let body = body_to_json(response.into_body()).await;
+        let date: NaiveDate = body["date"].as_str().unwrap().parse().unwrap();
+        // Allow for the test running across midnight
+        let today = Utc::now().date_naive();
+        assert!(date == today || date.succ_opt() == Some(today));
+    }
+
+    #[tokio::test]
+    async fn test_daily_endpoint_rejects_bad_date() {
+        let (status, body) = get_json("/random/daily?date=2024-02-30").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("invalid date"));
+    }
+
+    #[tokio::test]
+    async fn test_flip_endpoint_defaults_to_one_fair_flip() {
+        let (status, body) = get_json("/flip").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["flips"].as_array().unwrap().len(), 1);
+        assert_eq!(body["stats"]["count"], 1);
+        assert_eq!(body["stats"]["longest_streak"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_flip_endpoint_with_count_bias_and_seed() {
+        let (status, body) = get_json("/flip?count=20&bias=1&seed=3").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["stats"]["heads"], 20);
+        assert_eq!(body["stats"]["longest_streak_side"], "Heads");
+
+        let (_, first) = get_json("/flip?count=30&seed=8").await;
+        let (_, second) = get_json("/flip?count=30&seed=8").await;
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_flip_endpoint_rejects_bad_params() {
+        for (uri, message) in [
+            ("/flip?bias=1.5", "invalid probability"),
+            ("/flip?bias=heads", "invalid bias"),
+            ("/flip?count=-1", "invalid count"),
+            ("/flip?count=1001", "at most 1000"),
+            ("/flip?seed=x", "invalid seed"),
+        ] {
+            let (status, body) = get_json(uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+            assert!(body["error"].as_str().unwrap().contains(message), "{uri}");
+        }
+    }
+
+    async fn get_events(uri: &str) -> (StatusCode, Vec<serde_json::Value>) {
+        let response = create_router()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let events = String::from_utf8(body.to_vec())
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .map(|data| serde_json::from_str(data).unwrap())
+            .collect();
+        (status, events)
+    }
+
+    #[tokio::test]
+    async fn test_random_stream_endpoint() {
+        let (status, events) = get_events("/random/stream?count=3&interval_ms=10").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(events.len(), 3);
+        for event in &events {
+            assert!(event["coins"].is_array());
+            assert!(event["value"].is_u64());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_random_stream_endpoint_with_seed_and_options() {
+        let uri = "/random/stream?count=5&interval_ms=10&seed=4&exact_coins=2";
+        let (_, first) = get_events(uri).await;
+        let (_, second) = get_events(uri).await;
+        assert_eq!(first, second);
+        for event in &first {
+            assert_eq!(event["coins"].as_array().unwrap().len(), 2);
+        }
+
+        let mut rng = StdRng::seed_from_u64(4);
+        let options = RandomOptions {
+            exact_coins: Some(2),
+            ..Default::default()
+        };
+        let expected: Vec<_> = (0..5)
+            .map(|_| serde_json::json!(options.generate_with(&mut rng).unwrap()))
+            .collect();
+        let coins: Vec<_> = first.iter().map(|event| event["coins"].clone()).collect();
+        assert_eq!(coins, expected);
+    }
+
+    #[tokio::test]
+    async fn test_random_stream_endpoint_rejects_bad_params() {
+        for uri in [
+            "/random/stream?interval_ms=5",
+            "/random/stream?interval_ms=soon",
+            "/random/stream?exact_coins=9",
+        ] {
+            let (status, _) = get_json(uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_random_combination_stream_paces_draws() {
+        let period = Duration::from_millis(20);
+        let start = tokio::time::Instant::now();
+        let draws: Vec<Combination> =
+            random_combination_stream(StdRng::seed_from_u64(1), RandomOptions::default(), period)
+                .take(3)
+                .collect()
+                .await;
+        assert_eq!(draws.len(), 3);
+        // The first is immediate, the other two wait a period each
+        assert!(start.elapsed() >= 2 * period);
+
+        let unpaced: Vec<Combination> = random_combination_stream(
+            StdRng::seed_from_u64(1),
+            RandomOptions::default(),
+            Duration::ZERO,
+        )
+        .take(3)
+        .collect()
+        .await;
+        assert_eq!(unpaced, draws);
+    }
+
+    async fn post_simulate(app: Router, body: &'static str) -> Response {
+        app.oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/simulate")
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_simulate_endpoint() {
+        let response = post_simulate(
+            create_router(),
+            r#"{"trials": 2000, "non_empty": true, "seed": 5}"#,
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        assert_eq!(body["trials"], 2000);
+        assert_eq!(body["empty_rate"], 0.0);
+        assert_eq!(body["value"]["count"], 2000);
+        assert!(body["coin_frequency"]["Penny"].as_f64().unwrap() > 0.4);
+    }
+
+    #[tokio::test]
+    async fn test_simulate_endpoint_with_seed_is_reproducible() {
+        let request = r#"{"trials": 100, "seed": 11}"#;
+        let first = post_simulate(create_router(), request).await;
+        let second = post_simulate(create_router(), request).await;
+
+        assert_eq!(
+            body_to_json(first.into_body()).await,
+            body_to_json(second.into_body()).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_simulate_endpoint_rejects_bad_requests() {
+        for (body, message) in [
+            (r#"{"trials": 0}"#, "at least 1"),
+            (r#"{"trials": 1000001}"#, "at most 1000000"),
+            (r#"{"trials": 10, "exact_coins": 5}"#, "invalid exact_coins"),
+            (
+                r#"{"trials": 10, "exact_coins": 0, "non_empty": true}"#,
+                "non-empty",
+            ),
+            (r#"{"seed": 1}"#, "trials"),
+        ] {
+            let response = post_simulate(create_router(), body).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{body}");
+
+            let json = body_to_json(response.into_body()).await;
+            assert!(json["error"].as_str().unwrap().contains(message), "{body}");
+        }
+    }
+
+    async fn post_json(uri: &str, body: &'static str) -> (StatusCode, serde_json::Value) {
+        let response = create_router()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        (status, body_to_json(response.into_body()).await)
+    }
+
+    #[tokio::test]
+    async fn test_change_endpoint() {
+        let (status, body) = post_json("/change", r#"{"amount": 87}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["currency"], "usd");
+        assert_eq!(body["strategy"], "optimal");
+        assert_eq!(
+            body["coins"],
+            serde_json::json!(["Penny", "Penny", "Dime", "Quarter", "Quarter", "Quarter"])
+        );
+        assert_eq!(
+            body["counts"][0],
+            serde_json::json!({"name": "Penny", "value": 1, "count": 2})
+        );
+        assert_eq!(body["counts"][1]["count"], 0);
+        assert_eq!(body["coin_count"], 6);
+        assert_eq!(body["total"], 87);
+        assert_eq!(body["formatted_total"], "$0.87");
+    }
+
+    #[tokio::test]
+    async fn test_change_endpoint_currency_and_strategy() {
+        let (status, body) = post_json(
+            "/change",
+            r#"{"amount": 388, "currency": "eur", "strategy": "greedy"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["strategy"], "greedy");
+        // 2 euro, 1 euro, 50, 20, 10, 5, 2 and 1 cent
+        assert_eq!(body["coin_count"], 8);
+        assert_eq!(body["coins"][7], "2 euro");
+        assert_eq!(body["counts"].as_array().unwrap().len(), 8);
+        assert_eq!(body["formatted_total"], "€3.88");
+
+        let (status, body) = post_json("/change", r#"{"amount": 5, "currency": "EUR"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["currency"], "eur");
+    }
+
+    #[tokio::test]
+    async fn test_change_endpoint_max_coins() {
+        let (status, _) = post_json("/change", r#"{"amount": 87, "max_coins": 6}"#).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = post_json("/change", r#"{"amount": 87, "max_coins": 5}"#).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body["error"],
+            "$0.87 takes 6 coins, more than max_coins (5)"
+        );
     }
 
-    #[test]
-    fn test_health_response_serialization() {
-        let response = HealthResponse {
-            status: "healthy".to_string(),
-            service: "coins-api".to_string(),
-            version: "0.1.0".to_string(),
-        };
-
-        let json = serde_json::to_string(&response).unwrap();
-        assert!(json.contains("\"status\":\"healthy\""));
-        assert!(json.contains("\"service\":\"coins-api\""));
-        assert!(json.contains("\"version\":\"0.1.0\""));
+    #[tokio::test]
+    async fn test_change_endpoint_rejects_bad_requests() {
+        for (body, message) in [
+            (r#"{"amount": 1000001}"#, "at most 1000000"),
+            (
+                r#"{"amount": 5, "currency": "cad"}"#,
+                "unknown currency 'cad'",
+            ),
+            (r#"{"amount": 5, "strategy": "fastest"}"#, "strategy"),
+            (r#"{"amount": -5}"#, "amount"),
+        ] {
+            let (status, json) = post_json("/change", body).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+            assert!(json["error"].as_str().unwrap().contains(message), "{body}");
+        }
     }
 
-    #[test]
-    fn test_combination_detail_serialization() {
-        let detail = CombinationDetail {
-            index: 5,
-            coins: vec![Coin::Penny, Coin::Dime],
-            value: 11,
-        };
+    #[tokio::test]
+    async fn test_value_endpoint() {
+        let (status, body) = post_json(
+            "/value",
+            r#"{"coins": ["Penny", "Quarter", "quarter", " DIME ", "pennies"]}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["coin_count"], 5);
+        assert_eq!(body["total"], 62);
+        assert_eq!(body["formatted_total"], "$0.62");
+        assert_eq!(
+            body["breakdown"],
+            serde_json::json!([
+                {"coin": "Penny", "count": 2, "value": 2},
+                {"coin": "Nickel", "count": 0, "value": 0},
+                {"coin": "Dime", "count": 1, "value": 10},
+                {"coin": "Quarter", "count": 2, "value": 50}
+            ])
+        );
 
-        let json = serde_json::to_string(&detail).unwrap();
-        assert!(json.contains("\"index\":5"));
-        assert!(json.contains("\"value\":11"));
-        assert!(json.contains("Penny"));
-        assert!(json.contains("Dime"));
+        let (status, body) = post_json("/value", r#"{"coins": []}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total"], 0);
+        assert_eq!(body["formatted_total"], "$0.00");
     }
 
-    #[test]
-    fn test_all_combinations_response_structure() {
-        let response = AllCombinationsResponse {
-            total_combinations: 2,
-            combinations: vec![
-                CombinationDetail {
-                    index: 0,
-                    coins: vec![],
-                    value: 0,
-                },
-                CombinationDetail {
-                    index: 1,
-                    coins: vec![Coin::Penny],
-                    value: 1,
-                },
-            ],
-        };
+    #[tokio::test]
+    async fn test_value_endpoint_rejects_bad_requests() {
+        let (status, body) = post_json("/value", r#"{"coins": ["Dime", "Doubloon"]}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("coins[1]: unknown coin 'Doubloon'"));
+
+        for request in [r#"{"coins": "Dime"}"#, r#"{}"#, "not json"] {
+            let (status, body) = post_json("/value", request).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{request}");
+            assert!(body["error"].is_string(), "{request}");
+        }
+    }
 
-        let json = serde_json::to_string(&response).unwrap();
-        assert!(json.contains("\"total_combinations\":2"));
-        assert!(json.contains("\"combinations\""));
+    #[tokio::test]
+    async fn test_validate_endpoint() {
+        let (status, body) = post_json(
+            "/validate",
+            r#"{"coins": ["Penny", "qaurter", "dime", "Doubloon", "nikel"]}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["valid"], false);
+        assert_eq!(body["coins"], serde_json::json!(["Penny", "Dime"]));
+
+        let errors = body["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0]["position"], 1);
+        assert_eq!(errors[0]["input"], "qaurter");
+        assert_eq!(errors[0]["suggestion"], "Quarter");
+        assert!(errors[0]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("unknown coin 'qaurter'"));
+        assert_eq!(errors[1]["position"], 3);
+        assert!(errors[1].get("suggestion").is_none());
+        assert_eq!(errors[2]["position"], 4);
+        assert_eq!(errors[2]["suggestion"], "Nickel");
     }
 
-    // ========================================================================
-    // HTTP Endpoint Tests
-    // ========================================================================
+    #[tokio::test]
+    async fn test_validate_endpoint_all_valid() {
+        let (status, body) = post_json("/validate", r#"{"coins": ["25c", "QUARTERS"]}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!({"valid": true, "coins": ["Quarter", "Quarter"], "errors": []})
+        );
+
+        let (status, _) = post_json("/validate", r#"{"coins": [25]}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
 
     #[tokio::test]
-    async fn test_root_endpoint() {
+    async fn test_all_endpoint_sorted_by_value() {
         let app = create_router();
 
         let response = app
-            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .oneshot(
+                Request::builder()
+                    .uri("/all?sort=value")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
             .await
             .unwrap();
 
         assert_eq!(response.status(), StatusCode::OK);
 
         let body = body_to_json(response.into_body()).await;
-        assert_eq!(body["service"], "Coin Combinations API");
-        assert!(body["endpoints"].is_object());
-        assert_eq!(body["endpoints"]["/"], "API information");
-        assert_eq!(body["endpoints"]["/health"], "Health check");
-        assert_eq!(
-            body["endpoints"]["/random"],
-            "Get a random coin combination"
-        );
-        assert_eq!(
-            body["endpoints"]["/all"],
-            "Get all possible coin combinations (16 total)"
-        );
+        let combinations = body["combinations"].as_array().unwrap();
+        let values: Vec<u64> = combinations
+            .iter()
+            .map(|c| c["value"].as_u64().unwrap())
+            .collect();
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        // Nickel (5) and Penny + Nickel (6) both come before Dime (10)
+        assert_eq!(combinations[2]["index"], 2);
+        assert_eq!(combinations[3]["index"], 3);
+        assert_eq!(combinations[4]["index"], 4);
     }
 
     #[tokio::test]
-    async fn test_health_endpoint() {
+    async fn test_all_endpoint_sorted_by_coin_count() {
         let app = create_router();
 
         let response = app
             .oneshot(
                 Request::builder()
-                    .uri("/health")
+                    .uri("/all?sort=coin_count")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body = body_to_json(response.into_body()).await;
+        let combinations = body["combinations"].as_array().unwrap();
+        assert_eq!(combinations[4]["index"], 8);
+        assert_eq!(combinations[5]["coins"].as_array().unwrap().len(), 2);
+        assert_eq!(combinations[15]["index"], 15);
+    }
+
+    #[tokio::test]
+    async fn test_all_endpoint_filters_by_len() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/all?len=2&sort=value")
                     .body(Body::empty())
                     .unwrap(),
             )
@@ -305,121 +3414,460 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
 
         let body = body_to_json(response.into_body()).await;
-        assert_eq!(body["status"], "healthy");
-        assert_eq!(body["service"], "coins-api");
-        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(body["total_combinations"], 6);
+        let combinations = body["combinations"].as_array().unwrap();
+        assert!(combinations
+            .iter()
+            .all(|c| c["coins"].as_array().unwrap().len() == 2));
+        assert_eq!(combinations[0]["value"], 6);
     }
 
     #[tokio::test]
-    async fn test_random_endpoint_returns_valid_response() {
+    async fn test_all_endpoint_rejects_bad_len() {
         let app = create_router();
 
         let response = app
             .oneshot(
                 Request::builder()
-                    .uri("/random")
+                    .uri("/all?len=two")
                     .body(Body::empty())
                     .unwrap(),
             )
             .await
             .unwrap();
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
         let body = body_to_json(response.into_body()).await;
-        assert!(body["coins"].is_array());
-        assert!(body["value"].is_number());
+        assert_eq!(body["error"], "invalid len: two");
+    }
 
-        let value = body["value"].as_u64().unwrap();
-        assert!(value <= 41, "Value should be at most 41 cents");
+    #[tokio::test]
+    async fn test_all_endpoint_filters_by_value_and_coin() {
+        let (status, body) = get_json("/all?min_value=10&max_value=30&contains=dime").await;
+        assert_eq!(status, StatusCode::OK);
+        // Dime, Penny + Dime, Nickel + Dime, Penny + Nickel + Dime
+        let indices: Vec<u64> = body["combinations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["index"].as_u64().unwrap())
+            .collect();
+        assert_eq!(indices, [4, 5, 6, 7]);
+        assert_eq!(body["total_combinations"], 4);
+
+        let (_, body) = get_json("/all?contains=Dime,25&len=2").await;
+        assert_eq!(body["total_combinations"], 1);
+        assert_eq!(body["combinations"][0]["value"], 35);
+
+        let (_, body) = get_json("/all?min_value=42").await;
+        assert_eq!(body["total_combinations"], 0);
     }
 
     #[tokio::test]
-    async fn test_random_endpoint_produces_variety() {
-        let app = create_router();
+    async fn test_all_endpoint_rejects_bad_filters() {
+        for (uri, message) in [
+            ("/all?min_value=ten", "invalid min_value: ten"),
+            ("/all?max_value=-1", "invalid max_value: -1"),
+            ("/all?min_value=30&max_value=10", "greater than max_value"),
+            ("/all?contains=Doubloon", "unknown coin 'Doubloon'"),
+            ("/all?contains=Dime,", "unknown coin ''"),
+        ] {
+            let (status, body) = get_json(uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+            assert!(body["error"].as_str().unwrap().contains(message), "{uri}");
+        }
+    }
 
-        let mut results = Vec::new();
-        for _ in 0..10 {
-            let response = app
-                .clone()
-                .oneshot(
-                    Request::builder()
-                        .uri("/random")
-                        .body(Body::empty())
-                        .unwrap(),
-                )
-                .await
-                .unwrap();
+    #[tokio::test]
+    async fn test_all_endpoint_without_paging() {
+        let (_, body) = get_json("/all").await;
+        assert_eq!(body["total"], 16);
+        assert_eq!(body["total_combinations"], 16);
+        assert_eq!(body["offset"], 0);
+        assert!(body.get("limit").is_none());
+        assert!(body.get("next").is_none());
+        assert!(body.get("prev").is_none());
+    }
 
-            let body = body_to_json(response.into_body()).await;
-            results.push(format!("{:?}", body["coins"]));
+    #[tokio::test]
+    async fn test_all_endpoint_pages() {
+        let (status, body) = get_json("/all?limit=5&offset=5").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total"], 16);
+        assert_eq!(body["total_combinations"], 5);
+        assert_eq!(body["combinations"][0]["index"], 5);
+        assert_eq!(body["limit"], 5);
+        assert_eq!(body["next"], "/all?limit=5&offset=10");
+        assert_eq!(body["prev"], "/all?limit=5&offset=0");
+
+        // Following `next` walks the whole list once
+        let mut indices = Vec::new();
+        let mut uri = "/all?sort=value&limit=6".to_string();
+        loop {
+            let (_, page) = get_json(&uri).await;
+            for combination in page["combinations"].as_array().unwrap() {
+                indices.push(combination["index"].as_u64().unwrap());
+            }
+            match page["next"].as_str() {
+                Some(next) => uri = next.to_string(),
+                None => break,
+            }
         }
+        let (_, all) = get_json("/all?sort=value").await;
+        let expected: Vec<u64> = all["combinations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["index"].as_u64().unwrap())
+            .collect();
+        assert_eq!(indices, expected);
+    }
 
-        // With 10 attempts, we should get at least 2 different results
-        results.sort();
-        results.dedup();
-        assert!(results.len() >= 2, "Random endpoint should produce variety");
+    #[tokio::test]
+    async fn test_all_endpoint_page_links_keep_filters() {
+        let (_, body) = get_json("/all?len=2&offset=4&limit=3&sort=value").await;
+        assert_eq!(body["total"], 6);
+        assert_eq!(body["total_combinations"], 2);
+        assert!(body.get("next").is_none());
+        assert_eq!(body["prev"], "/all?len=2&sort=value&limit=3&offset=1");
+
+        let (_, body) = get_json("/all?offset=20").await;
+        assert_eq!(body["total_combinations"], 0);
+        assert!(body.get("next").is_none());
+        assert_eq!(body["prev"], "/all?offset=0");
     }
 
     #[tokio::test]
-    async fn test_all_endpoint_returns_all_combinations() {
+    async fn test_all_endpoint_prev_past_the_end_is_the_last_page() {
+        let (_, body) = get_json("/all?limit=5&offset=100").await;
+        assert_eq!(body["total_combinations"], 0);
+        assert_eq!(body["prev"], "/all?limit=5&offset=11");
+
+        let (_, last) = get_json("/all?limit=5&offset=11").await;
+        assert_eq!(last["total_combinations"], 5);
+        assert!(last.get("next").is_none());
+
+        let (_, body) = get_json("/all?len=9&limit=5&offset=3").await;
+        assert_eq!(body["total"], 0);
+        assert_eq!(body["prev"], "/all?len=9&limit=5&offset=0");
+    }
+
+    #[tokio::test]
+    async fn test_all_endpoint_rejects_bad_paging() {
+        for (uri, message) in [
+            ("/all?limit=0", "limit must be at least 1"),
+            ("/all?limit=many", "invalid limit: many"),
+            ("/all?offset=-1", "invalid offset: -1"),
+        ] {
+            let (status, body) = get_json(uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+            assert_eq!(body["error"], message, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_all_endpoint_rejects_unknown_sort() {
         let app = create_router();
 
         let response = app
-            .oneshot(Request::builder().uri("/all").body(Body::empty()).unwrap())
+            .oneshot(
+                Request::builder()
+                    .uri("/all?sort=weight")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
             .await
             .unwrap();
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
         let body = body_to_json(response.into_body()).await;
-        assert_eq!(body["total_combinations"], 16);
-        assert!(body["combinations"].is_array());
+        assert!(body["error"].as_str().unwrap().contains("weight"));
+    }
 
-        let combinations = body["combinations"].as_array().unwrap();
-        assert_eq!(combinations.len(), 16);
+    #[tokio::test]
+    async fn test_combination_endpoint() {
+        let (status, body) = get_json("/combinations/5").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["index"], 5);
+        assert_eq!(body["coins"], serde_json::json!(["Penny", "Dime"]));
+        assert_eq!(body["value"], 11);
+        assert!(body.get("formatted_value").is_none());
+
+        // The same entry as in /all
+        let (_, all) = get_json("/all").await;
+        for index in [0, 9, 15] {
+            let (_, one) = get_json(&format!("/combinations/{index}")).await;
+            assert_eq!(one, all["combinations"][index], "{index}");
+        }
+    }
 
-        // Check first combination (empty set)
-        assert_eq!(combinations[0]["index"], 0);
-        assert_eq!(combinations[0]["value"], 0);
-        assert_eq!(combinations[0]["coins"].as_array().unwrap().len(), 0);
+    #[tokio::test]
+    async fn test_combination_endpoint_localized() {
+        let (status, body) = get_json("/combinations/8?lang=es").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["localized_coins"].as_array().unwrap().len(), 1);
 
-        // Check last combination (all coins)
-        assert_eq!(combinations[15]["index"], 15);
-        assert_eq!(combinations[15]["value"], 41);
-        assert_eq!(combinations[15]["coins"].as_array().unwrap().len(), 4);
+        let (status, _) = get_json("/combinations/8?lang=xx").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
     }
 
     #[tokio::test]
-    async fn test_all_endpoint_combination_values_are_correct() {
-        let app = create_router();
+    async fn test_combination_endpoint_out_of_range() {
+        for index in ["16", "99999999999"] {
+            let (status, body) = get_json(&format!("/combinations/{index}")).await;
+            assert_eq!(status, StatusCode::NOT_FOUND, "{index}");
+            assert!(
+                body["error"].as_str().unwrap().contains("expected 0 to 15"),
+                "{index}"
+            );
+        }
 
-        let response = app
-            .oneshot(Request::builder().uri("/all").body(Body::empty()).unwrap())
+        let (status, body) = get_json("/combinations/first").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_denominations_endpoint() {
+        let (status, body) = get_json("/denominations").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["currency"], "usd");
+        assert_eq!(body["symbol"], "$");
+        assert_eq!(body["minor_units"], 100);
+        let denominations = body["denominations"].as_array().unwrap();
+        assert_eq!(denominations.len(), Coin::COUNT);
+        assert_eq!(
+            denominations[2],
+            serde_json::json!({"name": "Dime", "symbol": "10¢", "value": 10, "mass_mg": 2268})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_denominations_endpoint_other_currencies() {
+        for currency in CurrencySystem::all() {
+            let (status, body) =
+                get_json(&format!("/denominations?currency={}", currency.code())).await;
+            assert_eq!(status, StatusCode::OK, "{currency}");
+            assert_eq!(body["name"], currency.name());
+            assert_eq!(
+                body["denominations"].as_array().unwrap().len(),
+                currency.denominations().len()
+            );
+        }
+
+        let (_, body) = get_json("/denominations?currency=JPY").await;
+        assert_eq!(body["minor_units"], 1);
+        assert_eq!(body["denominations"][5]["symbol"], "¥500");
+
+        let (status, body) = get_json("/denominations?currency=cad").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("unknown currency 'cad'"));
+    }
+
+    // ========================================================================
+    // Currency selection
+    // ========================================================================
+
+    async fn send_with_currency_header(
+        method: &str,
+        uri: &str,
+        currency: &str,
+        body: &'static str,
+    ) -> (StatusCode, serde_json::Value) {
+        let response = create_router()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(CURRENCY_HEADER, currency)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
             .await
             .unwrap();
+        let status = response.status();
+        (status, body_to_json(response.into_body()).await)
+    }
 
-        let body = body_to_json(response.into_body()).await;
+    #[tokio::test]
+    async fn test_all_endpoint_other_currency() {
+        let (status, body) = get_json("/all?currency=EUR").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["currency"], "eur");
+        assert_eq!(body["total"], 256);
+        assert_eq!(body["total_combinations"], 256);
+        assert_eq!(
+            body["combinations"][5],
+            serde_json::json!({
+                "index": 5,
+                "coins": ["1 cent", "5 cent"],
+                "value": 6,
+                "weight_grams": 6.22,
+                "formatted_value": "€0.06"
+            })
+        );
+        assert!(body["combinations"][0].get("mask").is_none());
+
+        let (status, body) = get_json("/all?currency=jpy&len=2&sort=value&limit=3&offset=3").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total"], 15);
+        let values: Vec<_> = body["combinations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["value"].as_u64().unwrap())
+            .collect();
+        assert_eq!(values, [51, 55, 60]);
+        assert_eq!(
+            body["next"],
+            "/all?currency=jpy&len=2&sort=value&limit=3&offset=6"
+        );
+        assert_eq!(
+            body["prev"],
+            "/all?currency=jpy&len=2&sort=value&limit=3&offset=0"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_all_endpoint_usd_only_parameters() {
+        for query in ["locale=en-US", "lang=fr", "contains=Dime"] {
+            let (status, body) = get_json(&format!("/all?currency=gbp&{query}")).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{query}");
+            assert!(body["error"]
+                .as_str()
+                .unwrap()
+                .ends_with("is only supported for usd, not gbp"));
+        }
+
+        let (status, _) = get_json("/all?currency=usd&contains=Dime").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_random_endpoint_other_currency() {
+        let (status, first) = get_json("/random?currency=jpy&seed=42").await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, second) = get_json("/random?currency=jpy&seed=42").await;
+        assert_eq!(first, second);
+        assert!(first["formatted_value"].as_str().unwrap().starts_with('¥'));
+        assert!(first["index"].as_u64().unwrap() < 64);
+
+        let (status, body) = get_json("/random?currency=eur&exact_coins=8").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["index"], 255);
+        assert_eq!(body["value"], 388);
+
+        let (status, body) = get_json("/random?currency=gbp&count=4&non_empty=true").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["currency"], "gbp");
+        assert_eq!(body["count"], 4);
         let combinations = body["combinations"].as_array().unwrap();
+        let total: u64 = combinations
+            .iter()
+            .map(|c| c["value"].as_u64().unwrap())
+            .sum();
+        assert_eq!(body["total_value"], total);
+        assert!(combinations.iter().all(|c| c["value"] != 0));
+    }
 
-        // Verify specific combinations
-        // Combination 1: Penny only (value = 1)
-        assert_eq!(combinations[1]["value"], 1);
+    #[tokio::test]
+    async fn test_random_endpoint_other_currency_rejects_bad_requests() {
+        let (status, body) = get_json("/random?currency=eur&exact_coins=9").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid exact_coins: 9 (expected 0 to 8)");
 
-        // Combination 2: Nickel only (value = 5)
-        assert_eq!(combinations[2]["value"], 5);
+        let (status, body) = get_json("/random?currency=jpy&min_value=2000").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(body["error"].as_str().unwrap().ends_with("minor units"));
 
-        // Combination 4: Dime only (value = 10)
-        assert_eq!(combinations[4]["value"], 10);
+        let (status, _) = get_json("/random?currency=jpy&lang=de").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
 
-        // Combination 8: Quarter only (value = 25)
-        assert_eq!(combinations[8]["value"], 25);
+    #[tokio::test]
+    async fn test_currency_header_fallback() {
+        let (status, body) = send_with_currency_header("GET", "/all", "eur", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total"], 256);
+
+        let (status, body) = send_with_currency_header("GET", "/random", "Jpy", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["formatted_value"].as_str().unwrap().starts_with('¥'));
+
+        let (status, body) =
+            send_with_currency_header("POST", "/change", "gbp", r#"{"amount": 3}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["currency"], "gbp");
+        assert_eq!(body["coins"], serde_json::json!(["1 penny", "2 pence"]));
+
+        let (_, body) = send_with_currency_header("GET", "/denominations", "jpy", "").await;
+        assert_eq!(body["currency"], "jpy");
+    }
 
-        // Combination 3: Penny + Nickel (value = 6)
-        assert_eq!(combinations[3]["value"], 6);
+    #[tokio::test]
+    async fn test_currency_query_and_body_override_header() {
+        let (_, body) = send_with_currency_header("GET", "/all?currency=usd", "eur", "").await;
+        assert_eq!(body["total"], 16);
+        assert!(body.get("currency").is_none());
+
+        let (_, body) =
+            send_with_currency_header("POST", "/change?currency=jpy", "eur", r#"{"amount": 5}"#)
+                .await;
+        assert_eq!(body["currency"], "jpy");
+
+        let (_, body) = send_with_currency_header(
+            "POST",
+            "/change?currency=jpy",
+            "eur",
+            r#"{"amount": 5, "currency": "usd"}"#,
+        )
+        .await;
+        assert_eq!(body["currency"], "usd");
+    }
 
-        // Combination 5: Penny + Dime (value = 11)
-        assert_eq!(combinations[5]["value"], 11);
+    #[tokio::test]
+    async fn test_unknown_currency_is_rejected() {
+        for uri in ["/all", "/random", "/denominations"] {
+            let (status, body) = get_json(&format!("{uri}?currency=cad")).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+            assert!(body["error"].as_str().unwrap().contains("cad"), "{uri}");
+
+            let (status, _) = send_with_currency_header("GET", uri, "doge", "").await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        }
+
+        let (status, _) = post_json("/change?currency=cad", r#"{"amount": 5}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) =
+            send_with_currency_header("POST", "/change", "doge", r#"{"amount": 5}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_schema_endpoint() {
+        let app = create_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/schema")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_to_json(response.into_body()).await;
+        assert_eq!(body, schema());
+        assert!(body["CombinationDetail"]["properties"]["weight_grams"].is_object());
     }
 
     #[tokio::test]
@@ -453,7 +3901,7 @@ mod tests {
 
     #[test]
     fn test_app_state_creation() {
-        let state = AppState {};
+        let state = AppState::new();
         let cloned = state.clone();
         // Verify Clone trait works
         assert_eq!(
@@ -528,20 +3976,14 @@ mod tests {
 
         let body = body_to_json(response.into_body()).await;
         let coins_array = body["coins"].as_array().unwrap();
-        let value = body["value"].as_u64().unwrap() as u32;
+        let value = body["value"].as_u64().unwrap();
 
         // Calculate expected value from coins
-        let mut expected_value = 0u32;
-        for coin in coins_array {
-            let coin_name = coin.as_str().unwrap();
-            expected_value += match coin_name {
-                "Penny" => 1,
-                "Nickel" => 5,
-                "Dime" => 10,
-                "Quarter" => 25,
-                _ => 0,
-            };
-        }
+        let expected_value: u64 = coins_array
+            .iter()
+            .map(|coin| Coin::try_from(coin.as_str().unwrap()).unwrap())
+            .map(|coin| coin.value_in_cents())
+            .sum();
 
         assert_eq!(
             value, expected_value,